@@ -2,7 +2,15 @@ use crate::model::{
     internal_game_state::InternalGameState,
     narrative_event::NarrativeEvent,
 };
+use crate::model::calendar::ScheduledEvent;
 use crate::model::event_result::EventApplyOutcome;
+use crate::model::game_state::{
+    CardEntry, FactionStance, NpcMission, NpcRoutine, Relationship, RelationshipNote,
+};
+use crate::model::internal_game_state::NPC_MISSION_SECTION;
+
+/// Relationship changes remembered per pair.
+const MAX_RELATIONSHIP_HISTORY: usize = 5;
 
 fn generate_unique_npc_id(state: &InternalGameState, name: &str) -> String {
     let mut base = String::new();
@@ -149,12 +157,113 @@ fn upsert_accepted_quests_from_tags(state: &mut InternalGameState, tags: &[Strin
                 rewards: Vec::new(),
                 sub_quests: Vec::new(),
                 rewards_claimed: false,
+                prerequisites: Vec::new(),
+                follow_ups: Vec::new(),
+                giver_npc: None,
+                giver_faction: None,
+                location: None,
+                deadline_minutes: None,
+                failure_conditions: Vec::new(),
+                negotiation: Default::default(),
             },
         );
     }
 }
 
-fn merge_strings(target: &mut Vec<String>, add: Option<Vec<String>>, remove: Option<Vec<String>>) {
+/// The `start_quest` event that begins an accepted offer.
+pub fn quest_offer_start_event(offer: crate::model::game_state::QuestOffer) -> NarrativeEvent {
+    let quest = offer.quest;
+    NarrativeEvent::StartQuest {
+        id: quest.id,
+        title: quest.title,
+        description: quest.description,
+        difficulty: quest.difficulty,
+        negotiable: Some(quest.negotiable),
+        reward_options: Some(quest.reward_options),
+        rewards: Some(quest.rewards),
+        sub_quests: Some(quest.sub_quests),
+        declinable: None,
+        prerequisites: Some(quest.prerequisites),
+        follow_ups: Some(quest.follow_ups),
+        giver_npc: quest.giver_npc,
+        giver_faction: quest.giver_faction,
+        location: quest.location,
+        deadline_minutes: offer.deadline_after_minutes,
+        failure_conditions: Some(quest.failure_conditions),
+    }
+}
+
+/// Faction reputation change once rewards are agreed: taking an offer as
+/// it stands earns goodwill, each counter-offer past the first costs some.
+fn bargaining_rep_delta(counter_offers: u32) -> i32 {
+    match counter_offers {
+        0 => 1,
+        n => -((n - 1).min(5) as i32) * 2,
+    }
+}
+
+/// Lock in the quest's current rewards and settle the giver faction's
+/// reputation for the bargaining.
+fn lock_quest_rewards(state: &mut InternalGameState, quest_id: &str) {
+    let Some(quest) = state.quests.get_mut(quest_id) else {
+        return;
+    };
+    quest.negotiation.locked = true;
+    quest.negotiation.counter_offer = None;
+    let delta = bargaining_rep_delta(quest.negotiation.counter_offers);
+    if let Some(faction) = quest.giver_faction.clone() {
+        change_faction_rep(state, &faction, delta);
+    }
+}
+
+/// Change a faction's reputation and ripple it out: its enemies move half as
+/// far the other way, its allies a quarter as far the same way.
+fn change_faction_rep(state: &mut InternalGameState, id: &str, delta: i32) {
+    let Some(faction) = state.factions.get_mut(id) else {
+        return;
+    };
+    faction.reputation += delta;
+    let (allies, enemies) = (faction.allies.clone(), faction.enemies.clone());
+    for (others, ripple) in [(enemies, -delta / 2), (allies, delta / 4)] {
+        if ripple == 0 {
+            continue;
+        }
+        for other in others.iter().filter(|other| other.as_str() != id) {
+            if let Some(other) = state.factions.get_mut(other) {
+                other.reputation += ripple;
+            }
+        }
+    }
+}
+
+/// Record `stance` between two factions on both sides.
+fn set_faction_stance(state: &mut InternalGameState, id: &str, other_id: &str, stance: FactionStance) {
+    for (this, other) in [(id, other_id), (other_id, id)] {
+        let Some(faction) = state.factions.get_mut(this) else {
+            continue;
+        };
+        faction.allies.retain(|f| f != other);
+        faction.enemies.retain(|f| f != other);
+        match stance {
+            FactionStance::Allied => faction.allies.push(other.to_string()),
+            FactionStance::Hostile => faction.enemies.push(other.to_string()),
+            FactionStance::Neutral => {}
+        }
+    }
+}
+
+fn clean_ids(ids: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for id in ids {
+        let trimmed = id.trim();
+        if !trimmed.is_empty() && !out.iter().any(|existing| existing == trimmed) {
+            out.push(trimmed.to_string());
+        }
+    }
+    out
+}
+
+pub(crate) fn merge_strings(target: &mut Vec<String>, add: Option<Vec<String>>, remove: Option<Vec<String>>) {
     if let Some(remove) = remove {
         if !remove.is_empty() {
             target.retain(|item| !remove.iter().any(|r| r.eq_ignore_ascii_case(item)));
@@ -173,22 +282,6 @@ fn merge_strings(target: &mut Vec<String>, add: Option<Vec<String>>, remove: Opt
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::merge_strings;
-
-    #[test]
-    fn merge_add_remove_case_insensitive() {
-        let mut items = vec!["Sword".to_string(), "Shield".to_string()];
-        merge_strings(
-            &mut items,
-            Some(vec!["sword".to_string(), "Bow".to_string()]),
-            Some(vec!["shield".to_string()]),
-        );
-        assert_eq!(items, vec!["Sword".to_string(), "Bow".to_string()]);
-    }
-}
-
 fn apply_exp_gain(state: &mut InternalGameState, amount: i32, multiplier: f32) {
     let mut exp = (state.player.exp + amount).max(0);
     let mut next = state.player.exp_to_next.max(1);
@@ -224,8 +317,8 @@ fn apply_level_ups(state: &mut InternalGameState, levels: u32, multiplier: f32,
     }
     state.player.exp_to_next = next;
 }
-/// Apply a NarrativeEvent to the InternalGameState, returning the outcome
 
+/// Apply a NarrativeEvent to the InternalGameState, returning the outcome
 pub fn apply_event(
     state: &mut InternalGameState,
     event: NarrativeEvent,
@@ -250,9 +343,16 @@ pub fn apply_event(
             EventApplyOutcome::Applied
         }
 
+        NarrativeEvent::Travel { from: _, to } => {
+            let to = to.trim();
+            if !to.is_empty() {
+                state.player_location = Some(to.to_string());
+                state.simulate_npcs();
+            }
+            EventApplyOutcome::Applied
+        }
         NarrativeEvent::Combat { .. }
         | NarrativeEvent::Dialogue { .. }
-        | NarrativeEvent::Travel { .. }
         | NarrativeEvent::Rest { .. } => {
             // Narrative-only events: recorded by the LLM but do not mutate state.
             EventApplyOutcome::Applied
@@ -400,6 +500,7 @@ pub fn apply_event(
                     role,
                     notes: details.unwrap_or_default(),
                     nearby: true,
+                    routine: NpcRoutine::default(),
                 },
             );
 
@@ -473,6 +574,14 @@ pub fn apply_event(
             name,
             role,
             details,
+            facts,
+            player_told,
+            promises,
+            promises_resolved,
+            location,
+            schedule,
+            goals,
+            goals_completed,
         } => {
             let id = match id {
                 Some(id) if !id.trim().is_empty() => id.trim().to_string(),
@@ -499,6 +608,7 @@ pub fn apply_event(
                     role: role.clone().unwrap_or_else(|| "Unknown".to_string()),
                     notes: String::new(),
                     nearby: true,
+                    routine: NpcRoutine::default(),
                 },
             );
             if let Some(name) = name {
@@ -522,7 +632,68 @@ pub fn apply_event(
                     entry.notes.push_str(trimmed);
                 }
             }
-            entry.nearby = true;
+            if let Some(location) = location {
+                let trimmed = location.trim();
+                if !trimmed.is_empty() {
+                    entry.routine.location = Some(trimmed.to_string());
+                    entry.routine.location_held_from = Some(state.world_time_minutes);
+                }
+            }
+            if let Some(schedule) = schedule {
+                entry.routine.schedule = schedule
+                    .into_iter()
+                    .filter(|slot| slot.hour < 24 && !slot.location.trim().is_empty())
+                    .collect();
+                entry.routine.schedule.sort_by_key(|slot| slot.hour);
+            }
+            merge_strings(&mut entry.routine.goals, goals, goals_completed);
+            entry.nearby = entry
+                .nearby_by_location(state.player_location.as_deref())
+                .unwrap_or(true);
+            let memory = state.npc_memories.entry(entry.id.clone()).or_default();
+            merge_strings(&mut memory.key_facts, facts, None);
+            merge_strings(&mut memory.player_told, player_told, None);
+            merge_strings(&mut memory.promises, promises, promises_resolved);
+            memory.last_interaction_minutes = Some(state.world_time_minutes);
+            EventApplyOutcome::Applied
+        }
+
+        NarrativeEvent::NpcMission { id, mission, destination, duration_minutes } => {
+            let now = state.world_time_minutes;
+            let Some(npc) = state.npcs.get_mut(&id) else {
+                return EventApplyOutcome::Deferred {
+                    reason: format!("NPC '{}' not found", id),
+                };
+            };
+            let destination = destination.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+            let mission = NpcMission {
+                description: mission.trim().to_string(),
+                destination: destination.clone(),
+                started_minutes: now,
+                return_minutes: now.saturating_add(u64::from(duration_minutes.max(1))),
+            };
+            let card = CardEntry {
+                id: id.clone(),
+                name: npc.name.clone(),
+                role: npc.role.clone(),
+                status: "Underway (0%)".to_string(),
+                details: mission.description.clone(),
+                notes: destination.map(|d| format!("Destination: {}", d)).unwrap_or_default(),
+                tags: Vec::new(),
+                items: Vec::new(),
+            };
+            npc.routine.location = mission.destination.clone();
+            npc.routine.activity = Some(mission.description.clone());
+            npc.routine.location_held_from = None;
+            npc.routine.mission = Some(mission);
+            npc.nearby = false;
+
+            let cards = state.sections.entry(NPC_MISSION_SECTION.to_string()).or_default();
+            match cards.iter_mut().find(|c| c.id == id) {
+                Some(existing) => *existing = card,
+                None => cards.push(card),
+            }
+            state.flags.insert(format!("unlock:{}", NPC_MISSION_SECTION));
             EventApplyOutcome::Applied
         }
 
@@ -551,22 +722,35 @@ pub fn apply_event(
                     role: member.role,
                     notes: String::new(),
                     nearby: true,
+                    routine: NpcRoutine {
+                        location: state.player_location.clone(),
+                        ..NpcRoutine::default()
+                    },
                 },
             );
 
             EventApplyOutcome::Applied
         }
 
-        NarrativeEvent::RelationshipChange { subject_id, target_id, delta } => {
-            let key = format!("{}::{}", subject_id, target_id);
-            let entry = state.relationships.entry(key.clone()).or_insert(
-                crate::model::game_state::Relationship {
-                    subject_id,
-                    target_id,
-                    value: 0,
-                },
-            );
+        NarrativeEvent::RelationshipChange { subject_id, target_id, delta, reason } => {
+            let now = state.world_time_minutes;
+            let key = Relationship::key(&subject_id, &target_id);
+            let entry = state.relationships.entry(key).or_insert(Relationship {
+                subject_id,
+                target_id,
+                value: 0,
+                history: Vec::new(),
+                decay_from_minutes: now,
+            });
             entry.value += delta;
+            entry.decay_from_minutes = now;
+            entry.history.push(RelationshipNote {
+                delta,
+                reason: reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+                at_minutes: now,
+            });
+            let excess = entry.history.len().saturating_sub(MAX_RELATIONSHIP_HISTORY);
+            entry.history.drain(..excess);
             EventApplyOutcome::Applied
         }
         NarrativeEvent::SectionCardUpsert {
@@ -627,6 +811,9 @@ pub fn apply_event(
                     items: items.unwrap_or_default(),
                 });
             }
+            if section == NPC_MISSION_SECTION {
+                state.adopt_mission_cards();
+            }
             EventApplyOutcome::Applied
         }
         NarrativeEvent::SectionCardRemove { section, id } => {
@@ -709,16 +896,114 @@ pub fn apply_event(
         NarrativeEvent::TimePassed { minutes, reason: _ } => {
             let add = minutes as u64;
             state.world_time_minutes = state.world_time_minutes.saturating_add(add);
+            state.expire_status_effects();
+            state.fail_expired_quests();
+            state.decay_relationships();
+            state.simulate_npcs();
+            EventApplyOutcome::Applied
+        }
+        NarrativeEvent::ScheduleEvent { id, name, description, in_minutes, repeat_minutes } => {
+            let id = id.trim().to_string();
+            if id.is_empty() {
+                return EventApplyOutcome::Rejected {
+                    reason: "Scheduled event id is empty".to_string(),
+                };
+            }
+            let event = ScheduledEvent {
+                id: id.clone(),
+                name,
+                description: description.unwrap_or_default(),
+                at_minutes: state.world_time_minutes.saturating_add(u64::from(in_minutes.max(1))),
+                repeat_minutes: repeat_minutes.filter(|r| *r > 0).map(u64::from),
+                effects: Vec::new(),
+            };
+            state.scheduled_events.insert(id, event);
             EventApplyOutcome::Applied
         }
+        NarrativeEvent::ApplyStatusEffect {
+            id,
+            name,
+            description,
+            modifiers,
+            duration_minutes,
+            duration_turns,
+        } => {
+            let key = id.trim().to_lowercase();
+            if key.is_empty() {
+                return EventApplyOutcome::Rejected {
+                    reason: "Status effect id is empty".to_string(),
+                };
+            }
+            let expires_at_minutes = duration_minutes
+                .filter(|m| *m > 0)
+                .map(|m| state.world_time_minutes.saturating_add(m as u64));
+            let remaining_turns = duration_turns.filter(|t| *t > 0);
+            let effect = state.status_effects.entry(key.clone()).or_insert(
+                crate::model::game_state::StatusEffect {
+                    id: key,
+                    name: String::new(),
+                    description: String::new(),
+                    modifiers: Default::default(),
+                    expires_at_minutes: None,
+                    remaining_turns: None,
+                },
+            );
+            // Re-applying an effect refreshes it rather than stacking.
+            if !name.trim().is_empty() {
+                effect.name = name.trim().to_string();
+            }
+            if let Some(description) = description {
+                effect.description = description.trim().to_string();
+            }
+            if let Some(modifiers) = modifiers {
+                effect.modifiers = modifiers
+                    .into_iter()
+                    .map(|(k, v)| (k.trim().to_lowercase(), v))
+                    .filter(|(k, v)| !k.is_empty() && *v != 0)
+                    .collect();
+            }
+            effect.expires_at_minutes = expires_at_minutes;
+            effect.remaining_turns = remaining_turns;
+            if effect.name.is_empty() {
+                effect.name = effect.id.clone();
+            }
+            EventApplyOutcome::Applied
+        }
+        NarrativeEvent::RemoveStatusEffect { id } => {
+            let key = id.trim().to_lowercase();
+            let removed = state.status_effects.remove(&key).is_some() || {
+                let by_name = state
+                    .status_effects
+                    .values()
+                    .find(|e| e.name.eq_ignore_ascii_case(id.trim()))
+                    .map(|e| e.id.clone());
+                by_name
+                    .and_then(|k| state.status_effects.remove(&k))
+                    .is_some()
+            };
+            if removed {
+                EventApplyOutcome::Applied
+            } else {
+                EventApplyOutcome::Deferred {
+                    reason: format!("Unknown status effect '{}'", id),
+                }
+            }
+        }
         NarrativeEvent::EquipItem {
             item_id,
             slot,
             set_id,
             description,
+            modifiers,
         } => {
             let key = item_id.clone();
             let slot_norm = slot.trim().to_lowercase();
+            let modifiers = modifiers
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(k, v)| (k.trim().to_lowercase(), v))
+                .filter(|(k, v)| !k.is_empty() && *v != 0)
+                .collect();
             state.equipment.insert(
                 key.clone(),
                 crate::model::game_state::EquippedItem {
@@ -726,6 +1011,7 @@ pub fn apply_event(
                     slot: slot_norm.clone(),
                     set_id,
                     description,
+                    modifiers,
                 },
             );
             if let Some(item) = state.inventory.get_mut(&key) {
@@ -736,20 +1022,20 @@ pub fn apply_event(
                 }
             }
             match slot_norm.as_str() {
-                "weapon" | "weapons" => {
-                    if !state.player.weapons.iter().any(|w| w.eq_ignore_ascii_case(&key)) {
-                        state.player.weapons.push(key);
-                    }
+                "weapon" | "weapons"
+                    if !state.player.weapons.iter().any(|w| w.eq_ignore_ascii_case(&key)) =>
+                {
+                    state.player.weapons.push(key);
                 }
-                "armor" | "armour" => {
-                    if !state.player.armor.iter().any(|a| a.eq_ignore_ascii_case(&key)) {
-                        state.player.armor.push(key);
-                    }
+                "armor" | "armour"
+                    if !state.player.armor.iter().any(|a| a.eq_ignore_ascii_case(&key)) =>
+                {
+                    state.player.armor.push(key);
                 }
-                "clothing" => {
-                    if !state.player.clothing.iter().any(|c| c.eq_ignore_ascii_case(&key)) {
-                        state.player.clothing.push(key);
-                    }
+                "clothing"
+                    if !state.player.clothing.iter().any(|c| c.eq_ignore_ascii_case(&key)) =>
+                {
+                    state.player.clothing.push(key);
                 }
                 _ => {}
             }
@@ -805,48 +1091,172 @@ pub fn apply_event(
             rewards,
             sub_quests,
             declinable: _,
+            prerequisites,
+            follow_ups,
+            giver_npc,
+            giver_faction,
+            location,
+            deadline_minutes,
+            failure_conditions,
         } => {
-            if let Some(existing_id) = find_quest_id_by_title(state, &title) {
-                if let Some(existing) = state.quests.get_mut(&existing_id) {
+            let deadline_minutes = deadline_minutes
+                .filter(|m| *m > 0)
+                .map(|m| state.world_time_minutes.saturating_add(m as u64));
+            let existing_id = if state.quests.contains_key(&id) {
+                Some(id.clone())
+            } else {
+                find_quest_id_by_title(state, &title)
+            };
+            let key = match existing_id {
+                Some(existing_id) => {
+                    let Some(existing) = state.quests.get_mut(&existing_id) else {
+                        return EventApplyOutcome::Rejected {
+                            reason: format!("Quest '{}' not found", existing_id),
+                        };
+                    };
+                    if existing.status == crate::model::game_state::QuestStatus::Completed {
+                        return EventApplyOutcome::Rejected {
+                            reason: format!("Quest '{}' is already completed", existing_id),
+                        };
+                    }
                     existing.title = title;
                     existing.description = description;
-                    existing.status = crate::model::game_state::QuestStatus::Active;
                     existing.difficulty = difficulty;
                     if let Some(neg) = negotiable {
                         existing.negotiable = neg;
                     }
-                    if let Some(options) = reward_options {
-                        existing.reward_options = options;
-                    }
-                    if let Some(rewards) = rewards {
-                        existing.rewards = rewards;
+                    if !existing.negotiation.locked {
+                        if let Some(options) = reward_options {
+                            existing.reward_options = options;
+                        }
+                        if let Some(rewards) = rewards {
+                            existing.rewards = rewards;
+                        }
                     }
                     if let Some(sub_quests) = sub_quests {
                         existing.sub_quests = sub_quests;
                     }
-                    return EventApplyOutcome::Applied;
+                    if let Some(prerequisites) = prerequisites {
+                        existing.prerequisites = clean_ids(prerequisites);
+                    }
+                    if let Some(follow_ups) = follow_ups {
+                        existing.follow_ups = clean_ids(follow_ups);
+                    }
+                    if giver_npc.is_some() {
+                        existing.giver_npc = giver_npc;
+                    }
+                    if giver_faction.is_some() {
+                        existing.giver_faction = giver_faction;
+                    }
+                    if location.is_some() {
+                        existing.location = location;
+                    }
+                    if deadline_minutes.is_some() {
+                        existing.deadline_minutes = deadline_minutes;
+                    }
+                    if let Some(conditions) = failure_conditions {
+                        existing.failure_conditions = conditions;
+                    }
+                    existing_id
                 }
+                None => {
+                    state.quests.insert(
+                        id.clone(),
+                        crate::model::game_state::Quest {
+                            id: id.clone(),
+                            title,
+                            description,
+                            status: crate::model::game_state::QuestStatus::Active,
+                            difficulty,
+                            negotiable: negotiable.unwrap_or(false),
+                            reward_options: reward_options.unwrap_or_default(),
+                            rewards: rewards.unwrap_or_default(),
+                            sub_quests: sub_quests.unwrap_or_default(),
+                            rewards_claimed: false,
+                            prerequisites: clean_ids(prerequisites.unwrap_or_default()),
+                            follow_ups: clean_ids(follow_ups.unwrap_or_default()),
+                            giver_npc,
+                            giver_faction,
+                            location,
+                            deadline_minutes,
+                            failure_conditions: failure_conditions.unwrap_or_default(),
+                            negotiation: Default::default(),
+                        },
+                    );
+                    id
+                }
+            };
+            // Quests in a chain wait until everything before them is done.
+            let status = if state.quest_prerequisites_met(&key) {
+                crate::model::game_state::QuestStatus::Active
+            } else {
+                crate::model::game_state::QuestStatus::Locked
+            };
+            if let Some(quest) = state.quests.get_mut(&key) {
+                quest.status = status;
             }
-            if state.quests.contains_key(&id) {
+            EventApplyOutcome::Applied
+        }
+        NarrativeEvent::OfferQuest {
+            id,
+            title,
+            description,
+            difficulty,
+            negotiable,
+            reward_options,
+            rewards,
+            sub_quests,
+            declinable,
+            prerequisites,
+            follow_ups,
+            giver_npc,
+            giver_faction,
+            location,
+            deadline_minutes,
+            failure_conditions,
+        } => {
+            let quest = crate::model::game_state::Quest {
+                id: id.trim().to_string(),
+                title: title.trim().to_string(),
+                description,
+                status: crate::model::game_state::QuestStatus::Active,
+                difficulty,
+                negotiable: negotiable.unwrap_or(false),
+                reward_options: reward_options.unwrap_or_default(),
+                rewards: rewards.unwrap_or_default(),
+                sub_quests: sub_quests.unwrap_or_default(),
+                rewards_claimed: false,
+                prerequisites: clean_ids(prerequisites.unwrap_or_default()),
+                follow_ups: clean_ids(follow_ups.unwrap_or_default()),
+                giver_npc: giver_npc.filter(|g| !g.trim().is_empty()),
+                giver_faction: giver_faction.filter(|g| !g.trim().is_empty()),
+                location,
+                deadline_minutes: None,
+                failure_conditions: failure_conditions.unwrap_or_default(),
+                negotiation: Default::default(),
+            };
+            let offer = crate::model::game_state::QuestOffer {
+                quest,
+                deadline_after_minutes: deadline_minutes,
+                turns_waiting: 0,
+            };
+            if offer.quest.id.is_empty() || offer.quest.title.is_empty() {
                 return EventApplyOutcome::Rejected {
-                    reason: format!("Quest '{}' already exists", id),
+                    reason: "Quest offer needs an id and a title".to_string(),
                 };
             }
-            state.quests.insert(
-                id.clone(),
-                crate::model::game_state::Quest {
-                    id,
-                    title,
-                    description,
-                    status: crate::model::game_state::QuestStatus::Active,
-                    difficulty,
-                    negotiable: negotiable.unwrap_or(false),
-                    reward_options: reward_options.unwrap_or_default(),
-                    rewards: rewards.unwrap_or_default(),
-                    sub_quests: sub_quests.unwrap_or_default(),
-                    rewards_claimed: false,
-                },
-            );
+            // Mandatory world quests skip the player's answer.
+            if declinable == Some(false) {
+                return apply_event(state, quest_offer_start_event(offer));
+            }
+            if state.quests.contains_key(&offer.quest.id)
+                || find_quest_id_by_title(state, &offer.quest.title).is_some()
+            {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("Quest '{}' was already started", offer.quest.title),
+                };
+            }
+            state.quest_offers.insert(offer.quest.id.clone(), offer);
             EventApplyOutcome::Applied
         }
         NarrativeEvent::UpdateQuest {
@@ -859,13 +1269,26 @@ pub fn apply_event(
             reward_options,
             rewards,
             sub_quests,
+            follow_ups,
+            location,
+            deadline_minutes,
+            failure_conditions,
         } => {
+            let now = state.world_time_minutes;
+            let unlockable = state.quest_prerequisites_met(&id);
             let Some(quest) = state.quests.get_mut(&id) else {
                 return EventApplyOutcome::Deferred {
                     reason: format!("Quest '{}' not found", id),
                 };
             };
-            let mut rewards_to_apply: Option<Vec<String>> = None;
+            if quest.status == crate::model::game_state::QuestStatus::Locked
+                && status == Some(crate::model::game_state::QuestStatus::Active)
+                && !unlockable
+            {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("Quest '{}' stays locked until its prerequisites are completed", id),
+                };
+            }
 
             if let Some(title) = title {
                 let t = title.trim();
@@ -888,11 +1311,26 @@ pub fn apply_event(
             if let Some(neg) = negotiable {
                 quest.negotiable = neg;
             }
-            if let Some(options) = reward_options {
-                quest.reward_options = options;
+            // Agreed rewards stay as bargained.
+            if !quest.negotiation.locked {
+                if let Some(options) = reward_options {
+                    quest.reward_options = options;
+                }
+                if let Some(rewards) = rewards {
+                    quest.rewards = rewards;
+                }
+            }
+            if let Some(follow_ups) = follow_ups {
+                quest.follow_ups = clean_ids(follow_ups);
+            }
+            if location.is_some() {
+                quest.location = location;
             }
-            if let Some(rewards) = rewards {
-                quest.rewards = rewards;
+            if let Some(minutes) = deadline_minutes.filter(|m| *m > 0) {
+                quest.deadline_minutes = Some(now.saturating_add(minutes as u64));
+            }
+            if let Some(conditions) = failure_conditions {
+                quest.failure_conditions = conditions;
             }
 
             if let Some(updates) = sub_quests {
@@ -908,6 +1346,9 @@ pub fn apply_event(
                         if let Some(completed) = update.completed {
                             existing.completed = completed;
                         }
+                        if let Some(optional) = update.optional {
+                            existing.optional = optional;
+                        }
                     } else {
                         let description = update
                             .description
@@ -917,26 +1358,112 @@ pub fn apply_event(
                             id: update.id,
                             description,
                             completed,
+                            optional: update.optional.unwrap_or(false),
                         });
                     }
                 }
             }
 
-            if quest.status == crate::model::game_state::QuestStatus::Completed
-                && !quest.rewards_claimed
-                && !quest.rewards.is_empty()
+            // Finishing every required objective completes the quest.
+            if quest.status == crate::model::game_state::QuestStatus::Active
+                && quest.sub_quests.iter().any(|s| !s.optional)
+                && quest.sub_quests.iter().filter(|s| !s.optional).all(|s| s.completed)
             {
-                rewards_to_apply = Some(quest.rewards.clone());
-                quest.rewards_claimed = true;
+                quest.status = crate::model::game_state::QuestStatus::Completed;
             }
 
-            if let Some(rewards) = rewards_to_apply {
-                apply_quest_rewards(state, &rewards);
+            if quest.status == crate::model::game_state::QuestStatus::Completed {
+                let rewards_to_apply = if !quest.rewards_claimed && !quest.rewards.is_empty() {
+                    quest.rewards_claimed = true;
+                    Some(quest.rewards.clone())
+                } else {
+                    None
+                };
+                if let Some(rewards) = rewards_to_apply {
+                    apply_quest_rewards(state, &rewards);
+                }
+                state.unlock_quests();
+            } else if quest.status == crate::model::game_state::QuestStatus::Failed {
+                state.fail_blocked_quests();
             }
 
             EventApplyOutcome::Applied
         }
 
+        NarrativeEvent::ChooseReward { quest_id, option } => {
+            let Some(quest) = state.quests.get_mut(&quest_id) else {
+                return EventApplyOutcome::Deferred {
+                    reason: format!("Quest '{}' not found", quest_id),
+                };
+            };
+            if quest.negotiation.locked {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("Rewards for quest '{}' are already agreed", quest_id),
+                };
+            }
+            let Some(option) = quest
+                .reward_options
+                .iter()
+                .find(|o| o.trim().eq_ignore_ascii_case(option.trim()))
+                .cloned()
+            else {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("'{}' is not a reward option of quest '{}'", option, quest_id),
+                };
+            };
+            quest.rewards = vec![option];
+            lock_quest_rewards(state, &quest_id);
+            EventApplyOutcome::Applied
+        }
+        NarrativeEvent::CounterOffer { quest_id, rewards } => {
+            let Some(quest) = state.quests.get_mut(&quest_id) else {
+                return EventApplyOutcome::Deferred {
+                    reason: format!("Quest '{}' not found", quest_id),
+                };
+            };
+            if quest.negotiation.locked {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("Rewards for quest '{}' are already agreed", quest_id),
+                };
+            }
+            if !quest.negotiable {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("Quest '{}' is not negotiable", quest_id),
+                };
+            }
+            let rewards: Vec<String> = rewards
+                .iter()
+                .map(|r| r.trim())
+                .filter(|r| !r.is_empty())
+                .map(str::to_string)
+                .collect();
+            if rewards.is_empty() {
+                return EventApplyOutcome::Rejected {
+                    reason: "Counter-offer names no rewards".to_string(),
+                };
+            }
+            quest.negotiation.counter_offer = Some(rewards);
+            quest.negotiation.counter_offers = quest.negotiation.counter_offers.saturating_add(1);
+            EventApplyOutcome::Applied
+        }
+        NarrativeEvent::SettleReward { quest_id, accepted } => {
+            let Some(quest) = state.quests.get_mut(&quest_id) else {
+                return EventApplyOutcome::Deferred {
+                    reason: format!("Quest '{}' not found", quest_id),
+                };
+            };
+            let Some(counter_offer) = quest.negotiation.counter_offer.take() else {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("No counter-offer is pending for quest '{}'", quest_id),
+                };
+            };
+            if accepted {
+                quest.rewards = counter_offer;
+                lock_quest_rewards(state, &quest_id);
+            }
+            EventApplyOutcome::Applied
+        }
+
         NarrativeEvent::SetFlag { flag } => {
             state.flags.insert(flag);
             EventApplyOutcome::Applied
@@ -982,8 +1509,9 @@ pub fn apply_event(
         }
 
         NarrativeEvent::CurrencyChange { currency, delta } => {
-            let entry = state.currencies.entry(currency).or_insert(0);
-            *entry += delta;
+            let key = state.currency_key(&currency);
+            let entry = state.currencies.entry(key).or_insert(0);
+            *entry = entry.saturating_add(delta);
             EventApplyOutcome::Applied
         }
         NarrativeEvent::FactionSpawn {
@@ -1005,6 +1533,11 @@ pub fn apply_event(
                     kind,
                     description,
                     reputation: 0,
+                    tier: None,
+                    member: false,
+                    rank: None,
+                    allies: Vec::new(),
+                    enemies: Vec::new(),
                 },
             );
             EventApplyOutcome::Applied
@@ -1041,16 +1574,40 @@ pub fn apply_event(
             EventApplyOutcome::Applied
         }
         NarrativeEvent::FactionRepChange { id, delta } => {
-            let entry = state.factions.entry(id.clone()).or_insert(
-                crate::model::game_state::FactionRep {
-                    id,
-                    name: "Unknown Faction".to_string(),
-                    kind: None,
-                    description: None,
-                    reputation: 0,
-                },
-            );
-            entry.reputation += delta;
+            if !state.factions.contains_key(&id) {
+                return EventApplyOutcome::Deferred {
+                    reason: format!("Faction '{}' not found; create it with faction_spawn first", id),
+                };
+            }
+            change_faction_rep(state, &id, delta);
+            EventApplyOutcome::Applied
+        }
+        NarrativeEvent::FactionStanceChange { id, other_id, stance } => {
+            if id == other_id {
+                return EventApplyOutcome::Rejected {
+                    reason: format!("Faction '{}' cannot take a stance toward itself", id),
+                };
+            }
+            if let Some(missing) = [&id, &other_id].into_iter().find(|f| !state.factions.contains_key(*f)) {
+                return EventApplyOutcome::Deferred {
+                    reason: format!("Faction '{}' not found", missing),
+                };
+            }
+            set_faction_stance(state, &id, &other_id, stance);
+            EventApplyOutcome::Applied
+        }
+        NarrativeEvent::FactionMembership { id, member, rank } => {
+            let Some(faction) = state.factions.get_mut(&id) else {
+                return EventApplyOutcome::Deferred {
+                    reason: format!("Faction '{}' not found", id),
+                };
+            };
+            faction.member = member;
+            if !member {
+                faction.rank = None;
+            } else if let Some(rank) = rank.filter(|r| !r.trim().is_empty()) {
+                faction.rank = Some(rank.trim().to_string());
+            }
             EventApplyOutcome::Applied
         }
 
@@ -1277,3 +1834,378 @@ fn looks_like_weapon(item: &str) -> bool {
     ];
     keywords.iter().any(|k| item.contains(k))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_event, merge_strings};
+    use crate::model::event_result::EventApplyOutcome;
+    use crate::model::game_state::{FactionStance, QuestStatus, QuestStep, ScheduleSlot};
+    use crate::model::internal_game_state::{InternalGameState, NPC_MISSION_SECTION};
+    use crate::model::narrative_event::{NarrativeEvent, QuestStepUpdate};
+
+    #[test]
+    fn merge_add_remove_case_insensitive() {
+        let mut items = vec!["Sword".to_string(), "Shield".to_string()];
+        merge_strings(
+            &mut items,
+            Some(vec!["sword".to_string(), "Bow".to_string()]),
+            Some(vec!["shield".to_string()]),
+        );
+        assert_eq!(items, vec!["Sword".to_string(), "Bow".to_string()]);
+    }
+
+    fn effective_strength(state: &InternalGameState) -> i32 {
+        state
+            .stat_breakdown()
+            .into_iter()
+            .find(|s| s.id == "strength")
+            .map(|s| s.total)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn status_effect_modifies_effective_stats_until_time_passes() {
+        let mut state = InternalGameState::default();
+        let event = NarrativeEvent::ApplyStatusEffect {
+            id: "Poisoned".to_string(),
+            name: "Poisoned".to_string(),
+            description: None,
+            modifiers: Some([("strength".to_string(), -3)].into_iter().collect()),
+            duration_minutes: Some(30),
+            duration_turns: None,
+        };
+        apply_event(&mut state, event);
+
+        assert_eq!(state.stats["strength"], 10);
+        assert_eq!(effective_strength(&state), 7);
+
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 20, reason: None });
+        assert!(state.status_effects.contains_key("poisoned"));
+
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 10, reason: None });
+        assert!(state.status_effects.is_empty());
+        assert_eq!(effective_strength(&state), 10);
+    }
+
+    fn start_quest(id: &str, prerequisites: &[&str], deadline_minutes: Option<u32>) -> NarrativeEvent {
+        NarrativeEvent::StartQuest {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: String::new(),
+            difficulty: None,
+            negotiable: None,
+            reward_options: None,
+            rewards: None,
+            sub_quests: Some(vec![QuestStep {
+                id: "s1".to_string(),
+                description: "Do it".to_string(),
+                completed: false,
+                optional: false,
+            }]),
+            declinable: None,
+            prerequisites: Some(prerequisites.iter().map(|p| p.to_string()).collect()),
+            follow_ups: None,
+            giver_npc: None,
+            giver_faction: None,
+            location: None,
+            deadline_minutes,
+            failure_conditions: None,
+        }
+    }
+
+    #[test]
+    fn quest_chains_unlock_on_completion_and_deadlines_fail_quests() {
+        let mut state = InternalGameState::default();
+        apply_event(&mut state, start_quest("first", &[], None));
+        apply_event(&mut state, start_quest("second", &["first"], None));
+        apply_event(&mut state, start_quest("timed", &[], Some(30)));
+        apply_event(&mut state, start_quest("after_timed", &["timed"], None));
+        assert_eq!(state.quests["second"].status, QuestStatus::Locked);
+
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 30, reason: None });
+        assert_eq!(state.quests["timed"].status, QuestStatus::Failed);
+        assert_eq!(state.quests["after_timed"].status, QuestStatus::Failed);
+        assert_eq!(state.quest_failures.len(), 2);
+        assert!(state.quest_failures[0].contains("deadline"));
+
+        let update = |id: &str, status, completed: bool| NarrativeEvent::UpdateQuest {
+            id: id.to_string(),
+            title: None,
+            description: None,
+            status,
+            difficulty: None,
+            negotiable: None,
+            reward_options: None,
+            rewards: None,
+            sub_quests: completed.then(|| {
+                vec![QuestStepUpdate {
+                    id: "s1".to_string(),
+                    description: None,
+                    completed: Some(true),
+                    optional: None,
+                }]
+            }),
+            follow_ups: None,
+            location: None,
+            deadline_minutes: None,
+            failure_conditions: None,
+        };
+        assert!(matches!(
+            apply_event(&mut state, update("second", Some(QuestStatus::Active), false)),
+            EventApplyOutcome::Rejected { .. }
+        ));
+        assert_eq!(state.quests["second"].status, QuestStatus::Locked);
+
+        apply_event(&mut state, update("first", None, true));
+        assert_eq!(state.quests["first"].status, QuestStatus::Completed);
+        assert_eq!(state.quests["second"].status, QuestStatus::Active);
+    }
+
+    #[test]
+    fn counter_offers_lock_rewards_and_cost_faction_reputation() {
+        let mut state = InternalGameState::default();
+        apply_event(
+            &mut state,
+            NarrativeEvent::FactionSpawn {
+                id: "guild".to_string(),
+                name: "Smiths' Guild".to_string(),
+                kind: None,
+                description: None,
+            },
+        );
+        apply_event(&mut state, start_quest("forge", &[], None));
+        let quest = state.quests.get_mut("forge").unwrap();
+        quest.negotiable = true;
+        quest.reward_options = vec!["Iron sword".to_string()];
+        quest.giver_faction = Some("guild".to_string());
+
+        for _ in 0..2 {
+            apply_event(
+                &mut state,
+                NarrativeEvent::CounterOffer {
+                    quest_id: "forge".to_string(),
+                    rewards: vec!["Steel sword".to_string()],
+                },
+            );
+        }
+        apply_event(
+            &mut state,
+            NarrativeEvent::SettleReward { quest_id: "forge".to_string(), accepted: true },
+        );
+
+        let quest = &state.quests["forge"];
+        assert!(quest.negotiation.locked);
+        assert_eq!(quest.rewards, vec!["Steel sword".to_string()]);
+        assert_eq!(state.factions["guild"].reputation, -2);
+        assert!(matches!(
+            apply_event(
+                &mut state,
+                NarrativeEvent::ChooseReward {
+                    quest_id: "forge".to_string(),
+                    option: "Iron sword".to_string(),
+                },
+            ),
+            EventApplyOutcome::Rejected { .. }
+        ));
+    }
+
+    #[test]
+    fn rep_ripples_to_enemies_and_tier_changes_are_reported() {
+        let mut state = InternalGameState::default();
+        for id in ["guard", "thieves", "merchants"] {
+            apply_event(
+                &mut state,
+                NarrativeEvent::FactionSpawn {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    kind: None,
+                    description: None,
+                },
+            );
+        }
+        let stance = |other: &str, stance| NarrativeEvent::FactionStanceChange {
+            id: "guard".to_string(),
+            other_id: other.to_string(),
+            stance,
+        };
+        apply_event(&mut state, stance("thieves", FactionStance::Hostile));
+        apply_event(&mut state, stance("merchants", FactionStance::Allied));
+        assert_eq!(state.factions["thieves"].enemies, vec!["guard".to_string()]);
+        assert!(state.update_faction_tiers().is_empty(), "first tiers are not reported");
+
+        let rep = |id: &str, delta| NarrativeEvent::FactionRepChange { id: id.to_string(), delta };
+        apply_event(&mut state, rep("guard", 12));
+        assert_eq!(state.factions["thieves"].reputation, -6);
+        assert_eq!(state.factions["merchants"].reputation, 3);
+        let changes = state.update_faction_tiers();
+        assert_eq!(changes.len(), 2);
+        assert_eq!((changes[0].faction_id.as_str(), changes[0].to.as_str()), ("guard", "Friendly"));
+        assert!(changes[0].improved);
+        assert_eq!((changes[1].faction_id.as_str(), changes[1].to.as_str()), ("thieves", "Unfriendly"));
+        assert!(!changes[1].improved);
+
+        assert!(matches!(apply_event(&mut state, rep("cult", 5)), EventApplyOutcome::Deferred { .. }));
+        assert!(!state.factions.contains_key("cult"));
+
+        apply_event(
+            &mut state,
+            NarrativeEvent::FactionMembership {
+                id: "guard".to_string(),
+                member: true,
+                rank: Some("Recruit".to_string()),
+            },
+        );
+        assert_eq!(state.factions["guard"].rank.as_deref(), Some("Recruit"));
+    }
+
+    #[test]
+    fn relationship_history_is_capped_and_value_decays_by_day() {
+        let mut state = InternalGameState::default();
+        for i in 0..7 {
+            apply_event(
+                &mut state,
+                NarrativeEvent::RelationshipChange {
+                    subject_id: "npc_mira".to_string(),
+                    target_id: "player".to_string(),
+                    delta: 5,
+                    reason: Some(format!("favour {}", i)),
+                },
+            );
+        }
+        let rel = &state.relationships["npc_mira::player"];
+        assert_eq!(rel.value, 35);
+        assert_eq!(rel.history.len(), 5);
+        assert_eq!(rel.history[0].reason.as_deref(), Some("favour 2"));
+
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 23 * 60, reason: None });
+        assert_eq!(state.relationships["npc_mira::player"].value, 35);
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 2 * 24 * 60, reason: None });
+        assert_eq!(state.relationships["npc_mira::player"].value, 33);
+    }
+
+    #[test]
+    fn scheduled_events_fall_due_once_and_repeats_move_on() {
+        let mut state = InternalGameState::default();
+        let schedule = |id: &str, repeat_minutes| NarrativeEvent::ScheduleEvent {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            in_minutes: 60,
+            repeat_minutes,
+        };
+        apply_event(&mut state, schedule("caravan", None));
+        apply_event(&mut state, schedule("curfew", Some(24 * 60)));
+        assert!(state.take_due_scheduled_events().is_empty());
+
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 90, reason: None });
+        let due: Vec<String> = state.take_due_scheduled_events().into_iter().map(|e| e.id).collect();
+        assert_eq!(due, vec!["caravan".to_string(), "curfew".to_string()]);
+        assert!(!state.scheduled_events.contains_key("caravan"));
+        assert_eq!(state.scheduled_events["curfew"].at_minutes, 60 + 24 * 60);
+
+        // A finished one-off world event is not scheduled again.
+        let caravan = crate::model::calendar::ScheduledEvent {
+            id: "caravan".to_string(),
+            name: "Caravan".to_string(),
+            description: String::new(),
+            at_minutes: 30,
+            repeat_minutes: None,
+            effects: Vec::new(),
+        };
+        state.sync_world_schedule(&[caravan]);
+        assert!(!state.scheduled_events.contains_key("caravan"));
+    }
+
+    #[test]
+    fn npcs_follow_schedules_and_missions_off_screen() {
+        let mut state = InternalGameState::default();
+        apply_event(
+            &mut state,
+            NarrativeEvent::NpcSpawn {
+                id: Some("mira".to_string()),
+                name: "Mira".to_string(),
+                role: "Smith".to_string(),
+                details: None,
+            },
+        );
+        let slot = |hour, location: &str| ScheduleSlot {
+            hour,
+            location: location.to_string(),
+            activity: None,
+        };
+        let schedule = vec![slot(20, "Tavern"), slot(6, "Forge")];
+        let npc_update = |location: Option<&str>| NarrativeEvent::NpcUpdate {
+            id: Some("mira".to_string()),
+            name: None,
+            role: None,
+            details: None,
+            facts: None,
+            player_told: None,
+            promises: None,
+            promises_resolved: None,
+            location: location.map(str::to_string),
+            schedule: location.is_none().then(|| schedule.clone()),
+            goals: Some(vec!["Repay her debt".to_string()]),
+            goals_completed: None,
+        };
+        apply_event(&mut state, npc_update(None));
+        apply_event(
+            &mut state,
+            NarrativeEvent::Travel { from: "Road".to_string(), to: "Tavern".to_string() },
+        );
+        // Starts at 08:00, so she is at the forge
+        assert_eq!(state.npcs["mira"].routine.location.as_deref(), Some("Forge"));
+        assert!(!state.npcs["mira"].nearby);
+
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 12 * 60, reason: None });
+        assert_eq!(state.npcs["mira"].routine.location.as_deref(), Some("Tavern"));
+        assert!(state.npcs["mira"].nearby);
+
+        apply_event(
+            &mut state,
+            NarrativeEvent::NpcMission {
+                id: "mira".to_string(),
+                mission: "Fetch ore".to_string(),
+                destination: Some("Mine".to_string()),
+                duration_minutes: 120,
+            },
+        );
+        assert!(!state.npcs["mira"].nearby);
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 60, reason: None });
+        assert_eq!(state.sections[NPC_MISSION_SECTION][0].status, "Underway (50%)");
+
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 60, reason: None });
+        let mira = &state.npcs["mira"];
+        assert!(mira.routine.mission.is_none());
+        assert_eq!(mira.routine.location.as_deref(), Some("Tavern"));
+        assert!(mira.nearby);
+        assert!(state.sections[NPC_MISSION_SECTION].is_empty(), "returned cards are dropped");
+
+        // A location set by hand holds until the next slot begins (06:00)
+        apply_event(&mut state, npc_update(Some("Market")));
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 60, reason: None });
+        assert_eq!(state.npcs["mira"].routine.location.as_deref(), Some("Market"));
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 8 * 60, reason: None });
+        assert_eq!(state.npcs["mira"].routine.location.as_deref(), Some("Forge"));
+
+        // Cards the narrator adds are matched to NPCs by name
+        apply_event(
+            &mut state,
+            NarrativeEvent::SectionCardUpsert {
+                section: NPC_MISSION_SECTION.to_string(),
+                id: "scouting".to_string(),
+                name: "Mira".to_string(),
+                role: None,
+                status: None,
+                details: Some("Scouting the pass".to_string()),
+                notes: None,
+                tags: None,
+                items: None,
+            },
+        );
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 6 * 60, reason: None });
+        assert_eq!(state.sections[NPC_MISSION_SECTION][0].status, "Underway (25%)");
+        apply_event(&mut state, NarrativeEvent::TimePassed { minutes: 18 * 60, reason: None });
+        assert!(state.sections[NPC_MISSION_SECTION].is_empty());
+    }
+}
@@ -3,10 +3,11 @@ use std::time::{Duration, Instant};
 use std::thread;
 use std::collections::HashSet;
 
-use crate::engine::apply_event::apply_event;
+use crate::engine::apply_event::{apply_event, merge_strings, quest_offer_start_event};
+use crate::engine::event_validator::EventValidators;
 use crate::engine::protocol::{EngineCommand, EngineResponse};
-use crate::engine::prompt_builder::PromptBuilder;
-use crate::engine::llm_client::{abort_generation, call_llm, call_llm_events_structured, test_connection};
+use crate::engine::prompt_builder::{BuiltPrompt, PromptBuilder};
+use crate::engine::llm_client::{abort_generation, call_llm, AbortSignal, AbortToken, ChatMessage, call_llm_events_repair, call_llm_events_structured, test_connection, LlmConfig, LlmRole};
 use crate::engine::narrative_parser::parse_narrative;
 
 use crate::model::event_result::{
@@ -14,8 +15,9 @@ use crate::model::event_result::{
     EventApplication,
     EventApplyOutcome,
 };
+use crate::model::calendar::{format_elapsed, FiredWorldEvent};
 use crate::model::internal_game_state::InternalGameState;
-use crate::model::game_state::LootDrop;
+use crate::model::game_state::{LootDrop, NpcExchange, ReputationTier, StoryChapter};
 use crate::model::message::Message;
 use crate::model::narrative_event::NarrativeEvent;
 use crate::model::game_save::GameSave;
@@ -32,18 +34,25 @@ pub struct Engine {
     debug_messages_enabled: bool,
     npc_recency_limit: usize,
     turn_index: u64,
-    last_quest_offer_source: Option<QuestOfferSource>,
-    last_quest_offer_turn: Option<u64>,
     pending_generation: Option<PendingGeneration>,
-}
-
-const SAVE_VERSION: u32 = 4;
-
-#[derive(Clone, Copy, Debug)]
-enum QuestOfferSource {
-    World,
-    Npc,
-}
+    story_summary_interval: u32,
+    pending_summary: Option<PendingSummary>,
+    /// Hold LLM events for approval unless the world auto-approves them
+    review_events: bool,
+    pending_review: Option<PendingReview>,
+    /// Fired by the UI's Stop button
+    abort: AbortSignal,
+}
+
+const SAVE_VERSION: u32 = 6;
+/// Newest messages never folded into a story chapter.
+const STORY_SUMMARY_KEEP_RECENT: usize = 20;
+/// Skip summarizing until at least this many story messages are waiting.
+const STORY_SUMMARY_MIN_MESSAGES: usize = 6;
+/// How many times invalid EVENTS are sent back to the LLM for fixing.
+const MAX_EVENT_REPAIR_ATTEMPTS: usize = 2;
+/// ENGINE FEEDBACK lines carried into the next prompt.
+const MAX_ENGINE_FEEDBACK_LINES: usize = 12;
 
 struct PendingGeneration {
     messages_start: usize,
@@ -51,14 +60,93 @@ struct PendingGeneration {
     context: crate::model::game_context::GameContext,
     llm: crate::engine::llm_client::LlmConfig,
     total_start: Instant,
+    prompt_tokens: usize,
     response_rx: Receiver<anyhow::Result<String>>,
     canceled: bool,
+    /// Aborts the narration call and the follow-up calls of this turn
+    abort: AbortToken,
+}
+
+/// LLM events waiting for the player's approval.
+struct PendingReview {
+    context: crate::model::game_context::GameContext,
+    /// The turn's validators, which check approved events before they apply
+    validators: EventValidators,
+}
+
+/// What `finish_turn` applied and what still waits for review.
+struct FinishedTurn {
+    applications: Vec<EventApplication>,
+    awaiting_review: Vec<NarrativeEvent>,
+    /// Validators with the turn's running totals, for the reviewed events
+    validators: EventValidators,
+    /// Anything the UI should hear about besides proximity
+    changed: bool,
+}
+
+/// Per-turn checks an LLM event must pass before it is applied or held
+/// for review. `EventValidators` run after review, right before applying.
+struct TurnChecks<'a> {
+    text: &'a str,
+    world: &'a crate::ui::app::WorldDefinition,
+}
+
+impl TurnChecks<'_> {
+    /// The event to apply (party updates come back sanitized), or `None`
+    /// after recording why it was held back in `applications`.
+    fn screen(
+        &self,
+        event: NarrativeEvent,
+        state: &InternalGameState,
+        applications: &mut Vec<EventApplication>,
+    ) -> Option<NarrativeEvent> {
+        // Quests only start once the player accepts, so a plain start_quest
+        // becomes an offer.
+        let event = if is_new_declinable_quest(&event, state) {
+            event.into_quest_offer()
+        } else {
+            event
+        };
+        if let Some(reason) = validate_quest_offer(&event, self.world) {
+            applications.push(EventApplication {
+                event,
+                outcome: EventApplyOutcome::Rejected { reason },
+            });
+            return None;
+        }
+        if let NarrativeEvent::PartyUpdate { .. } = event {
+            if !player_requested_party_details(self.text) {
+                applications.push(EventApplication {
+                    event,
+                    outcome: EventApplyOutcome::Deferred {
+                        reason: "Party update ignored: player did not request details.".to_string(),
+                    },
+                });
+                return None;
+            }
+            return Some(sanitize_party_update(&event));
+        }
+        Some(event)
+    }
+}
+
+struct PendingSummary {
+    target: SummaryTarget,
+    response_rx: Receiver<anyhow::Result<String>>,
+}
+
+/// What a background summary call condenses.
+enum SummaryTarget {
+    Story { from_message: usize, to_message: usize },
+    /// Earlier exchanges being folded into an NPC's memory lists
+    NpcMemory { id: String, exchanges: Vec<NpcExchange> },
 }
 
 impl Engine {
     pub fn new(
         rx: Receiver<EngineCommand>,
         tx: Sender<EngineResponse>,
+        abort: AbortSignal,
     ) -> Self {
         Self {
             rx,
@@ -69,9 +157,12 @@ impl Engine {
             debug_messages_enabled: true,
             npc_recency_limit: 10,
             turn_index: 0,
-            last_quest_offer_source: None,
-            last_quest_offer_turn: None,
             pending_generation: None,
+            story_summary_interval: 10,
+            pending_summary: None,
+            review_events: false,
+            pending_review: None,
+            abort,
         }
     }
 
@@ -85,6 +176,16 @@ impl Engine {
         }
     }
 
+    /// Surface prompt trimming and template failures as debug lines.
+    fn note_prompt(&mut self, prompt: &BuiltPrompt) {
+        if let Some(report) = prompt.trim_report() {
+            self.push_debug_message(report);
+        }
+        for warning in &prompt.warnings {
+            self.push_debug_message(warning.clone());
+        }
+    }
+
     fn trim_messages_after_last_user(&mut self) -> Option<String> {
         let mut idx = self.messages.len();
         while idx > 0 {
@@ -107,6 +208,15 @@ impl Engine {
         Some(last_user)
     }
 
+    /// Fill the context's history from the engine log, ending at `end` and
+    /// capped at `history_limit` messages. The token budget trims it further
+    /// when the prompt is built.
+    fn attach_history(&self, context: &mut crate::model::game_context::GameContext, end: usize) {
+        let start = context.history_limit.map_or(0, |limit| end.saturating_sub(limit));
+        context.history = self.messages[start..end].to_vec();
+        context.history_start = start;
+    }
+
     fn update_npc_proximity_from_recent_messages(&mut self, limit: usize) -> bool {
         use std::collections::HashSet;
 
@@ -121,17 +231,19 @@ impl Engine {
                 text,
             } = msg
             {
-                let name = text.splitn(2, ':').next().unwrap_or("").trim();
+                let name = text.split(':').next().unwrap_or("").trim();
                 if !name.is_empty() {
                     active_names.insert(name.to_lowercase());
                 }
             }
         }
 
+        let player_location = self.game_state.player_location.clone();
         let mut changed = false;
         for npc in self.game_state.npcs.values_mut() {
-            let name_key = npc.name.to_lowercase();
-            let should_be_nearby = active_names.contains(&name_key);
+            let should_be_nearby = npc
+                .nearby_by_location(player_location.as_deref())
+                .unwrap_or_else(|| active_names.contains(&npc.name.to_lowercase()));
             if npc.nearby != should_be_nearby {
                 npc.nearby = should_be_nearby;
                 changed = true;
@@ -170,6 +282,148 @@ impl Engine {
         }
     }
 
+    /// Lenient first decode, used to spot `request_context`. Parse errors
+    /// are reported only when no structured pass follows.
+    fn decode_raw_events(&mut self, events_json: &str, use_structured_events: bool) -> Vec<NarrativeEvent> {
+        match crate::model::llm_decode::decode_llm_events(events_json) {
+            Ok(events) => events,
+            Err(_) if use_structured_events => Vec::new(),
+            Err(err) => {
+                self.push_debug_message(format!("Failed to parse EVENTS: {}", err));
+                self.send_ui_error(format!("Failed to parse EVENTS: {}", err));
+                Vec::new()
+            }
+        }
+    }
+
+    /// The turn's events: re-extracted by the events backend when structured
+    /// EVENTS are on, then decoded with repair.
+    fn decode_turn_events(
+        &mut self,
+        narrative: &str,
+        events_json: &str,
+        llm: &LlmConfig,
+        abort: &AbortToken,
+    ) -> Vec<NarrativeEvent> {
+        let structured_events_json = if llm.use_structured_events
+            && !abort.is_aborted()
+            && !events_json.trim().is_empty()
+            && events_json.trim() != "[]"
+        {
+            let raw_types = Self::extract_event_types(events_json);
+            match call_llm_events_structured(narrative, events_json, &llm.for_role(LlmRole::Events)) {
+                Ok(json) => {
+                    let structured_types = Self::extract_event_types(&json);
+                    if Self::should_accept_structured_events(raw_types, structured_types) {
+                        Some(json)
+                    } else {
+                        let warning = "Structured EVENTS added new event types; using raw EVENTS instead.";
+                        self.push_debug_message(warning.to_string());
+                        self.send_ui_error(warning.to_string());
+                        None
+                    }
+                }
+                Err(err) => {
+                    let warning =
+                        format!("Structured EVENTS failed, using raw EVENTS: {}", err);
+                    self.push_debug_message(warning.clone());
+                    self.send_ui_error(warning);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let events_json = structured_events_json.as_deref().unwrap_or(events_json);
+        self.decode_events_with_repair(events_json, llm, abort)
+    }
+
+    /// End a turn the player stopped after the narration came back.
+    fn stop_turn(&mut self, messages_start: usize) {
+        self.messages.push(Message::System("Generation stopped.".to_string()));
+        self.send_new_messages_since(messages_start);
+    }
+
+    /// Decode EVENTS, asking the events backend to fix them when they fail
+    /// to parse or an item maps to no event. A repair is used only if it
+    /// decodes cleanly and adds no new event types; otherwise the original
+    /// is decoded leniently (unmappable items become `Unknown`). Repairs
+    /// stop early once `abort` fires.
+    fn decode_events_with_repair(
+        &mut self,
+        events_json: &str,
+        llm: &LlmConfig,
+        abort: &AbortToken,
+    ) -> Vec<NarrativeEvent> {
+        let original_types = Self::extract_event_types(events_json);
+        let mut json = events_json.to_string();
+        for attempt in 0..=MAX_EVENT_REPAIR_ATTEMPTS {
+            let errors = match crate::model::llm_decode::decode_llm_events_strict(&json) {
+                Ok(events) if attempt == 0 => return events,
+                Ok(events) => {
+                    let repaired_types = Self::extract_event_types(&json).unwrap_or_default();
+                    match &original_types {
+                        Some(original) if !repaired_types.is_subset(original) => {
+                            vec!["Repair added event types that were not in the original EVENTS".to_string()]
+                        }
+                        _ => {
+                            self.push_debug_message(format!(
+                                "EVENTS repair attempt {} succeeded.",
+                                attempt
+                            ));
+                            return events;
+                        }
+                    }
+                }
+                Err(errors) => errors,
+            };
+            if abort.is_aborted() {
+                break;
+            }
+            if attempt == MAX_EVENT_REPAIR_ATTEMPTS {
+                self.push_debug_message(format!(
+                    "EVENTS still invalid after {} repair attempts: {}",
+                    attempt,
+                    errors.join("; ")
+                ));
+                break;
+            }
+
+            self.push_debug_message(format!(
+                "EVENTS repair attempt {}/{}: {}",
+                attempt + 1,
+                MAX_EVENT_REPAIR_ATTEMPTS,
+                errors.join("; ")
+            ));
+            match call_llm_events_repair(&json, &errors, &llm.for_role(LlmRole::Events)) {
+                Ok(repaired) => json = repaired,
+                Err(err) => {
+                    self.push_debug_message(format!("EVENTS repair request failed: {}", err));
+                    break;
+                }
+            }
+        }
+
+        match crate::model::llm_decode::decode_llm_events(events_json) {
+            Ok(events) => events,
+            Err(err) => {
+                self.push_debug_message(format!("Failed to parse EVENTS: {}", err));
+                self.send_ui_error(format!("Failed to parse EVENTS: {}", err));
+                Vec::new()
+            }
+        }
+    }
+
+    /// Keep this turn's held-back events for the next prompt. Returns true
+    /// when the feedback changed, so the UI snapshot needs refreshing.
+    fn record_engine_feedback(&mut self, applications: &[EventApplication]) -> bool {
+        let feedback = engine_feedback(applications);
+        let changed = feedback != self.game_state.engine_feedback;
+        self.game_state.engine_feedback = feedback;
+        changed
+    }
+
     fn split_llm_output(llm_output: &str) -> (&str, &str) {
         if let Some((narrative, events)) = llm_output.split_once("EVENTS:") {
             return (narrative, events);
@@ -188,7 +442,8 @@ impl Engine {
 pub fn run(&mut self) {
     loop {
         let mut cmd_opt: Option<EngineCommand> = None;
-        if self.pending_generation.is_some() {
+        let polling = self.pending_generation.is_some() || self.pending_summary.is_some();
+        if polling {
             match self.rx.try_recv() {
                 Ok(cmd) => cmd_opt = Some(cmd),
                 Err(TryRecvError::Disconnected) => break,
@@ -201,7 +456,10 @@ pub fn run(&mut self) {
                 match pending.response_rx.try_recv() {
                     Ok(result) => {
                         let pending = self.pending_generation.take().expect("pending generation");
+                        let llm = pending.llm.clone();
                         self.handle_llm_result(pending, result);
+                        self.maybe_start_story_summary(&llm);
+                        self.maybe_start_npc_memory_summary(&llm);
                         continue;
                     }
                     Err(TryRecvError::Empty) => {}
@@ -215,11 +473,30 @@ pub fn run(&mut self) {
                     }
                 }
             }
+
+            if let Some(pending) = &self.pending_summary {
+                match pending.response_rx.try_recv() {
+                    Ok(result) => {
+                        let pending = self.pending_summary.take().expect("pending summary");
+                        self.handle_summary(pending, result);
+                        continue;
+                    }
+                    Err(TryRecvError::Empty) => {}
+                    Err(TryRecvError::Disconnected) => {
+                        let pending = self.pending_summary.take().expect("pending summary");
+                        self.handle_summary(
+                            pending,
+                            Err(anyhow::anyhow!("summary thread disconnected")),
+                        );
+                        continue;
+                    }
+                }
+            }
         }
 
         let cmd = if let Some(cmd) = cmd_opt {
             Some(cmd)
-        } else if self.pending_generation.is_some() {
+        } else if polling {
             match self.rx.recv_timeout(Duration::from_millis(50)) {
                 Ok(cmd) => Some(cmd),
                 Err(RecvTimeoutError::Timeout) => None,
@@ -246,8 +523,8 @@ pub fn run(&mut self) {
                 self.messages.clear();
                 self.game_state = InternalGameState::default();
                 self.turn_index = 0;
-                self.last_quest_offer_source = None;
-                self.last_quest_offer_turn = None;
+                self.pending_summary = None;
+                self.pending_review = None;
 
                 // Inject narrator opening
                 self.messages.push(Message::Roleplay {
@@ -269,16 +546,33 @@ pub fn run(&mut self) {
                     self.send_ui_error("Generation already in progress.".to_string());
                     continue;
                 }
+                if self.pending_review.is_some() {
+                    self.send_ui_error("Approve or reject the pending events first.".to_string());
+                    continue;
+                }
                 self.turn_index = self.turn_index.saturating_add(1);
                 let total_start = Instant::now();
                 let messages_start = self.messages.len();
                 self.game_state.player.exp_multiplier = context.world.exp_multiplier.max(1.0);
+                self.game_state.reputation_tiers = context.world.reputation_tiers.clone();
+                self.game_state.relationship_tiers = context.world.relationship_tiers.clone();
+                self.game_state.relationship_decay_per_day = context.world.relationship_decay_per_day;
+                self.game_state.start_hour = context.world.calendar.start.hour;
+                self.game_state.sync_world_schedule(&context.world.scheduled_events);
                 sync_stats_from_context(&mut self.game_state, &context);
+                self.game_state.tick_status_effect_turns();
                 update_action_counts(&mut self.game_state, &text);
                 update_power_usage(&mut self.game_state, &text);
                 // 1. Record player input
                 self.messages.push(Message::User(text.clone()));
 
+                // 1a. Withdraw stale quest offers and take the reply as an answer
+                let mut context = context;
+                self.attach_history(&mut context, messages_start);
+                if self.update_quest_offers(&text, context.world.quest_offer_turns) {
+                    context.snapshot = Some((&self.game_state).into());
+                }
+
                 // 1b. Handle explicit pickup commands without the LLM
                 if is_pickup_intent(&text) {
                     if is_pickup_all_command(&text) {
@@ -295,7 +589,7 @@ pub fn run(&mut self) {
                             "Added all loot to inventory.".to_string(),
                         ));
 
-                        let report = NarrativeApplyReport { applications };
+                        let report = self.report(applications);
                         let snapshot = (&self.game_state).into();
                         let _ = self.tx.send(
                             EngineResponse::NarrativeApplied {
@@ -319,7 +613,7 @@ pub fn run(&mut self) {
                         };
                         self.messages.push(Message::System(summary));
 
-                        let report = NarrativeApplyReport { applications };
+                        let report = self.report(applications);
                         let snapshot = (&self.game_state).into();
                         let _ = self.tx.send(
                             EngineResponse::NarrativeApplied {
@@ -334,12 +628,20 @@ pub fn run(&mut self) {
 
                 // 2. Build prompt
                 let prompt = PromptBuilder::build(&context, &text);
+                self.note_prompt(&prompt);
+                let prompt_tokens = prompt.estimated_tokens;
 
                 // 3. Call LM Studio asynchronously
                 let (resp_tx, resp_rx) = std::sync::mpsc::channel();
-                let llm_clone = llm.clone();
+                let llm_clone = llm.for_role(LlmRole::Narrator);
+                let abort = self.abort.token();
+                let call_abort = abort.clone();
                 thread::spawn(move || {
-                    let result = call_llm(prompt, &llm_clone);
+                    let result = call_llm(
+                        prompt.chat_messages(llm_clone.sends_chat_messages()),
+                        &llm_clone,
+                        &call_abort,
+                    );
                     let _ = resp_tx.send(result);
                 });
 
@@ -349,8 +651,10 @@ pub fn run(&mut self) {
                     context,
                     llm,
                     total_start,
+                    prompt_tokens,
                     response_rx: resp_rx,
                     canceled: false,
+                    abort,
                 });
             }
 
@@ -359,6 +663,10 @@ pub fn run(&mut self) {
                     self.send_ui_error("Generation already in progress.".to_string());
                     continue;
                 }
+                if self.pending_review.is_some() {
+                    self.send_ui_error("Approve or reject the pending events first.".to_string());
+                    continue;
+                }
 
                 let Some(last_user) = self.trim_messages_after_last_user() else {
                     self.send_ui_error("No user message to regenerate.".to_string());
@@ -373,15 +681,30 @@ pub fn run(&mut self) {
 
                 let total_start = Instant::now();
                 let messages_start = self.messages.len();
+                let mut context = context;
+                self.attach_history(&mut context, messages_start);
                 self.game_state.player.exp_multiplier = context.world.exp_multiplier.max(1.0);
+                self.game_state.reputation_tiers = context.world.reputation_tiers.clone();
+                self.game_state.relationship_tiers = context.world.relationship_tiers.clone();
+                self.game_state.relationship_decay_per_day = context.world.relationship_decay_per_day;
+                self.game_state.start_hour = context.world.calendar.start.hour;
+                self.game_state.sync_world_schedule(&context.world.scheduled_events);
                 sync_stats_from_context(&mut self.game_state, &context);
 
                 let prompt = PromptBuilder::build(&context, &text);
+                self.note_prompt(&prompt);
+                let prompt_tokens = prompt.estimated_tokens;
 
                 let (resp_tx, resp_rx) = std::sync::mpsc::channel();
-                let llm_clone = llm.clone();
+                let llm_clone = llm.for_role(LlmRole::Narrator);
+                let abort = self.abort.token();
+                let call_abort = abort.clone();
                 thread::spawn(move || {
-                    let result = call_llm(prompt, &llm_clone);
+                    let result = call_llm(
+                        prompt.chat_messages(llm_clone.sends_chat_messages()),
+                        &llm_clone,
+                        &call_abort,
+                    );
                     let _ = resp_tx.send(result);
                 });
 
@@ -391,8 +714,10 @@ pub fn run(&mut self) {
                     context,
                     llm,
                     total_start,
+                    prompt_tokens,
                     response_rx: resp_rx,
                     canceled: false,
+                    abort,
                 });
             }
 
@@ -401,7 +726,7 @@ pub fn run(&mut self) {
                ========================= */
             EngineCommand::StopGeneration => {
                 if let Some(mut pending) = self.pending_generation.take() {
-                    let llm = pending.llm.clone();
+                    let llm = pending.llm.for_role(LlmRole::Narrator);
                     if !pending.canceled {
                         pending.canceled = true;
                         self.messages.push(Message::System("Generation stopped.".to_string()));
@@ -414,15 +739,16 @@ pub fn run(&mut self) {
             }
 
             /* =========================
-               Connect to LM Studio
+               Connect to LLM backend
                ========================= */
             EngineCommand::ConnectToLlm { llm } => {
                 match test_connection(&llm) {
-                    Ok(msg) => {
+                    Ok(status) => {
                         let _ = self.tx.send(
                             EngineResponse::LlmConnectionResult {
                                 success: true,
-                                message: msg,
+                                message: status.message,
+                                models: status.models,
                             }
                         );
                     }
@@ -431,6 +757,7 @@ pub fn run(&mut self) {
                             EngineResponse::LlmConnectionResult {
                                 success: false,
                                 message: format!("Connection failed: {}", e),
+                                models: Vec::new(),
                             }
                         );
                     }
@@ -452,9 +779,7 @@ pub fn run(&mut self) {
                 };
 
                 let outcome = apply_event(&mut self.game_state, event.clone());
-                let report = NarrativeApplyReport {
-                    applications: vec![EventApplication { event, outcome }],
-                };
+                let report = self.report(vec![EventApplication { event, outcome }]);
                 let snapshot = (&self.game_state).into();
 
                 let _ = self.tx.send(
@@ -479,9 +804,7 @@ pub fn run(&mut self) {
                 };
 
                 let outcome = apply_event(&mut self.game_state, event.clone());
-                let report = NarrativeApplyReport {
-                    applications: vec![EventApplication { event, outcome }],
-                };
+                let report = self.report(vec![EventApplication { event, outcome }]);
                 let snapshot = (&self.game_state).into();
 
                 let _ = self.tx.send(
@@ -512,9 +835,7 @@ pub fn run(&mut self) {
                     member.armor = armor;
                     member.clothing = clothing;
                 }
-                let report = NarrativeApplyReport {
-                    applications: vec![EventApplication { event, outcome }],
-                };
+                let report = self.report(vec![EventApplication { event, outcome }]);
                 let snapshot = (&self.game_state).into();
                 let _ = self.tx.send(EngineResponse::NarrativeApplied { report, snapshot });
             }
@@ -546,9 +867,7 @@ pub fn run(&mut self) {
                         armor_remove: Some(armor_remove),
                     };
                     let outcome = apply_event(&mut self.game_state, event.clone());
-                    let report = NarrativeApplyReport {
-                        applications: vec![EventApplication { event, outcome }],
-                    };
+                    let report = self.report(vec![EventApplication { event, outcome }]);
                     let snapshot = (&self.game_state).into();
                     let _ = self.tx.send(EngineResponse::NarrativeApplied { report, snapshot });
                 }
@@ -556,12 +875,29 @@ pub fn run(&mut self) {
 
             EngineCommand::RemovePartyMember { id } => {
                 if self.game_state.party.remove(&id).is_some() {
-                    let report = NarrativeApplyReport { applications: Vec::new() };
+                    let report = self.report(Vec::new());
                     let snapshot = (&self.game_state).into();
                     let _ = self.tx.send(EngineResponse::NarrativeApplied { report, snapshot });
                 }
             }
 
+            EngineCommand::AnswerQuestOffer { quest_id, accept } => {
+                let messages_start = self.messages.len();
+                let applications = self.answer_quest_offer(&quest_id, accept);
+                let report = self.report(applications);
+                let snapshot = (&self.game_state).into();
+                let _ = self.tx.send(EngineResponse::NarrativeApplied { report, snapshot });
+                self.send_new_messages_since(messages_start);
+            }
+
+            EngineCommand::PickQuestReward { quest_id, option } => {
+                self.apply_player_event(NarrativeEvent::ChooseReward { quest_id, option });
+            }
+
+            EngineCommand::CounterQuestReward { quest_id, rewards } => {
+                self.apply_player_event(NarrativeEvent::CounterOffer { quest_id, rewards });
+            }
+
             EngineCommand::SetPartyMemberLocks {
                 id,
                 lock_name,
@@ -593,6 +929,18 @@ pub fn run(&mut self) {
                 self.npc_recency_limit = limit.max(1);
             }
 
+            EngineCommand::SetStorySummaryInterval { turns } => {
+                self.story_summary_interval = turns;
+            }
+
+            EngineCommand::SetEventReview { enabled } => {
+                self.review_events = enabled;
+            }
+
+            EngineCommand::ResolveEventReview { approved, rejected } => {
+                self.resolve_event_review(approved, rejected);
+            }
+
             /* =========================
                Save / Load Game
                ========================= */
@@ -657,6 +1005,8 @@ pub fn run(&mut self) {
                         migrate_save(&mut save);
                         self.messages = save.messages.clone();
                         self.game_state = save.internal_state.clone();
+                        self.pending_summary = None;
+                        self.pending_review = None;
                         let snapshot = (&self.game_state).into();
 
                         let _ = self.tx.send(
@@ -679,6 +1029,7 @@ pub fn run(&mut self) {
     }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn emit_timing(
         &mut self,
         tag: &str,
@@ -688,7 +1039,8 @@ pub fn run(&mut self) {
         narrative_done: Instant,
         apply_done: Instant,
         snapshot_done: Instant,
-        followup: Option<(Instant, Instant, Instant)>,
+        prompt_tokens: usize,
+        followup: Option<(Instant, Instant, Instant, usize)>,
     ) {
         if !self.timing_enabled {
             return;
@@ -702,11 +1054,13 @@ pub fn run(&mut self) {
         let snapshot_ms = snapshot_done.duration_since(apply_done).as_secs_f64() * 1000.0;
 
         let mut msg = format!(
-            "[timing:{}] total={:.2}ms split={:.2}ms parse={:.2}ms narrative={:.2}ms apply={:.2}ms snapshot={:.2}ms",
-            tag, total_ms, split_ms, parse_ms, narrative_ms, apply_ms, snapshot_ms
+            "[timing:{}] total={:.2}ms split={:.2}ms parse={:.2}ms narrative={:.2}ms apply={:.2}ms snapshot={:.2}ms prompt_tokens~{}",
+            tag, total_ms, split_ms, parse_ms, narrative_ms, apply_ms, snapshot_ms, prompt_tokens
         );
 
-        if let Some((followup_start, followup_split_done, followup_parse_done)) = followup {
+        if let Some((followup_start, followup_split_done, followup_parse_done, followup_tokens)) =
+            followup
+        {
             let followup_total = followup_start.elapsed().as_secs_f64() * 1000.0;
             let followup_split =
                 followup_split_done.duration_since(followup_start).as_secs_f64() * 1000.0;
@@ -715,8 +1069,8 @@ pub fn run(&mut self) {
                 .as_secs_f64()
                 * 1000.0;
             msg.push_str(&format!(
-                " followup_total={:.2}ms followup_split={:.2}ms followup_parse={:.2}ms",
-                followup_total, followup_split, followup_parse
+                " followup_total={:.2}ms followup_split={:.2}ms followup_parse={:.2}ms followup_prompt_tokens~{}",
+                followup_total, followup_split, followup_parse, followup_tokens
             ));
         }
 
@@ -731,6 +1085,11 @@ pub fn run(&mut self) {
         if pending.canceled {
             return;
         }
+        if pending.abort.is_aborted() {
+            // Stopped before the StopGeneration command got here.
+            self.stop_turn(pending.messages_start);
+            return;
+        }
 
         let PendingGeneration {
             messages_start,
@@ -738,6 +1097,8 @@ pub fn run(&mut self) {
             context,
             llm,
             total_start,
+            prompt_tokens,
+            abort,
             ..
         } = pending;
 
@@ -754,70 +1115,19 @@ pub fn run(&mut self) {
             }
         };
 
-        let current_turn = self.turn_index;
-
         // 4. Split NARRATIVE vs EVENTS
         let (narrative, events_json) = Self::split_llm_output(&llm_output);
         let split_done = Instant::now();
 
-        let use_structured_events =
-            llm.use_structured_events && matches!(llm.api_mode, crate::engine::llm_client::LlmApiMode::OpenAiChat);
-
         // 5. Decode EVENTS JSON (raw) for request_context detection
-        let raw_events = if use_structured_events {
-            crate::model::llm_decode::decode_llm_events(events_json).unwrap_or_default()
-        } else {
-            match crate::model::llm_decode::decode_llm_events(events_json) {
-                Ok(events) => events,
-                Err(err) => {
-                    self.push_debug_message(format!("Failed to parse EVENTS: {}", err));
-                    self.send_ui_error(format!("Failed to parse EVENTS: {}", err));
-                    Vec::new()
-                }
-            }
-        };
+        let raw_events = self.decode_raw_events(events_json, llm.use_structured_events);
         let parse_done = Instant::now();
 
-        let structured_events_json = if use_structured_events {
-            let raw_types = Self::extract_event_types(events_json);
-            if events_json.trim().is_empty() || events_json.trim() == "[]" {
-                None
-            } else {
-                match call_llm_events_structured(narrative, events_json, &llm) {
-                    Ok(json) => {
-                        let structured_types = Self::extract_event_types(&json);
-                        if Self::should_accept_structured_events(raw_types, structured_types) {
-                            Some(json)
-                        } else {
-                            let warning = "Structured EVENTS added new event types; using raw EVENTS instead.";
-                            self.push_debug_message(warning.to_string());
-                            self.send_ui_error(warning.to_string());
-                            None
-                        }
-                    }
-                    Err(err) => {
-                        let warning =
-                            format!("Structured EVENTS failed, using raw EVENTS: {}", err);
-                        self.push_debug_message(warning.clone());
-                        self.send_ui_error(warning);
-                        None
-                    }
-                }
-            }
-        } else {
-            None
-        };
-
-        let events_json = structured_events_json.as_deref().unwrap_or(events_json);
-
-        let events = match crate::model::llm_decode::decode_llm_events(events_json) {
-            Ok(events) => events,
-            Err(err) => {
-                self.push_debug_message(format!("Failed to parse EVENTS: {}", err));
-                self.send_ui_error(format!("Failed to parse EVENTS: {}", err));
-                Vec::new()
-            }
-        };
+        let events = self.decode_turn_events(narrative, events_json, &llm, &abort);
+        if abort.is_aborted() {
+            self.stop_turn(messages_start);
+            return;
+        }
 
         // 6. Handle request_context (one additional round)
         if let Some(topics) = collect_requested_topics(&raw_events) {
@@ -834,8 +1144,23 @@ pub fn run(&mut self) {
                 &requested_context,
                 &recent_history,
             );
-            let llm_output = match call_llm(followup_prompt, &llm) {
+            self.note_prompt(&followup_prompt);
+            let followup_prompt_tokens = followup_prompt.estimated_tokens;
+            let followup_llm = llm.for_role(LlmRole::Followup);
+            if abort.is_aborted() {
+                self.stop_turn(messages_start);
+                return;
+            }
+            let llm_output = match call_llm(
+                followup_prompt.chat_messages(followup_llm.sends_chat_messages()),
+                &followup_llm,
+                &abort,
+            ) {
                 Ok(text) => text,
+                Err(_) if abort.is_aborted() => {
+                    self.stop_turn(messages_start);
+                    return;
+                }
                 Err(e) => {
                     self.messages.push(Message::System(format!(
                         "LLM error: {}",
@@ -849,61 +1174,15 @@ pub fn run(&mut self) {
 
             let (narrative, events_json) = Self::split_llm_output(&llm_output);
             let followup_split_done = Instant::now();
-            let raw_events = if use_structured_events {
-                crate::model::llm_decode::decode_llm_events(events_json).unwrap_or_default()
-            } else {
-                match crate::model::llm_decode::decode_llm_events(events_json) {
-                    Ok(events) => events,
-                    Err(err) => {
-                        self.push_debug_message(format!("Failed to parse EVENTS: {}", err));
-                        self.send_ui_error(format!("Failed to parse EVENTS: {}", err));
-                        Vec::new()
-                    }
-                }
-            };
+            let raw_events = self.decode_raw_events(events_json, llm.use_structured_events);
             let followup_parse_done = Instant::now();
 
-            let structured_events_json = if use_structured_events {
-                let raw_types = Self::extract_event_types(events_json);
-                if events_json.trim().is_empty() || events_json.trim() == "[]" {
-                    None
-                } else {
-                    match call_llm_events_structured(narrative, events_json, &llm) {
-                        Ok(json) => {
-                            let structured_types = Self::extract_event_types(&json);
-                            if Self::should_accept_structured_events(raw_types, structured_types) {
-                                Some(json)
-                            } else {
-                                let warning = "Structured EVENTS added new event types; using raw EVENTS instead.";
-                                self.push_debug_message(warning.to_string());
-                                self.send_ui_error(warning.to_string());
-                                None
-                            }
-                        }
-                        Err(err) => {
-                            let warning =
-                                format!("Structured EVENTS failed, using raw EVENTS: {}", err);
-                            self.push_debug_message(warning.clone());
-                            self.send_ui_error(warning);
-                            None
-                        }
-                    }
-                }
-            } else {
-                None
-            };
-
-            let events_json = structured_events_json.as_deref().unwrap_or(events_json);
-            let events = match crate::model::llm_decode::decode_llm_events(events_json) {
-                Ok(events) => events,
-                Err(err) => {
-                    self.push_debug_message(format!("Failed to parse EVENTS: {}", err));
-                    self.send_ui_error(format!("Failed to parse EVENTS: {}", err));
-                    Vec::new()
-                }
-            };
+            let events = self.decode_turn_events(narrative, events_json, &llm, &abort);
+            if abort.is_aborted() {
+                self.stop_turn(messages_start);
+                return;
+            }
 
-            let start_level = self.game_state.player.level;
             let had_redundant_context = raw_events
                 .iter()
                 .any(|e| matches!(e, NarrativeEvent::RequestContext { .. }));
@@ -919,85 +1198,16 @@ pub fn run(&mut self) {
                 .collect();
 
             let new_messages = parse_narrative(narrative);
-            self.messages.extend(new_messages);
+            self.messages.extend(new_messages.iter().cloned());
             let proximity_changed =
                 self.update_npc_proximity_from_recent_messages(self.npc_recency_limit);
             let narrative_done = Instant::now();
 
-            let mut applications = Vec::new();
-            let offer_source = quest_offer_source(narrative);
-            if let Some(source) = offer_source {
-                self.last_quest_offer_source = Some(source);
-                self.last_quest_offer_turn = Some(current_turn);
-            }
-            let player_accepts = player_accepts_quest(&text);
-            let mut effective_offer_source = offer_source;
-            if effective_offer_source.is_none() && player_accepts {
-                if let (Some(source), Some(turn)) =
-                    (self.last_quest_offer_source, self.last_quest_offer_turn)
-                {
-                    if turn + 1 == current_turn {
-                        effective_offer_source = Some(source);
-                    }
-                }
-            }
-            for event in events {
-                if let NarrativeEvent::StartQuest { .. } = event {
-                    if let Some(reason) =
-                        validate_start_quest(
-                            &event,
-                            effective_offer_source,
-                            player_accepts,
-                            &context.world,
-                        )
-                    {
-                        applications.push(EventApplication {
-                            event,
-                            outcome: EventApplyOutcome::Deferred { reason },
-                        });
-                        continue;
-                    }
-                }
-                if let NarrativeEvent::PartyUpdate { .. } = event {
-                    if !player_requested_party_details(&text) {
-                        applications.push(EventApplication {
-                            event,
-                            outcome: EventApplyOutcome::Deferred {
-                                reason: "Party update ignored: player did not request details.".to_string(),
-                            },
-                        });
-                        continue;
-                    }
-                    let sanitized = sanitize_party_update(&event);
-                    let outcome = apply_event(&mut self.game_state, sanitized.clone());
-                    applications.push(EventApplication {
-                        event: sanitized,
-                        outcome,
-                    });
-                    continue;
-                }
-                let outcome = apply_event(&mut self.game_state, event.clone());
-                applications.push(EventApplication { event, outcome });
-            }
-
-            maybe_grant_repetition_power(
-                &mut self.game_state,
-                &text,
-                &context.world,
-                &mut applications,
-            );
-            maybe_evolve_powers(&mut self.game_state, &context.world, &mut applications);
-            apply_set_bonuses(&mut self.game_state, &mut applications);
-            apply_level_stat_growth(
-                &mut self.game_state,
-                &context,
-                start_level,
-                &mut applications,
-            );
+            let turn = self.finish_turn(events, &text, &context, &new_messages);
             let apply_done = Instant::now();
 
-            if !applications.is_empty() || proximity_changed {
-                let report = NarrativeApplyReport { applications };
+            if turn.changed || proximity_changed {
+                let report = self.report(turn.applications);
                 let snapshot = (&self.game_state).into();
                 let _ = self.tx.send(
                     EngineResponse::NarrativeApplied { report, snapshot }
@@ -1011,7 +1221,13 @@ pub fn run(&mut self) {
                     narrative_done,
                     apply_done,
                     snapshot_done,
-                    Some((followup_start, followup_split_done, followup_parse_done)),
+                    prompt_tokens,
+                    Some((
+                        followup_start,
+                        followup_split_done,
+                        followup_parse_done,
+                        followup_prompt_tokens,
+                    )),
                 );
             } else {
                 self.emit_timing(
@@ -1022,136 +1238,438 @@ pub fn run(&mut self) {
                     narrative_done,
                     apply_done,
                     Instant::now(),
-                    Some((followup_start, followup_split_done, followup_parse_done)),
+                    prompt_tokens,
+                    Some((
+                        followup_start,
+                        followup_split_done,
+                        followup_parse_done,
+                        followup_prompt_tokens,
+                    )),
                 );
             }
 
             self.send_new_messages_since(messages_start);
+            self.start_event_review(turn.awaiting_review, turn.validators, context);
             return;
         }
 
         // 7. Parse narrative into structured messages
             let new_messages = parse_narrative(narrative);
-            self.messages.extend(new_messages);
+            self.messages.extend(new_messages.iter().cloned());
             let proximity_changed =
                 self.update_npc_proximity_from_recent_messages(self.npc_recency_limit);
             let narrative_done = Instant::now();
 
         // 8. Apply events
-        let mut applications = Vec::new();
-        let offer_source = quest_offer_source(narrative);
-        if let Some(source) = offer_source {
-            self.last_quest_offer_source = Some(source);
-            self.last_quest_offer_turn = Some(current_turn);
-        }
-        let player_accepts = player_accepts_quest(&text);
-        let mut effective_offer_source = offer_source;
-        if effective_offer_source.is_none() && player_accepts {
-            if let (Some(source), Some(turn)) =
-                (self.last_quest_offer_source, self.last_quest_offer_turn)
-            {
-                if turn + 1 == current_turn {
-                    effective_offer_source = Some(source);
+        let turn = self.finish_turn(events, &text, &context, &new_messages);
+        let apply_done = Instant::now();
+
+        // 9. Send state mutation report
+        if turn.changed || proximity_changed {
+            let report = self.report(turn.applications);
+            let snapshot = (&self.game_state).into();
+
+            let _ = self.tx.send(
+                EngineResponse::NarrativeApplied {
+                    report,
+                    snapshot,
                 }
-            }
+            );
+            let snapshot_done = Instant::now();
+            self.emit_timing(
+                "primary",
+                total_start,
+                split_done,
+                parse_done,
+                narrative_done,
+                apply_done,
+                snapshot_done,
+                prompt_tokens,
+                None,
+            );
+        } else {
+            self.emit_timing(
+                "primary",
+                total_start,
+                split_done,
+                parse_done,
+                narrative_done,
+                apply_done,
+                Instant::now(),
+                prompt_tokens,
+                None,
+            );
         }
-        let start_level = self.game_state.player.level;
 
+        // 10. Update UI with full history
+        self.send_new_messages_since(messages_start);
+        self.start_event_review(turn.awaiting_review, turn.validators, context);
+    }
+
+    /// Screen and apply one turn's LLM events, then run every post-apply
+    /// pass. Events that need the player's approval are returned unapplied.
+    fn finish_turn(
+        &mut self,
+        events: Vec<NarrativeEvent>,
+        text: &str,
+        context: &crate::model::game_context::GameContext,
+        new_messages: &[Message],
+    ) -> FinishedTurn {
+        let start_level = self.game_state.player.level;
+        let announced = !std::mem::take(&mut self.game_state.fired_world_events).is_empty();
+        let mut applications = Vec::new();
+        let checks = TurnChecks {
+            text,
+            world: &context.world,
+        };
+        let mut validators = EventValidators::for_world(&context.world);
+        let mut awaiting_review = Vec::new();
         for event in events {
-            if let NarrativeEvent::StartQuest { .. } = event {
-                if let Some(reason) =
-                    validate_start_quest(
-                        &event,
-                        effective_offer_source,
-                        player_accepts,
-                        &context.world,
-                    )
-                {
-                    applications.push(EventApplication {
-                        event,
-                        outcome: EventApplyOutcome::Deferred { reason },
-                    });
-                    continue;
-                }
+            let Some(event) = checks.screen(event, &self.game_state, &mut applications) else {
+                continue;
+            };
+            if self.needs_review(&event, &context.world) {
+                awaiting_review.push(event);
+                continue;
             }
-            if let NarrativeEvent::PartyUpdate { .. } = event {
-                if !player_requested_party_details(&text) {
-                    applications.push(EventApplication {
-                        event,
-                        outcome: EventApplyOutcome::Deferred {
-                            reason: "Party update ignored: player did not request details.".to_string(),
-                        },
-                    });
-                    continue;
-                }
-                let sanitized = sanitize_party_update(&event);
-                let outcome = apply_event(&mut self.game_state, sanitized.clone());
-                applications.push(EventApplication {
-                    event: sanitized,
-                    outcome,
-                });
+            if let Err(outcome) = validators.validate(&event, &self.game_state) {
+                applications.push(EventApplication { event, outcome });
                 continue;
             }
             let outcome = apply_event(&mut self.game_state, event.clone());
+            applications.push(EventApplication { event, outcome });
+        }
+
+        maybe_grant_repetition_power(&mut self.game_state, text, &context.world, &mut applications);
+        maybe_evolve_powers(&mut self.game_state, &context.world, &mut applications);
+        self.settle_applied(context, start_level, &mut applications);
+        record_npc_exchanges(&mut self.game_state, text, new_messages);
+        let feedback_changed = self.record_engine_feedback(&applications);
+
+        FinishedTurn {
+            changed: !applications.is_empty() || feedback_changed || announced,
+            applications,
+            awaiting_review,
+            validators,
+        }
+    }
+
+    /// Passes that follow any batch of applied events: set bonuses, due
+    /// world events and level-up stat growth.
+    fn settle_applied(
+        &mut self,
+        context: &crate::model::game_context::GameContext,
+        start_level: u32,
+        applications: &mut Vec<EventApplication>,
+    ) {
+        apply_set_bonuses(&mut self.game_state, applications);
+        self.fire_scheduled_events(applications);
+        apply_level_stat_growth(&mut self.game_state, context, start_level);
+    }
+
+    /// Wrap a batch of applications, noting any reputation tier changes.
+    fn report(&mut self, applications: Vec<EventApplication>) -> NarrativeApplyReport {
+        let tier_changes = self.game_state.update_faction_tiers();
+        // The narrator learns about quests the engine failed next turn.
+        let quest_failures = std::mem::take(&mut self.game_state.quest_failures);
+        add_engine_feedback(&mut self.game_state.engine_feedback, quest_failures.iter().cloned());
+        NarrativeApplyReport { applications, tier_changes, quest_failures }
+    }
+
+    /// Age pending quest offers and apply the player's reply to one, if the
+    /// reply is a clear answer. Returns whether the state changed.
+    fn update_quest_offers(&mut self, text: &str, max_turns: u32) -> bool {
+        let withdrawn = self.game_state.tick_quest_offers(max_turns);
+        for offer in &withdrawn {
+            self.messages
+                .push(Message::System(format!("Quest offer withdrawn: {}", offer.label())));
+        }
+        let Some(accept) = classify_offer_reply(text) else {
+            return !withdrawn.is_empty();
+        };
+        let Some(quest_id) = self.offer_answered_by(text) else {
+            return !withdrawn.is_empty();
+        };
+        let applications = self.answer_quest_offer(&quest_id, accept);
+        let report = self.report(applications);
+        let snapshot = (&self.game_state).into();
+        let _ = self.tx.send(EngineResponse::NarrativeApplied { report, snapshot });
+        true
+    }
+
+    /// The only pending offer, or the one whose title the reply names.
+    fn offer_answered_by(&self, text: &str) -> Option<String> {
+        let offers = &self.game_state.quest_offers;
+        if offers.len() == 1 {
+            return offers.keys().next().cloned();
+        }
+        let reply = normalize_phrase(text);
+        let mut named = offers
+            .values()
+            .filter(|o| reply.contains(&normalize_phrase(&o.quest.title)));
+        match (named.next(), named.next()) {
+            (Some(offer), None) => Some(offer.quest.id.clone()),
+            _ => None,
+        }
+    }
+
+    /// Start or drop a pending quest offer and note it in the chat.
+    fn answer_quest_offer(&mut self, quest_id: &str, accept: bool) -> Vec<EventApplication> {
+        let Some(offer) = self.game_state.quest_offers.remove(quest_id) else {
+            self.send_ui_error(format!("No pending quest offer '{}'.", quest_id));
+            return Vec::new();
+        };
+        if !accept {
+            self.messages
+                .push(Message::System(format!("Quest declined: {}", offer.label())));
+            return Vec::new();
+        }
+        self.messages
+            .push(Message::System(format!("Quest accepted: {}", offer.label())));
+        let event = quest_offer_start_event(offer);
+        let outcome = apply_event(&mut self.game_state, event.clone());
+        vec![EventApplication { event, outcome }]
+    }
+
+    /// Fire the scheduled world events whose time has come: apply their
+    /// effects, note them in the chat and keep them for the next prompt.
+    fn fire_scheduled_events(&mut self, applications: &mut Vec<EventApplication>) {
+        for scheduled in self.game_state.take_due_scheduled_events() {
+            for event in scheduled.effects {
+                let outcome = apply_event(&mut self.game_state, event.clone());
+                applications.push(EventApplication { event, outcome });
+            }
+            self.messages
+                .push(Message::System(format!("World event: {}", scheduled.name)));
+            self.game_state.fired_world_events.push(FiredWorldEvent {
+                id: scheduled.id,
+                name: scheduled.name,
+                description: scheduled.description,
+                at_minutes: scheduled.at_minutes,
+            });
+        }
+    }
+
+    /// Apply an event the player triggered from the UI and report it.
+    fn apply_player_event(&mut self, event: NarrativeEvent) {
+        let outcome = apply_event(&mut self.game_state, event.clone());
+        let report = self.report(vec![EventApplication { event, outcome }]);
+        let snapshot = (&self.game_state).into();
+        let _ = self.tx.send(EngineResponse::NarrativeApplied { report, snapshot });
+    }
+
+    fn needs_review(&self, event: &NarrativeEvent, world: &crate::ui::app::WorldDefinition) -> bool {
+        self.review_events && world.event_review.requires_review(event)
+    }
+
+    /// Hold `events` until the player approves or rejects them.
+    fn start_event_review(
+        &mut self,
+        events: Vec<NarrativeEvent>,
+        validators: EventValidators,
+        context: crate::model::game_context::GameContext,
+    ) {
+        if events.is_empty() {
+            return;
+        }
+        self.pending_review = Some(PendingReview { context, validators });
+        let _ = self.tx.send(EngineResponse::EventsAwaitingReview { events });
+    }
+
+    /// Apply the events the player approved, if they still pass the turn's
+    /// validators; rejected ones are reported back to the LLM next turn.
+    fn resolve_event_review(&mut self, approved: Vec<NarrativeEvent>, rejected: Vec<NarrativeEvent>) {
+        let Some(mut review) = self.pending_review.take() else {
+            self.send_ui_error("No events are waiting for review.".to_string());
+            return;
+        };
+
+        let start_level = self.game_state.player.level;
+        let messages_start = self.messages.len();
+        let mut applications = Vec::new();
+        for event in approved {
+            // The player may have edited the event, so check it again.
+            if let Err(outcome) = review.validators.validate(&event, &self.game_state) {
+                applications.push(EventApplication { event, outcome });
+                continue;
+            }
+            let outcome = apply_event(&mut self.game_state, event.clone());
+            applications.push(EventApplication { event, outcome });
+        }
+        for event in rejected {
             applications.push(EventApplication {
                 event,
-                outcome,
+                outcome: EventApplyOutcome::Rejected {
+                    reason: "Rejected by the player".to_string(),
+                },
             });
         }
+        self.settle_applied(&review.context, start_level, &mut applications);
+        // Keep the turn's earlier feedback; add what the review held back.
+        add_engine_feedback(
+            &mut self.game_state.engine_feedback,
+            applications.iter().filter(|a| !a.is_applied()).map(|a| a.summary()),
+        );
+
+        self.send_new_messages_since(messages_start);
+        let report = self.report(applications);
+        let snapshot = (&self.game_state).into();
+        let _ = self.tx.send(EngineResponse::NarrativeApplied { report, snapshot });
+    }
+
+    /// Every `story_summary_interval` turns, condense messages that are about
+    /// to leave the prompt window into a story chapter on a background thread.
+    fn maybe_start_story_summary(&mut self, llm: &crate::engine::llm_client::LlmConfig) {
+        if self.story_summary_interval == 0 || self.pending_summary.is_some() {
+            return;
+        }
+        if !self.turn_index.is_multiple_of(u64::from(self.story_summary_interval)) {
+            return;
+        }
+
+        let Some((from_message, to_message)) = story_summary_range(
+            &self.messages,
+            self.game_state.summarized_through,
+        ) else {
+            return;
+        };
 
-        maybe_grant_repetition_power(
-            &mut self.game_state,
-            &text,
-            &context.world,
-            &mut applications,
+        let previous = self
+            .game_state
+            .story_chapters
+            .last()
+            .map(|chapter| chapter.summary.clone());
+        let prompt = PromptBuilder::build_story_summary(
+            previous.as_deref(),
+            &self.messages[from_message..to_message],
         );
-        maybe_evolve_powers(&mut self.game_state, &context.world, &mut applications);
-        apply_set_bonuses(&mut self.game_state, &mut applications);
-        apply_level_stat_growth(
-            &mut self.game_state,
-            &context,
-            start_level,
-            &mut applications,
+
+        self.start_summary(
+            SummaryTarget::Story { from_message, to_message },
+            prompt,
+            llm,
         );
-        let apply_done = Instant::now();
+    }
 
-        // 9. Send state mutation report
-        if !applications.is_empty() || proximity_changed {
-            let report = NarrativeApplyReport { applications };
-            let snapshot = (&self.game_state).into();
+    /// Once an NPC has a batch of exchanges that left the recent log, fold
+    /// them into the NPC's facts, what they were told and open promises.
+    /// Runs between story summaries, one NPC at a time.
+    fn maybe_start_npc_memory_summary(&mut self, llm: &crate::engine::llm_client::LlmConfig) {
+        if self.story_summary_interval == 0 || self.pending_summary.is_some() {
+            return;
+        }
+        let Some((id, memory)) = self
+            .game_state
+            .npc_memories
+            .iter()
+            .filter(|(_, memory)| memory.earlier_exchanges.len() >= NPC_SUMMARY_BATCH)
+            .max_by(|a, b| {
+                a.1.earlier_exchanges
+                    .len()
+                    .cmp(&b.1.earlier_exchanges.len())
+                    .then_with(|| b.0.cmp(a.0))
+            })
+        else {
+            return;
+        };
 
-            let _ = self.tx.send(
-                EngineResponse::NarrativeApplied {
-                    report,
-                    snapshot,
-                }
-            );
-            let snapshot_done = Instant::now();
-            self.emit_timing(
-                "primary",
-                total_start,
-                split_done,
-                parse_done,
-                narrative_done,
-                apply_done,
-                snapshot_done,
-                None,
-            );
-        } else {
-            self.emit_timing(
-                "primary",
-                total_start,
-                split_done,
-                parse_done,
-                narrative_done,
-                apply_done,
-                Instant::now(),
-                None,
-            );
+        let name = npc_display_name(&self.game_state, id);
+        let exchanges = memory.earlier_exchanges.clone();
+        let prompt = PromptBuilder::build_npc_memory_summary(&name, memory, &exchanges);
+        let id = id.clone();
+        self.start_summary(SummaryTarget::NpcMemory { id, exchanges }, prompt, llm);
+    }
+
+    fn start_summary(
+        &mut self,
+        target: SummaryTarget,
+        prompt: String,
+        llm: &crate::engine::llm_client::LlmConfig,
+    ) {
+        let (resp_tx, resp_rx) = std::sync::mpsc::channel();
+        let llm = llm.for_role(LlmRole::Summarizer);
+        thread::spawn(move || {
+            let result = call_llm(vec![ChatMessage::system(prompt)], &llm, &AbortToken::never());
+            let _ = resp_tx.send(result);
+        });
+
+        self.pending_summary = Some(PendingSummary {
+            target,
+            response_rx: resp_rx,
+        });
+    }
+
+    fn handle_summary(&mut self, pending: PendingSummary, result: anyhow::Result<String>) {
+        let note = match pending.target {
+            SummaryTarget::Story { from_message, to_message } => {
+                self.apply_story_summary(from_message, to_message, result)
+            }
+            SummaryTarget::NpcMemory { id, exchanges } => {
+                self.apply_npc_memory_summary(&id, &exchanges, result)
+            }
+        };
+
+        let snapshot = (&self.game_state).into();
+        let _ = self.tx.send(EngineResponse::SummaryApplied { snapshot, note });
+    }
+
+    fn apply_story_summary(
+        &mut self,
+        from_message: usize,
+        to_message: usize,
+        result: anyhow::Result<String>,
+    ) -> String {
+        match result {
+            Ok(text) if !text.trim().is_empty() => {
+                let index = self.game_state.story_chapters.len() as u32 + 1;
+                self.game_state.story_chapters.push(StoryChapter {
+                    index,
+                    summary: text.trim().to_string(),
+                    from_message,
+                    to_message,
+                    world_time_minutes: self.game_state.world_time_minutes,
+                });
+                self.game_state.summarized_through = to_message;
+                format!(
+                    "Story summary: chapter {} covers messages {}-{}.",
+                    index, from_message, to_message
+                )
+            }
+            Ok(_) => "Story summary skipped: summarizer returned nothing.".to_string(),
+            Err(err) => format!("Story summary failed: {}", err),
         }
+    }
 
-        // 10. Update UI with full history
-        self.send_new_messages_since(messages_start);
+    fn apply_npc_memory_summary(
+        &mut self,
+        id: &str,
+        exchanges: &[NpcExchange],
+        result: anyhow::Result<String>,
+    ) -> String {
+        let name = npc_display_name(&self.game_state, id);
+        // An empty reply means nothing was worth keeping; the exchanges are
+        // still done with.
+        let text = match result {
+            Ok(text) => text,
+            Err(err) => return format!("NPC memory for {} failed: {}", name, err),
+        };
+        let Some(memory) = self.game_state.npc_memories.get_mut(id) else {
+            return format!("NPC memory for {} skipped: the NPC is gone.", name);
+        };
+
+        let notes = parse_npc_memory_summary(&text);
+        let added = notes.facts.len() + notes.told.len() + notes.promises.len();
+        merge_strings(&mut memory.key_facts, Some(notes.facts), None);
+        merge_strings(&mut memory.player_told, Some(notes.told), None);
+        merge_strings(&mut memory.promises, Some(notes.promises), None);
+        // Exchanges logged while the call ran stay queued for the next one.
+        memory.earlier_exchanges.retain(|e| !exchanges.contains(e));
+        format!(
+            "NPC memory: {} exchanges with {} folded into {} notes.",
+            exchanges.len(),
+            name,
+            added
+        )
     }
 
     fn send_new_messages_since(&self, start_len: usize) {
@@ -1228,6 +1746,21 @@ fn collect_requested_topics(events: &[NarrativeEvent]) -> Option<Vec<String>> {
     }
 }
 
+/// Messages from `summarized_through` up to the last
+/// `STORY_SUMMARY_KEEP_RECENT`, if enough story (non-system) messages wait.
+fn story_summary_range(messages: &[Message], summarized_through: usize) -> Option<(usize, usize)> {
+    let end = messages.len().saturating_sub(STORY_SUMMARY_KEEP_RECENT);
+    let start = summarized_through.min(end);
+    let story_messages = messages[start..end]
+        .iter()
+        .filter(|m| !matches!(m, Message::System(_)))
+        .count();
+    if story_messages < STORY_SUMMARY_MIN_MESSAGES {
+        return None;
+    }
+    Some((start, end))
+}
+
 fn tail_messages(messages: &[Message], max: usize) -> Vec<Message> {
     if messages.len() <= max {
         messages.to_vec()
@@ -1257,6 +1790,9 @@ fn build_requested_context(
             "stats" => {
                 push_section(&mut out, "STATS", &format_state_stats(state));
             }
+            "effects" | "status_effects" | "buffs" | "debuffs" => {
+                push_section(&mut out, "STATUS EFFECTS", &format_status_effects(state));
+            }
             "exp" | "experience" | "level" => {
                 push_section(&mut out, "EXP", &format_exp(state));
             }
@@ -1330,7 +1866,7 @@ fn build_requested_context(
                 push_section(&mut out, "PLAYER CARD", &format_player_card(state));
             }
             "time" | "clock" | "world_time" => {
-                push_section(&mut out, "TIME", &format_time(state));
+                push_section(&mut out, "TIME", &format_time(state, context));
             }
             _ => {
                 push_section(
@@ -1542,7 +2078,7 @@ fn format_player_state(
 ) -> String {
     let p = &context.player;
     let s = &state.player;
-    format!(
+    let mut out = format!(
         "Name: {}\nClass: {}\nLevel: {}\nEXP: {}/{}\nHP: {}/{}\nBackground:\n{}\n",
         p.name,
         p.class,
@@ -1552,7 +2088,12 @@ fn format_player_state(
         s.hp,
         s.max_hp,
         p.background
-    )
+    );
+    if !state.status_effects.is_empty() {
+        out.push_str("Status Effects:\n");
+        out.push_str(&format_status_effects(state));
+    }
+    out
 }
 
 fn format_exp(state: &InternalGameState) -> String {
@@ -1568,8 +2109,28 @@ fn format_state_stats(state: &InternalGameState) -> String {
         return "None\n".to_string();
     }
     let mut s = String::new();
-    for (k, v) in &state.stats {
-        s.push_str(&format!("- {}: {}\n", k, v));
+    for stat in state.stat_breakdown() {
+        if stat.contributions.len() > 1 {
+            s.push_str(&format!("- {}: {}\n", stat.id, stat.breakdown_text()));
+        } else {
+            s.push_str(&format!("- {}: {}\n", stat.id, stat.total));
+        }
+    }
+    s
+}
+
+fn format_status_effects(state: &InternalGameState) -> String {
+    if state.status_effects.is_empty() {
+        return "None\n".to_string();
+    }
+    let mut effects: Vec<_> = state.status_effects.values().collect();
+    effects.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut s = String::new();
+    for effect in effects {
+        s.push_str(&format!(
+            "- {}\n",
+            effect.summary(state.world_time_minutes)
+        ));
     }
     s
 }
@@ -1750,6 +2311,11 @@ fn format_quests(state: &InternalGameState) -> String {
         if quest.negotiable {
             s.push_str("  Negotiable rewards: yes\n");
         }
+        if quest.negotiation.locked {
+            s.push_str("  Rewards agreed: yes\n");
+        } else if let Some(counter) = &quest.negotiation.counter_offer {
+            s.push_str(&format!("  Player counter-offer: {}\n", counter.join(", ")));
+        }
         if !quest.reward_options.is_empty() {
             s.push_str("  Reward options:\n");
             for opt in &quest.reward_options {
@@ -1759,6 +2325,28 @@ fn format_quests(state: &InternalGameState) -> String {
         if !quest.description.trim().is_empty() {
             s.push_str(&format!("  Description: {}\n", quest.description));
         }
+        if let Some(giver) = quest.giver_npc.as_ref().or(quest.giver_faction.as_ref()) {
+            s.push_str(&format!("  Giver: {}\n", giver));
+        }
+        if let Some(location) = &quest.location {
+            s.push_str(&format!("  Location: {}\n", location));
+        }
+        if let Some(deadline) = quest.deadline_minutes {
+            let left = deadline.saturating_sub(state.world_time_minutes);
+            s.push_str(&format!("  Deadline: {} minutes left\n", left));
+        }
+        if !quest.prerequisites.is_empty() {
+            s.push_str(&format!("  Requires: {}\n", quest.prerequisites.join(", ")));
+        }
+        if !quest.follow_ups.is_empty() {
+            s.push_str(&format!("  Unlocks: {}\n", quest.follow_ups.join(", ")));
+        }
+        if !quest.failure_conditions.is_empty() {
+            s.push_str("  Fails if:\n");
+            for condition in &quest.failure_conditions {
+                s.push_str(&format!("  - {}\n", condition));
+            }
+        }
         if !quest.rewards.is_empty() {
             s.push_str("  Rewards:\n");
             for r in &quest.rewards {
@@ -1769,7 +2357,8 @@ fn format_quests(state: &InternalGameState) -> String {
             s.push_str("  Sub-quests:\n");
             for step in &quest.sub_quests {
                 let status = if step.completed { "done" } else { "open" };
-                s.push_str(&format!("  - [{}] {}\n", status, step.description));
+                let optional = if step.optional { " (optional)" } else { "" };
+                s.push_str(&format!("  - [{}] {}{}\n", status, step.description, optional));
             }
         }
     }
@@ -1778,6 +2367,7 @@ fn format_quests(state: &InternalGameState) -> String {
 
 fn quest_status_label(status: &crate::model::game_state::QuestStatus) -> &'static str {
     match status {
+        crate::model::game_state::QuestStatus::Locked => "locked",
         crate::model::game_state::QuestStatus::Active => "active",
         crate::model::game_state::QuestStatus::Completed => "completed",
         crate::model::game_state::QuestStatus::Failed => "failed",
@@ -1792,10 +2382,128 @@ fn format_npcs(state: &InternalGameState) -> String {
     for npc in state.npcs.values() {
         let status = if npc.nearby { "nearby" } else { "away" };
         s.push_str(&format!("- {} ({}) [{}]\n", npc.name, npc.role, status));
+        if let Some(memory) = state.npc_memories.get(&npc.id) {
+            if !memory.key_facts.is_empty() {
+                s.push_str(&format!("  Facts: {}\n", memory.key_facts.join("; ")));
+            }
+            if !memory.promises.is_empty() {
+                s.push_str(&format!("  Promises: {}\n", memory.promises.join("; ")));
+            }
+        }
     }
     s
 }
 
+const NPC_RECENT_EXCHANGES: usize = 6;
+const NPC_EARLIER_EXCHANGES: usize = 10;
+/// Earlier exchanges an NPC collects before they are summarized
+const NPC_SUMMARY_BATCH: usize = 4;
+
+/// Engine-side conversation log: every NPC or party member who spoke this
+/// turn gets the exchange logged in their memory. Exchanges that roll out
+/// of the recent list wait, shortened, for the summarizer; past a fixed
+/// count the oldest are dropped.
+fn record_npc_exchanges(state: &mut InternalGameState, player_text: &str, new_messages: &[Message]) {
+    use crate::model::message::RoleplaySpeaker;
+
+    let mut lines_by_id: Vec<(String, Vec<String>)> = Vec::new();
+    for msg in new_messages {
+        let Message::Roleplay { speaker, text } = msg else { continue };
+        if matches!(speaker, RoleplaySpeaker::Narrator) {
+            continue;
+        }
+        let Some((name, body)) = text.split_once(':') else { continue };
+        let name = name.trim();
+        let body = body.trim();
+        if name.is_empty() || body.is_empty() {
+            continue;
+        }
+        let id = state
+            .npcs
+            .values()
+            .find(|n| n.name.eq_ignore_ascii_case(name))
+            .map(|n| n.id.clone())
+            .or_else(|| {
+                state
+                    .party
+                    .values()
+                    .find(|m| m.name.eq_ignore_ascii_case(name))
+                    .map(|m| m.id.clone())
+            });
+        let Some(id) = id else { continue };
+        match lines_by_id.iter_mut().find(|(existing, _)| *existing == id) {
+            Some((_, lines)) => lines.push(body.to_string()),
+            None => lines_by_id.push((id, vec![body.to_string()])),
+        }
+    }
+
+    let now = state.world_time_minutes;
+    for (id, lines) in lines_by_id {
+        let memory = state.npc_memories.entry(id).or_default();
+        memory.last_interaction_minutes = Some(now);
+        memory.recent_exchanges.push(NpcExchange {
+            at_minutes: now,
+            player: truncate_chars(player_text.trim(), 160),
+            reply: truncate_chars(&lines.join(" "), 200),
+        });
+        let excess = memory.recent_exchanges.len().saturating_sub(NPC_RECENT_EXCHANGES);
+        for mut older in memory.recent_exchanges.drain(..excess) {
+            older.player = truncate_chars(&older.player, 60);
+            older.reply = truncate_chars(&older.reply, 100);
+            memory.earlier_exchanges.push(older);
+        }
+        let excess = memory.earlier_exchanges.len().saturating_sub(NPC_EARLIER_EXCHANGES);
+        memory.earlier_exchanges.drain(..excess);
+    }
+}
+
+fn npc_display_name(state: &InternalGameState, id: &str) -> String {
+    state
+        .npcs
+        .get(id)
+        .map(|n| n.name.clone())
+        .or_else(|| state.party.get(id).map(|m| m.name.clone()))
+        .unwrap_or_else(|| id.to_string())
+}
+
+#[derive(Debug, Default)]
+struct NpcMemoryNotes {
+    facts: Vec<String>,
+    told: Vec<String>,
+    promises: Vec<String>,
+}
+
+/// Reads the `FACT:` / `TOLD:` / `PROMISE:` lines of an NPC memory summary.
+/// Anything else the summarizer writes is ignored.
+fn parse_npc_memory_summary(text: &str) -> NpcMemoryNotes {
+    let mut notes = NpcMemoryNotes::default();
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches(['-', '*']).trim_start();
+        let Some((label, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let list = match label.trim().to_ascii_uppercase().as_str() {
+            "FACT" => &mut notes.facts,
+            "TOLD" => &mut notes.told,
+            "PROMISE" => &mut notes.promises,
+            _ => continue,
+        };
+        list.push(truncate_chars(value, 200));
+    }
+    notes
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
 fn format_section_cards(state: &InternalGameState, section: &str) -> String {
     let Some(cards) = state.sections.get(section) else {
         return "None\n".to_string();
@@ -1861,27 +2569,43 @@ fn format_player_card(state: &InternalGameState) -> String {
     s
 }
 
-fn format_time(state: &InternalGameState) -> String {
+fn format_time(state: &InternalGameState, context: &crate::model::game_context::GameContext) -> String {
     let total_minutes = state.world_time_minutes;
-    let days = total_minutes / (24 * 60);
-    let hours = (total_minutes / 60) % 24;
-    let minutes = total_minutes % 60;
-    format!(
-        "Elapsed time: {} days, {:02}:{:02}\n",
-        days, hours, minutes
-    )
+    let mut s = String::new();
+    if let Some(moment) = context.world.calendar.moment(total_minutes) {
+        s.push_str(&format!("Date: {}\n", moment.describe()));
+    }
+    s.push_str(&format!("{} elapsed since the story began\n", format_elapsed(total_minutes)));
+    let mut scheduled: Vec<_> = state.scheduled_events.values().collect();
+    scheduled.sort_by_key(|e| e.at_minutes);
+    for event in scheduled {
+        s.push_str(&format!(
+            "- {} [{}] at {}\n",
+            event.name,
+            event.id,
+            context.world.calendar.describe(event.at_minutes)
+        ));
+    }
+    s
 }
 
 fn format_relationships(state: &InternalGameState) -> String {
     if state.relationships.is_empty() {
         return "None\n".to_string();
     }
+    let mut relationships: Vec<_> = state.relationships.values().collect();
+    relationships.sort_by(|a, b| (&a.subject_id, &a.target_id).cmp(&(&b.subject_id, &b.target_id)));
     let mut s = String::new();
-    for rel in state.relationships.values() {
-        s.push_str(&format!(
-            "- {} -> {}: {}\n",
-            rel.subject_id, rel.target_id, rel.value
-        ));
+    for rel in relationships {
+        s.push_str(&format!("- {} -> {}: {}", rel.subject_id, rel.target_id, rel.value));
+        if let Some(tier) = ReputationTier::for_reputation(&state.relationship_tiers, rel.value) {
+            s.push_str(&format!(" ({})", tier.name));
+        }
+        s.push('\n');
+        for note in &rel.history {
+            let reason = note.reason.as_deref().unwrap_or("no reason given");
+            s.push_str(&format!("  {:+}: {}\n", note.delta, reason));
+        }
     }
     s
 }
@@ -1890,13 +2614,34 @@ fn format_factions(state: &InternalGameState) -> String {
     if state.factions.is_empty() {
         return "None\n".to_string();
     }
+    let name_of = |id: &String| {
+        state.factions.get(id).map_or(id.clone(), |f| f.name.clone())
+    };
+    let mut factions: Vec<_> = state.factions.values().collect();
+    factions.sort_by(|a, b| a.name.cmp(&b.name));
     let mut s = String::new();
-    for faction in state.factions.values() {
+    for faction in factions {
         let kind = faction.kind.clone().unwrap_or_else(|| "unknown".to_string());
         s.push_str(&format!(
-            "- {} ({}) rep: {}\n",
-            faction.name, kind, faction.reputation
+            "- {} [{}] ({}) rep: {}",
+            faction.name, faction.id, kind, faction.reputation
         ));
+        if let Some(tier) = &faction.tier {
+            s.push_str(&format!(" ({})", tier));
+        }
+        s.push('\n');
+        if faction.member {
+            let rank = faction.rank.as_deref().unwrap_or("no rank");
+            s.push_str(&format!("  Player is a member, rank: {}\n", rank));
+        }
+        if !faction.allies.is_empty() {
+            let allies: Vec<_> = faction.allies.iter().map(name_of).collect();
+            s.push_str(&format!("  Allies: {}\n", allies.join(", ")));
+        }
+        if !faction.enemies.is_empty() {
+            let enemies: Vec<_> = faction.enemies.iter().map(name_of).collect();
+            s.push_str(&format!("  Enemies: {}\n", enemies.join(", ")));
+        }
         if let Some(desc) = &faction.description {
             let trimmed = desc.trim();
             if !trimmed.is_empty() {
@@ -2001,64 +2746,40 @@ fn move_selected_loot_to_inventory(
     (applications, moved_labels)
 }
 
-fn quest_offer_source(narrative: &str) -> Option<QuestOfferSource> {
-    let n = normalize_phrase(narrative);
-    if n.contains("the world is offering you a quest") {
-        return Some(QuestOfferSource::World);
-    }
-    if n.contains("i hereby offer you a quest") {
-        if n.contains("[npc") {
-            return Some(QuestOfferSource::Npc);
-        }
-        if !looks_like_hostile_offer(&n) {
-            return Some(QuestOfferSource::Npc);
-        }
-        return Some(QuestOfferSource::Npc);
-    }
-    None
-}
-
-fn looks_like_hostile_offer(normalized: &str) -> bool {
-    let hostile = [
-        "attacks",
-        "attack",
-        "lunges",
-        "swings",
-        "strikes",
-        "slashes",
-        "bites",
-        "mauls",
-        "charges",
-        "roars",
-        "hostile",
-        "bloodthirsty",
-        "feral",
-        "enemy",
-        "ambush",
-    ];
-    hostile.iter().any(|k| normalized.contains(k))
-}
-
-fn player_accepts_quest(input: &str) -> bool {
+/// An explicit answer to a pending quest offer, judged on whole words: a
+/// clear accept or decline phrase, or a reply that is nothing but yes/no.
+fn classify_offer_reply(input: &str) -> Option<bool> {
     let t = normalize_phrase(input);
-    let phrases = [
+    let padded = format!(" {} ", t);
+    let has_phrase = |phrases: &[&str]| phrases.iter().any(|p| padded.contains(&format!(" {} ", p)));
+    let declines = [
+        "i decline",
+        "decline the quest",
+        "decline the offer",
+        "no thanks",
+        "no thank you",
+        "i refuse",
+        "not interested",
+        "i will pass",
+        "i ll pass",
+    ];
+    let accepts = [
         "i accept",
-        "i accept the quest",
-        "accept quest",
         "accept the quest",
-        "yes i accept",
-        "i agree",
+        "accept the offer",
         "i will do it",
+        "i ll do it",
         "i will take it",
-        "i accept it",
-        "accept it",
-        "i will do this",
-        "sure",
-        "yes",
-        "ok",
-        "okay",
+        "i ll take it",
+        "i agree",
     ];
-    phrases.iter().any(|p| t.contains(p))
+    if has_phrase(&declines) || ["no", "nope"].contains(&t.as_str()) {
+        return Some(false);
+    }
+    if has_phrase(&accepts) || ["yes", "ok", "okay", "sure", "deal"].contains(&t.as_str()) {
+        return Some(true);
+    }
+    None
 }
 
 fn normalize_phrase(input: &str) -> String {
@@ -2110,17 +2831,19 @@ fn update_action_counts(state: &mut InternalGameState, input: &str) {
     }
 }
 
+/// The character sheet owns base stats: mirror it so edits and removals
+/// made in the UI reach the engine. Derived bonuses are stored separately.
 fn sync_stats_from_context(state: &mut InternalGameState, context: &crate::model::game_context::GameContext) {
-    for (k, v) in &context.player.stats {
-        state.stats.entry(k.to_string()).or_insert(*v);
+    if context.player.stats.is_empty() {
+        return;
     }
+    state.stats = context.player.stats.clone();
 }
 
 fn apply_level_stat_growth(
     state: &mut InternalGameState,
     context: &crate::model::game_context::GameContext,
     start_level: u32,
-    applications: &mut Vec<EventApplication>,
 ) {
     let gained = state.player.level.saturating_sub(start_level);
     if gained == 0 {
@@ -2184,26 +2907,9 @@ fn apply_level_stat_growth(
             deltas.push(("luck", 1));
         }
 
-        apply_stat_deltas(state, deltas, applications);
-    }
-}
-
-fn apply_stat_deltas(
-    state: &mut InternalGameState,
-    deltas: Vec<(&str, i32)>,
-    applications: &mut Vec<EventApplication>,
-) {
-    for (stat_id, delta) in deltas {
-        let entry = state.stats.entry(stat_id.to_string()).or_insert(10);
-        *entry += delta;
-        let event = NarrativeEvent::ModifyStat {
-            stat_id: stat_id.to_string(),
-            delta,
-        };
-        applications.push(EventApplication {
-            event,
-            outcome: EventApplyOutcome::Applied,
-        });
+        for (stat_id, delta) in deltas {
+            *state.level_stat_bonuses.entry(stat_id.to_string()).or_insert(0) += delta;
+        }
     }
 }
 
@@ -2254,15 +2960,6 @@ fn apply_set_bonuses(state: &mut InternalGameState, applications: &mut Vec<Event
             continue;
         }
 
-        if current > 0 {
-            let deltas = set_bonus_deltas(current, true);
-            apply_stat_deltas(state, deltas, applications);
-        }
-        if desired > 0 {
-            let deltas = set_bonus_deltas(desired, false);
-            apply_stat_deltas(state, deltas, applications);
-        }
-
         if desired == 0 {
             state.set_bonus_tiers.remove(&set_id);
         } else {
@@ -2293,15 +2990,6 @@ fn apply_set_bonuses(state: &mut InternalGameState, applications: &mut Vec<Event
     }
 }
 
-fn set_bonus_deltas(tier: u32, remove: bool) -> Vec<(&'static str, i32)> {
-    let mult = if remove { -1 } else { 1 };
-    match tier {
-        1 => vec![("strength", 1 * mult), ("constitution", 1 * mult)],
-        2 => vec![("strength", 2 * mult), ("constitution", 2 * mult), ("agility", 1 * mult)],
-        _ => Vec::new(),
-    }
-}
-
 fn maybe_evolve_powers(
     state: &mut InternalGameState,
     world: &crate::ui::app::WorldDefinition,
@@ -2469,8 +3157,8 @@ fn tier_name_for(world: &crate::ui::app::WorldDefinition, tier: u32) -> String {
 fn ensure_tier_names(names: &mut Vec<String>) {
     let defaults = ["Novice", "Adept", "Expert", "Master", "Grandmaster"];
     if names.len() < 5 {
-        for i in names.len()..5 {
-            names.push(defaults[i].to_string());
+        for default in defaults.iter().skip(names.len()) {
+            names.push(default.to_string());
         }
     } else if names.len() > 5 {
         names.truncate(5);
@@ -2508,51 +3196,86 @@ fn skill_tier_names_for(
     }
     normalized_tier_names(&world.skill_tier_names)
 }
-fn validate_start_quest(
+/// ENGINE FEEDBACK lines for the next prompt: every rejected or deferred
+/// event of the turn, capped so a bad turn cannot flood the prompt.
+fn engine_feedback(applications: &[EventApplication]) -> Vec<String> {
+    let mut lines = Vec::new();
+    add_engine_feedback(
+        &mut lines,
+        applications.iter().filter(|a| !a.is_applied()).map(|a| a.summary()),
+    );
+    lines
+}
+
+/// Append ENGINE FEEDBACK lines, keeping at most `MAX_ENGINE_FEEDBACK_LINES`
+/// and counting the rest in one closing note.
+fn add_engine_feedback(feedback: &mut Vec<String>, lines: impl IntoIterator<Item = String>) {
+    let mut more = match feedback.last().and_then(|line| feedback_overflow(line)) {
+        Some(count) => {
+            feedback.pop();
+            count
+        }
+        None => 0,
+    };
+    for line in lines {
+        if feedback.len() < MAX_ENGINE_FEEDBACK_LINES {
+            feedback.push(line);
+        } else {
+            more += 1;
+        }
+    }
+    if more > 0 {
+        feedback.push(format!("...and {} more events that did not take effect", more));
+    }
+}
+
+fn feedback_overflow(line: &str) -> Option<usize> {
+    line.strip_prefix("...and ")?
+        .strip_suffix(" more events that did not take effect")?
+        .parse()
+        .ok()
+}
+
+/// A `start_quest` for a quest the player has not taken on yet.
+fn is_new_declinable_quest(event: &NarrativeEvent, state: &InternalGameState) -> bool {
+    let NarrativeEvent::StartQuest { id, title, declinable, .. } = event else {
+        return false;
+    };
+    *declinable != Some(false)
+        && !state.quests.contains_key(id)
+        && !state.quests.values().any(|q| q.title.eq_ignore_ascii_case(title.trim()))
+}
+
+/// Why a quest offer, or a mandatory quest start, breaks the world's quest
+/// rules. Quests without a giver NPC come from the world.
+fn validate_quest_offer(
     event: &NarrativeEvent,
-    offer_source: Option<QuestOfferSource>,
-    player_accepts: bool,
     world: &crate::ui::app::WorldDefinition,
 ) -> Option<String> {
-    let NarrativeEvent::StartQuest { declinable, .. } = event else {
-        return None;
-    };
-
-    let source = match offer_source {
-        Some(source) => source,
-        None => {
-            return Some("Quest rejected: missing quest offer phrase.".to_string());
+    let (giver_npc, mandatory) = match event {
+        NarrativeEvent::OfferQuest { giver_npc, declinable, .. } => {
+            (giver_npc, *declinable == Some(false))
         }
+        NarrativeEvent::StartQuest { giver_npc, declinable: Some(false), .. } => (giver_npc, true),
+        _ => return None,
     };
-
-    match source {
-        QuestOfferSource::World => {
-            if !world.world_quests_enabled {
-                return Some("Quest rejected: world quests are disabled.".to_string());
-            }
-            if declinable == &Some(false) && !world.world_quests_mandatory {
-                return Some("Quest rejected: mandatory world quests are disabled.".to_string());
-            }
-            if declinable == &Some(false) && world.world_quests_mandatory {
-                return None;
-            }
-            if player_accepts {
-                None
-            } else {
-                Some("Quest pending: player has not accepted the world quest.".to_string())
-            }
+    let from_world = giver_npc.as_deref().is_none_or(|g| g.trim().is_empty());
+    if from_world {
+        if !world.world_quests_enabled {
+            return Some("Quest rejected: world quests are disabled.".to_string());
         }
-        QuestOfferSource::Npc => {
-            if !world.npc_quests_enabled {
-                return Some("Quest rejected: NPC quests are disabled.".to_string());
-            }
-            if player_accepts {
-                None
-            } else {
-                Some("Quest pending: player has not accepted the quest.".to_string())
-            }
+        if mandatory && !world.world_quests_mandatory {
+            return Some("Quest rejected: mandatory world quests are disabled.".to_string());
+        }
+    } else {
+        if !world.npc_quests_enabled {
+            return Some("Quest rejected: NPC quests are disabled.".to_string());
+        }
+        if mandatory {
+            return Some("Quest rejected: only world quests can be mandatory.".to_string());
         }
     }
+    None
 }
 
 fn player_requested_party_details(input: &str) -> bool {
@@ -2636,6 +3359,30 @@ fn sanitize_party_update(event: &NarrativeEvent) -> NarrativeEvent {
 }
 
 fn migrate_save(save: &mut GameSave) {
+    if save.version < 5 {
+        // Older saves baked set bonuses into base stats; strip them so the
+        // derived stat layer does not count them twice.
+        let state = &mut save.internal_state;
+        for tier in state.set_bonus_tiers.values() {
+            for (stat_id, delta) in crate::model::internal_game_state::set_bonus_deltas(*tier) {
+                if let Some(value) = state.stats.get_mut(stat_id) {
+                    *value -= delta;
+                }
+            }
+        }
+        for (stat_id, value) in &state.stats {
+            save.player.stats.insert(stat_id.clone(), *value);
+        }
+    }
+    if save.version < 6 {
+        // Relationship decay was added; start counting from the save's
+        // current time rather than from world time 0.
+        let state = &mut save.internal_state;
+        let now = state.world_time_minutes;
+        for rel in state.relationships.values_mut() {
+            rel.decay_from_minutes = now;
+        }
+    }
     if save.version < SAVE_VERSION {
         save.version = SAVE_VERSION;
     }
@@ -2690,9 +3437,50 @@ fn diff_lists(old_list: &[String], new_list: &[String]) -> (Vec<String>, Vec<Str
 
 #[cfg(test)]
 mod tests {
-    use super::sanitize_party_update;
+    use super::{
+        add_engine_feedback, apply_set_bonuses, classify_offer_reply, engine_feedback, migrate_save,
+        parse_npc_memory_summary, record_npc_exchanges, sanitize_party_update,
+        MAX_ENGINE_FEEDBACK_LINES, NPC_EARLIER_EXCHANGES, NPC_RECENT_EXCHANGES,
+    };
+    use crate::engine::apply_event::apply_event;
+    use crate::model::game_save::GameSave;
+    use crate::model::event_result::{EventApplication, EventApplyOutcome};
+    use crate::model::game_state::{EquippedItem, Npc, NpcRoutine, StatSource};
+    use crate::model::message::{Message, RoleplaySpeaker};
+    use crate::model::internal_game_state::InternalGameState;
     use crate::model::narrative_event::NarrativeEvent;
 
+    #[test]
+    fn old_relationships_start_decaying_from_load_time() {
+        let mut state = InternalGameState {
+            world_time_minutes: 30 * 24 * 60,
+            ..Default::default()
+        };
+        let rel = serde_json::from_value(serde_json::json!({
+            "subject_id": "player",
+            "target_id": "mira",
+            "value": 40,
+        }))
+        .unwrap();
+        state.relationships.insert("player::mira".to_string(), rel);
+        let mut save = GameSave {
+            version: 5,
+            world: Default::default(),
+            player: Default::default(),
+            party: Vec::new(),
+            messages: Vec::new(),
+            internal_state: state,
+            speaker_colors: Default::default(),
+            character_image_rgba: None,
+            character_image_size: None,
+        };
+        migrate_save(&mut save);
+
+        let state = &mut save.internal_state;
+        apply_event(state, NarrativeEvent::TimePassed { minutes: 60, reason: None });
+        assert_eq!(state.relationships["player::mira"].value, 40);
+    }
+
     #[test]
     fn sanitize_party_update_trims_lists_and_details() {
         let event = NarrativeEvent::PartyUpdate {
@@ -2729,4 +3517,142 @@ mod tests {
             panic!("expected party update");
         }
     }
+
+    #[test]
+    fn set_bonus_is_derived_not_written_into_base_stats() {
+        let mut state = InternalGameState::default();
+        for id in ["helm", "boots"] {
+            state.equipment.insert(
+                id.to_string(),
+                EquippedItem {
+                    item_id: id.to_string(),
+                    slot: "armor".to_string(),
+                    set_id: Some("iron".to_string()),
+                    description: None,
+                    modifiers: Default::default(),
+                },
+            );
+        }
+        let mut applications = Vec::new();
+        apply_set_bonuses(&mut state, &mut applications);
+
+        assert_eq!(state.stats["strength"], 10);
+        let strength = state
+            .stat_breakdown()
+            .into_iter()
+            .find(|s| s.id == "strength")
+            .expect("strength");
+        assert_eq!(strength.total, 11);
+        assert!(strength
+            .contributions
+            .iter()
+            .any(|c| c.source == StatSource::Set && c.value == 1));
+
+        state.equipment.remove("boots");
+        apply_set_bonuses(&mut state, &mut applications);
+        assert!(state.set_bonus_tiers.is_empty());
+        assert_eq!(state.stats["strength"], 10);
+    }
+
+    #[test]
+    fn npc_exchanges_queue_for_the_summarizer() {
+        let mut state = InternalGameState::default();
+        state.npcs.insert(
+            "npc_mira".to_string(),
+            Npc {
+                id: "npc_mira".to_string(),
+                name: "Mira".to_string(),
+                role: "Smith".to_string(),
+                notes: String::new(),
+                nearby: true,
+                routine: NpcRoutine::default(),
+            },
+        );
+        let turns = NPC_RECENT_EXCHANGES + NPC_EARLIER_EXCHANGES + 2;
+        for turn in 0..turns {
+            state.world_time_minutes = turn as u64 * 10;
+            let reply = vec![Message::Roleplay {
+                speaker: RoleplaySpeaker::Npc,
+                text: format!("Mira: Reply number {}.", turn),
+            }];
+            record_npc_exchanges(&mut state, "Hello there", &reply);
+        }
+
+        let memory = &state.npc_memories["npc_mira"];
+        assert_eq!(memory.recent_exchanges.len(), NPC_RECENT_EXCHANGES);
+        assert_eq!(memory.earlier_exchanges.len(), NPC_EARLIER_EXCHANGES);
+        assert_eq!(memory.earlier_exchanges[0].reply, "Reply number 2.");
+        assert_eq!(memory.earlier_exchanges[0].at_minutes, 20);
+        assert_eq!(memory.last_interaction_minutes, Some((turns as u64 - 1) * 10));
+    }
+
+    #[test]
+    fn npc_memory_summary_keeps_only_labelled_lines() {
+        let notes = parse_npc_memory_summary(
+            "Here is what Mira remembers:\n\
+             FACT: The player repaired her forge.\n\
+             - told: The player is looking for their brother.\n\
+             PROMISE: Mira will forge a blade by day 5.\n\
+             PROMISE:\n\
+             MOOD: cheerful",
+        );
+        assert_eq!(notes.facts, vec!["The player repaired her forge."]);
+        assert_eq!(notes.told, vec!["The player is looking for their brother."]);
+        assert_eq!(notes.promises, vec!["Mira will forge a blade by day 5."]);
+    }
+
+    #[test]
+    fn offer_replies_need_an_explicit_answer() {
+        assert_eq!(classify_offer_reply("I'll do it."), Some(true));
+        assert_eq!(classify_offer_reply("Okay"), Some(true));
+        assert_eq!(classify_offer_reply("No thanks, I'm busy."), Some(false));
+        assert_eq!(classify_offer_reply("I look around. Okay, what's in the box?"), None);
+        assert_eq!(classify_offer_reply("Yes, but first tell me about the cave."), None);
+    }
+
+    #[test]
+    fn engine_feedback_lists_only_held_back_events() {
+        let applications = vec![
+            EventApplication {
+                event: NarrativeEvent::AddExp { amount: 10 },
+                outcome: EventApplyOutcome::Applied,
+            },
+            EventApplication {
+                event: NarrativeEvent::UpdateQuest {
+                    id: "q1".to_string(),
+                    title: None,
+                    description: None,
+                    status: None,
+                    difficulty: None,
+                    negotiable: None,
+                    reward_options: None,
+                    rewards: None,
+                    sub_quests: None,
+                    follow_ups: None,
+                    location: None,
+                    deadline_minutes: None,
+                    failure_conditions: None,
+                },
+                outcome: EventApplyOutcome::Deferred {
+                    reason: "Quest 'q1' not found".to_string(),
+                },
+            },
+        ];
+        assert_eq!(
+            engine_feedback(&applications),
+            vec!["update_quest deferred: Quest 'q1' not found".to_string()]
+        );
+    }
+
+    #[test]
+    fn engine_feedback_stays_capped_when_lines_are_added() {
+        let mut feedback = Vec::new();
+        add_engine_feedback(&mut feedback, (0..MAX_ENGINE_FEEDBACK_LINES + 2).map(|i| i.to_string()));
+        add_engine_feedback(&mut feedback, vec!["Quest 'q1' failed".to_string()]);
+        assert_eq!(feedback.len(), MAX_ENGINE_FEEDBACK_LINES + 1);
+        assert_eq!(
+            feedback.last().unwrap(),
+            "...and 3 more events that did not take effect"
+        );
+    }
 }
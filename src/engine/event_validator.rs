@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::event_result::EventApplyOutcome;
+use crate::model::game_state::party_morale;
+use crate::model::internal_game_state::InternalGameState;
+use crate::model::narrative_event::NarrativeEvent;
+use crate::ui::app::WorldDefinition;
+
+/// World-configured bounds on what the LLM may change in one turn.
+/// `None` means no limit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventLimits {
+    /// Largest total change to any one stat per turn (either direction)
+    pub max_stat_delta_per_turn: Option<i32>,
+    pub max_exp_per_turn: Option<i32>,
+    pub max_levels_per_turn: Option<u32>,
+    /// Currencies `currency_change` may use; empty allows any
+    pub allowed_currencies: Vec<String>,
+    pub allow_negative_balances: bool,
+}
+
+/// Which LLM events the player must approve when event review is on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventReviewPolicy {
+    /// Event types (e.g. `time_passed`) applied without review
+    pub auto_approve: Vec<String>,
+}
+
+impl Default for EventReviewPolicy {
+    fn default() -> Self {
+        let low_risk = [
+            "time_passed",
+            "npc_spawn",
+            "npc_update",
+            "npc_despawn",
+            "dialogue",
+            "combat",
+            "travel",
+            "rest",
+            "request_context",
+        ];
+        Self {
+            auto_approve: low_risk.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
+impl EventReviewPolicy {
+    pub fn requires_review(&self, event: &NarrativeEvent) -> bool {
+        let event_type = event.type_name();
+        !self
+            .auto_approve
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&event_type))
+    }
+}
+
+/// One check run on LLM events between decoding and `apply_event`.
+pub trait EventValidator {
+    /// Called in order, right before each event would be applied, so
+    /// `state` already reflects the events before it. Return the outcome
+    /// to record instead of applying the event.
+    fn validate(
+        &mut self,
+        event: &NarrativeEvent,
+        state: &InternalGameState,
+    ) -> Result<(), EventApplyOutcome>;
+}
+
+/// The validators for one turn. Build a fresh set per turn: the limit
+/// checks keep running totals.
+pub struct EventValidators {
+    validators: Vec<Box<dyn EventValidator>>,
+}
+
+impl EventValidators {
+    /// World limits followed by reference checks.
+    pub fn for_turn(limits: &EventLimits) -> Self {
+        let mut validators = Self { validators: Vec::new() };
+        validators.push(WorldLimits::new(limits.clone()));
+        validators.push(References);
+        validators
+    }
+
+    /// `for_turn` with the world's limits, plus the world's other rules.
+    pub fn for_world(world: &WorldDefinition) -> Self {
+        let mut validators = Self::for_turn(&world.event_limits);
+        if let Some(loyal_morale) = world.loyal_morale {
+            validators.push(PartyLoyalty { loyal_morale });
+        }
+        validators
+    }
+
+    pub fn push(&mut self, validator: impl EventValidator + 'static) {
+        self.validators.push(Box::new(validator));
+    }
+
+    /// The first objection from any validator, if there is one.
+    pub fn validate(
+        &mut self,
+        event: &NarrativeEvent,
+        state: &InternalGameState,
+    ) -> Result<(), EventApplyOutcome> {
+        self.validators
+            .iter_mut()
+            .try_for_each(|validator| validator.validate(event, state))
+    }
+}
+
+/// Rejects changes beyond the world's `EventLimits`.
+struct WorldLimits {
+    limits: EventLimits,
+    stat_totals: HashMap<String, i32>,
+    exp_total: i32,
+    levels_total: u32,
+}
+
+impl WorldLimits {
+    fn new(limits: EventLimits) -> Self {
+        Self {
+            limits,
+            stat_totals: HashMap::new(),
+            exp_total: 0,
+            levels_total: 0,
+        }
+    }
+}
+
+fn rejected(reason: String) -> Result<(), EventApplyOutcome> {
+    Err(EventApplyOutcome::Rejected { reason })
+}
+
+impl EventValidator for WorldLimits {
+    fn validate(
+        &mut self,
+        event: &NarrativeEvent,
+        state: &InternalGameState,
+    ) -> Result<(), EventApplyOutcome> {
+        match event {
+            NarrativeEvent::ModifyStat { stat_id, delta } => {
+                let so_far = self.stat_totals.get(stat_id).copied().unwrap_or(0);
+                let Some(total) = so_far.checked_add(*delta) else {
+                    return rejected(format!("modify_stat '{}' by {:+} is out of range", stat_id, delta));
+                };
+                if let Some(max) = self.limits.max_stat_delta_per_turn {
+                    if total.abs() > max {
+                        return rejected(format!(
+                            "modify_stat '{}' by {:+} exceeds the world limit of {} per turn",
+                            stat_id, delta, max
+                        ));
+                    }
+                }
+                self.stat_totals.insert(stat_id.clone(), total);
+            }
+            NarrativeEvent::AddExp { amount } => {
+                if *amount < 0 {
+                    return rejected("add_exp amount cannot be negative".to_string());
+                }
+                let total = self.exp_total.saturating_add(*amount);
+                if let Some(max) = self.limits.max_exp_per_turn {
+                    if total > max {
+                        return rejected(format!(
+                            "add_exp {} exceeds the world limit of {} exp per turn",
+                            amount, max
+                        ));
+                    }
+                }
+                self.exp_total = total;
+            }
+            NarrativeEvent::LevelUp { levels } => {
+                let total = self.levels_total.saturating_add(*levels);
+                if let Some(max) = self.limits.max_levels_per_turn {
+                    if total > max {
+                        return rejected(format!(
+                            "level_up by {} exceeds the world limit of {} level(s) per turn",
+                            levels, max
+                        ));
+                    }
+                }
+                self.levels_total = total;
+            }
+            NarrativeEvent::CurrencyChange { currency, delta } => {
+                let allowed = &self.limits.allowed_currencies;
+                if !allowed.is_empty() && !allowed.iter().any(|c| c.eq_ignore_ascii_case(currency)) {
+                    return rejected(format!(
+                        "currency '{}' does not exist in this world (allowed: {})",
+                        currency,
+                        allowed.join(", ")
+                    ));
+                }
+                let balance = state.currencies.get(&state.currency_key(currency)).copied().unwrap_or(0);
+                let Some(after) = balance.checked_add(*delta) else {
+                    return rejected(format!(
+                        "currency_change {} {:+} is out of range (have {})",
+                        currency, delta, balance
+                    ));
+                };
+                if !self.limits.allow_negative_balances && after < 0 {
+                    return rejected(format!(
+                        "currency_change {} {:+} would leave a negative balance (have {})",
+                        currency, delta, balance
+                    ));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Rejects `npc_leave_party` for members whose morale is at or above the
+/// world's `loyal_morale`.
+struct PartyLoyalty {
+    loyal_morale: i32,
+}
+
+impl EventValidator for PartyLoyalty {
+    fn validate(
+        &mut self,
+        event: &NarrativeEvent,
+        state: &InternalGameState,
+    ) -> Result<(), EventApplyOutcome> {
+        if let NarrativeEvent::NpcLeaveParty { id } = event {
+            let morale = party_morale(state.relationships.values(), id);
+            if morale >= self.loyal_morale {
+                return rejected(format!(
+                    "npc_leave_party: '{}' is loyal (morale {}, needs below {}); sour the relationship first",
+                    id, morale, self.loyal_morale
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Defers events whose ids name nothing the engine knows about.
+struct References;
+
+fn deferred(reason: String) -> Result<(), EventApplyOutcome> {
+    Err(EventApplyOutcome::Deferred { reason })
+}
+
+/// The player, an NPC, a party member or a faction.
+fn is_known_entity(state: &InternalGameState, id: &str) -> bool {
+    id.eq_ignore_ascii_case("player")
+        || state.npcs.contains_key(id)
+        || state.party.contains_key(id)
+        || state.factions.contains_key(id)
+}
+
+impl EventValidator for References {
+    fn validate(
+        &mut self,
+        event: &NarrativeEvent,
+        state: &InternalGameState,
+    ) -> Result<(), EventApplyOutcome> {
+        match event {
+            NarrativeEvent::RelationshipChange { subject_id, target_id, .. } => {
+                for id in [subject_id, target_id] {
+                    if !is_known_entity(state, id) {
+                        return deferred(format!(
+                            "relationship_change refers to unknown '{}'; use 'player' or an existing NPC, party or faction id",
+                            id
+                        ));
+                    }
+                }
+            }
+            NarrativeEvent::FactionRepChange { id, .. } if !state.factions.contains_key(id) => {
+                return deferred(format!(
+                    "faction_rep_change refers to unknown faction '{}'; create it with faction_spawn first",
+                    id
+                ));
+            }
+            NarrativeEvent::UnequipItem { item_id } if !state.equipment.contains_key(item_id) => {
+                return deferred(format!("unequip_item: '{}' is not equipped", item_id));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_policy_holds_back_rewards_but_not_scene_events() {
+        let policy = EventReviewPolicy::default();
+        assert!(!policy.requires_review(&NarrativeEvent::TimePassed { minutes: 30, reason: None }));
+        assert!(policy.requires_review(&NarrativeEvent::AddExp { amount: 10 }));
+        assert!(policy.requires_review(&NarrativeEvent::CurrencyChange {
+            currency: "gold".to_string(),
+            delta: 5,
+        }));
+    }
+
+    #[test]
+    fn limits_accumulate_over_the_turn_and_references_must_exist() {
+        let mut state = InternalGameState::default();
+        state.currencies.insert("gold".to_string(), 10);
+        let limits = EventLimits {
+            max_stat_delta_per_turn: Some(5),
+            max_levels_per_turn: Some(1),
+            allowed_currencies: vec!["gold".to_string()],
+            ..EventLimits::default()
+        };
+        let mut validators = EventValidators::for_turn(&limits);
+        let mut check = |event: NarrativeEvent| validators.validate(&event, &state);
+
+        let stat = |delta| NarrativeEvent::ModifyStat { stat_id: "strength".to_string(), delta };
+        assert!(check(stat(3)).is_ok());
+        assert!(matches!(check(stat(3)), Err(EventApplyOutcome::Rejected { .. })));
+        assert!(check(stat(-6)).is_ok());
+
+        assert!(check(NarrativeEvent::LevelUp { levels: 50 }).is_err());
+        let currency = |currency: &str, delta| NarrativeEvent::CurrencyChange {
+            currency: currency.to_string(),
+            delta,
+        };
+        assert!(check(currency("gold", -10)).is_ok());
+        assert!(check(currency("gold", -11)).is_err());
+        assert!(check(currency("gems", 5)).is_err());
+        assert!(check(currency("GOLD", -11)).is_err(), "balance lookup ignores case");
+        assert!(check(currency("gold", i32::MAX)).is_err());
+        assert!(check(stat(i32::MAX)).is_err());
+
+        let rep = NarrativeEvent::FactionRepChange { id: "guild".to_string(), delta: 5 };
+        assert!(matches!(check(rep), Err(EventApplyOutcome::Deferred { .. })));
+        let relationship = NarrativeEvent::RelationshipChange {
+            subject_id: "player".to_string(),
+            target_id: "npc_ghost".to_string(),
+            delta: 1,
+            reason: None,
+        };
+        assert!(matches!(check(relationship), Err(EventApplyOutcome::Deferred { .. })));
+    }
+
+    #[test]
+    fn loyal_party_members_refuse_to_leave() {
+        let mut state = InternalGameState::default();
+        let leave = NarrativeEvent::NpcLeaveParty { id: "npc_bran".to_string() };
+        let mut world = WorldDefinition::default();
+        let mut validators = EventValidators::for_world(&world);
+        assert!(validators.validate(&leave, &state).is_ok());
+
+        world.loyal_morale = Some(20);
+        let mut validators = EventValidators::for_world(&world);
+        assert!(validators.validate(&leave, &state).is_ok());
+
+        state.relationships.insert(
+            "npc_bran::player".to_string(),
+            crate::model::game_state::Relationship {
+                subject_id: "npc_bran".to_string(),
+                target_id: "player".to_string(),
+                value: 25,
+                history: Vec::new(),
+                decay_from_minutes: 0,
+            },
+        );
+        assert!(matches!(
+            validators.validate(&leave, &state),
+            Err(EventApplyOutcome::Rejected { .. })
+        ));
+    }
+}
@@ -0,0 +1,238 @@
+use serde_json::Value;
+
+/// Convert the subset of JSON Schema used by the event schema into a GBNF
+/// grammar (llama.cpp / KoboldCpp `grammar`). Supports objects with fixed
+/// properties or `additionalProperties` maps, arrays, strings, integers,
+/// booleans, `const`, `enum` and `oneOf`. Properties are emitted in schema
+/// order: required ones always, optional ones each at most once.
+pub fn schema_to_gbnf(schema: &Value) -> Result<String, String> {
+    let mut builder = GrammarBuilder::default();
+    let root = builder.visit(schema, "root")?;
+    let mut out = format!("root ::= ws {} ws\n", root);
+    for (name, body) in &builder.rules {
+        out.push_str(&format!("{} ::= {}\n", name, body));
+    }
+    out.push_str(PRIMITIVES);
+    Ok(out)
+}
+
+const PRIMITIVES: &str = r#"ws ::= [ \t\n]*
+string ::= "\"" ( [^"\\\x00-\x1F] | "\\" ["\\/bfnrt] | "\\u" [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] )* "\""
+integer ::= "-"? ( "0" | [1-9] [0-9]* )
+positive-integer ::= [1-9] [0-9]*
+boolean ::= "true" | "false"
+"#;
+
+#[derive(Default)]
+struct GrammarBuilder {
+    rules: Vec<(String, String)>,
+}
+
+impl GrammarBuilder {
+    /// Returns a grammar expression for `schema`, adding named rules for
+    /// objects and alternatives so the output stays readable.
+    fn visit(&mut self, schema: &Value, name: &str) -> Result<String, String> {
+        if let Some(value) = schema.get("const") {
+            return Ok(literal(value));
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            let options: Vec<String> = values.iter().map(literal).collect();
+            return Ok(format!("( {} )", options.join(" | ")));
+        }
+        if let Some(options) = schema.get("oneOf").and_then(Value::as_array) {
+            let mut alternatives = Vec::new();
+            for (i, option) in options.iter().enumerate() {
+                let hint = option
+                    .pointer("/properties/type/const")
+                    .and_then(Value::as_str)
+                    .map(|t| format!("{}-{}", name, t))
+                    .unwrap_or_else(|| format!("{}-{}", name, i));
+                alternatives.push(self.visit(option, &hint)?);
+            }
+            return Ok(self.rule(name, alternatives.join(" | ")));
+        }
+
+        match schema.get("type").and_then(Value::as_str) {
+            Some("string") => Ok("string".to_string()),
+            Some("integer") => {
+                let positive = schema
+                    .get("minimum")
+                    .and_then(Value::as_i64)
+                    .is_some_and(|min| min >= 1);
+                Ok(if positive { "positive-integer" } else { "integer" }.to_string())
+            }
+            Some("boolean") => Ok("boolean".to_string()),
+            Some("array") => {
+                let item_name = if name == "root" {
+                    "item".to_string()
+                } else {
+                    format!("{}-item", name)
+                };
+                let item = match schema.get("items") {
+                    Some(items) => self.visit(items, &item_name)?,
+                    None => "string".to_string(),
+                };
+                let body = format!(
+                    "\"[\" ws ( {item} ( ws \",\" ws {item} )* )? ws \"]\"",
+                    item = item
+                );
+                if is_primitive(&item) {
+                    return Ok(self.rule(&format!("{}-array", item), body));
+                }
+                Ok(self.rule(name, body))
+            }
+            Some("object") => self.object(schema, name),
+            other => Err(format!("Unsupported schema type {:?} at '{}'", other, name)),
+        }
+    }
+
+    fn object(&mut self, schema: &Value, name: &str) -> Result<String, String> {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let Some(properties) = properties.filter(|p| !p.is_empty()) else {
+            // A map: { "key": value, ... }
+            let value = match schema.get("additionalProperties") {
+                Some(additional) if additional.is_object() => {
+                    self.visit(additional, &format!("{}-value", name))?
+                }
+                _ => return Err(format!("Object '{}' has no properties", name)),
+            };
+            let pair = format!("string ws \":\" ws {}", value);
+            let body = format!(
+                "\"{{\" ws ( {pair} ( ws \",\" ws {pair} )* )? ws \"}}\"",
+                pair = pair
+            );
+            if is_primitive(&value) {
+                return Ok(self.rule(&format!("{}-map", value), body));
+            }
+            return Ok(self.rule(name, body));
+        };
+
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut required_pairs = Vec::new();
+        let mut optional_pairs = Vec::new();
+        for (key, property) in properties {
+            let value = self.visit(property, &format!("{}-{}", name, key))?;
+            let pair = format!("{} ws \":\" ws {}", literal(&Value::String(key.clone())), value);
+            if required.contains(&key.as_str()) {
+                required_pairs.push((key.as_str(), pair));
+            } else {
+                optional_pairs.push(pair);
+            }
+        }
+        // Keep the schema's `required` order so "type" comes first.
+        required_pairs.sort_by_key(|(key, _)| required.iter().position(|r| r == key));
+
+        let body = if required_pairs.is_empty() {
+            let any = format!("( {} )", optional_pairs.join(" | "));
+            format!(
+                "\"{{\" ws ( {any} ( ws \",\" ws {any} )* )? ws \"}}\"",
+                any = any
+            )
+        } else {
+            let mut parts: Vec<String> = required_pairs.into_iter().map(|(_, pair)| pair).collect();
+            let mut body = format!("\"{{\" ws {}", parts.remove(0));
+            for pair in parts {
+                body.push_str(&format!(" ws \",\" ws {}", pair));
+            }
+            for pair in optional_pairs {
+                body.push_str(&format!(" ( ws \",\" ws {} )?", pair));
+            }
+            body.push_str(" ws \"}\"");
+            body
+        };
+        Ok(self.rule(name, body))
+    }
+
+    /// Name `body` as a rule, reusing an existing rule with the same body.
+    fn rule(&mut self, name: &str, body: String) -> String {
+        let name = rule_name(name);
+        if name == "root" {
+            // The root expression is emitted by `schema_to_gbnf` itself.
+            return format!("( {} )", body);
+        }
+        if let Some((existing, _)) = self.rules.iter().find(|(_, b)| *b == body) {
+            return existing.clone();
+        }
+        self.rules.push((name.clone(), body));
+        name
+    }
+}
+
+fn is_primitive(expr: &str) -> bool {
+    matches!(expr, "string" | "integer" | "positive-integer" | "boolean")
+}
+
+fn rule_name(hint: &str) -> String {
+    hint.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect()
+}
+
+/// A GBNF literal matching the JSON encoding of `value`.
+fn literal(value: &Value) -> String {
+    let json = value.to_string();
+    let escaped = json.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn event_grammar_defines_every_rule_it_references() {
+        let schema = crate::model::event_schema::events_json_schema();
+        let grammar = schema_to_gbnf(&schema).unwrap();
+
+        assert!(grammar.starts_with(r#"root ::= ws ( "[" ws ( item ( ws "," ws item )* )? ws "]" ) ws"#));
+        assert!(grammar.contains(r#"item-grant-power ::= "{" ws "\"type\"" ws ":" ws "\"grant_power\"""#));
+
+        let defined: HashSet<&str> = grammar
+            .lines()
+            .filter_map(|line| line.split_once(" ::= ").map(|(name, _)| name))
+            .collect();
+        for line in grammar.lines() {
+            let body = line.split_once(" ::= ").map(|(_, body)| body).unwrap_or_default();
+            // Drop quoted literals and character classes, leaving rule names.
+            let mut names = String::new();
+            let mut chars = body.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => {
+                        while let Some(c) = chars.next() {
+                            match c {
+                                '\\' => {
+                                    chars.next();
+                                }
+                                '"' => break,
+                                _ => {}
+                            }
+                        }
+                    }
+                    '[' => {
+                        while let Some(c) = chars.next() {
+                            match c {
+                                '\\' => {
+                                    chars.next();
+                                }
+                                ']' => break,
+                                _ => {}
+                            }
+                        }
+                    }
+                    c if c.is_ascii_alphanumeric() || c == '-' => names.push(c),
+                    _ => names.push(' '),
+                }
+            }
+            for name in names.split_whitespace() {
+                assert!(defined.contains(name), "undefined rule '{}' in: {}", name, line);
+            }
+        }
+    }
+}
@@ -1,12 +1,23 @@
 use serde::{Deserialize, Serialize};
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Response};
 use anyhow::{Result, anyhow};
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
 use std::time::Duration;
 
+use crate::model::event_schema::events_json_schema;
+
 #[derive(Clone, Copy, Debug)]
 pub enum LlmApiMode {
     OpenAiChat,
     KoboldCpp,
+    /// Ollama native `/api/chat`
+    Ollama,
+    /// llama.cpp server native `/completion`
+    LlamaCpp,
 }
 
 #[derive(Clone, Debug)]
@@ -16,17 +27,210 @@ pub struct LlmConfig {
     pub api_key: Option<String>,
     pub api_mode: LlmApiMode,
     pub use_structured_events: bool,
+    /// Send prompts as separate system/user/assistant messages instead of
+    /// one system message (chat backends only)
+    pub multi_message_prompts: bool,
+    pub generation: GenerationPresets,
+    /// Roles routed to another backend; the rest use the fields above
+    pub role_endpoints: Vec<(LlmRole, LlmEndpoint)>,
+}
+
+/// What a call is for. Each role can be sent to its own backend, e.g. a
+/// large model for narration and a small fast one for EVENTS JSON.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LlmRole {
+    /// The story turn
+    Narrator,
+    /// Structured EVENTS extraction
+    Events,
+    /// Story chapter summaries
+    Summarizer,
+    /// The turn re-run after a `request_context` event
+    Followup,
+}
+
+/// Where to send a call.
+#[derive(Clone, Debug)]
+pub struct LlmEndpoint {
+    pub base_url: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    pub api_mode: LlmApiMode,
+}
+
+impl LlmConfig {
+    /// This config with the backend for `role` swapped in, if one is set.
+    pub fn for_role(&self, role: LlmRole) -> LlmConfig {
+        let mut cfg = self.clone();
+        if let Some((_, endpoint)) = self.role_endpoints.iter().find(|(r, _)| *r == role) {
+            cfg.base_url = endpoint.base_url.clone();
+            cfg.model = endpoint.model.clone();
+            cfg.api_key = endpoint.api_key.clone();
+            cfg.api_mode = endpoint.api_mode;
+        }
+        cfg
+    }
+
+    /// True when prompts go out as structured chat messages. Completion
+    /// backends (KoboldCpp, llama.cpp) always get the single prompt text.
+    pub fn sends_chat_messages(&self) -> bool {
+        self.multi_message_prompts
+            && matches!(self.api_mode, LlmApiMode::OpenAiChat | LlmApiMode::Ollama)
+    }
+}
+
+/// Sampler settings for one kind of call. `None` leaves the backend's own
+/// default in place.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SamplingSettings {
+    pub temperature: f32,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub top_k: Option<u32>,
+    #[serde(default)]
+    pub min_p: Option<f32>,
+    #[serde(default)]
+    pub repetition_penalty: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// One per entry; `\n` is read as a newline
+    #[serde(default)]
+    pub stop: Vec<String>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl SamplingSettings {
+    /// Story narration (also used for follow-ups and summaries).
+    pub fn narration() -> Self {
+        Self {
+            temperature: 0.7,
+            top_p: None,
+            top_k: None,
+            min_p: None,
+            repetition_penalty: None,
+            max_tokens: None,
+            stop: Vec::new(),
+            seed: None,
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+
+    /// The structured EVENTS extraction pass: deterministic and short.
+    pub fn events() -> Self {
+        Self {
+            temperature: 0.0,
+            max_tokens: Some(800),
+            ..Self::narration()
+        }
+    }
+
+    /// Named starting points offered in the Options window.
+    pub fn presets() -> Vec<(&'static str, Self)> {
+        vec![
+            ("Balanced", Self::narration()),
+            (
+                "Creative",
+                Self {
+                    temperature: 1.0,
+                    top_p: Some(0.95),
+                    min_p: Some(0.05),
+                    repetition_penalty: Some(1.05),
+                    ..Self::narration()
+                },
+            ),
+            (
+                "Precise",
+                Self {
+                    temperature: 0.3,
+                    top_p: Some(0.9),
+                    top_k: Some(40),
+                    ..Self::narration()
+                },
+            ),
+            ("Deterministic", Self::events()),
+        ]
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.max(1))
+    }
+
+    /// Request fields for `mode`, using that backend's parameter names.
+    /// The OpenAI API has no top-k, min-p or multiplicative repetition
+    /// penalty and rejects unknown fields, so those are left out there.
+    fn params(&self, mode: LlmApiMode) -> serde_json::Map<String, serde_json::Value> {
+        let (repetition_penalty, max_tokens, stop, seed) = match mode {
+            LlmApiMode::OpenAiChat => ("repetition_penalty", "max_tokens", "stop", "seed"),
+            LlmApiMode::KoboldCpp => ("rep_pen", "max_length", "stop_sequence", "sampler_seed"),
+            LlmApiMode::Ollama => ("repeat_penalty", "num_predict", "stop", "seed"),
+            LlmApiMode::LlamaCpp => ("repeat_penalty", "n_predict", "stop", "seed"),
+        };
+        let openai = matches!(mode, LlmApiMode::OpenAiChat);
+        let mut params = serde_json::Map::new();
+        params.insert("temperature".into(), self.temperature.into());
+        let optional = [
+            ("top_p", self.top_p.map(serde_json::Value::from)),
+            ("top_k", self.top_k.filter(|_| !openai).map(serde_json::Value::from)),
+            ("min_p", self.min_p.filter(|_| !openai).map(serde_json::Value::from)),
+            (
+                repetition_penalty,
+                self.repetition_penalty.filter(|_| !openai).map(serde_json::Value::from),
+            ),
+            (max_tokens, self.max_tokens.map(serde_json::Value::from)),
+            (seed, self.seed.map(serde_json::Value::from)),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                params.insert(key.into(), value);
+            }
+        }
+        let stops: Vec<String> = self
+            .stop
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.replace("\\n", "\n"))
+            .collect();
+        if !stops.is_empty() {
+            params.insert(stop.into(), stops.into());
+        }
+        params
+    }
+}
+
+/// Sampling for the narration call and the structured EVENTS call.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationPresets {
+    #[serde(default = "SamplingSettings::narration")]
+    pub narration: SamplingSettings,
+    #[serde(default = "SamplingSettings::events")]
+    pub events: SamplingSettings,
+}
+
+impl Default for GenerationPresets {
+    fn default() -> Self {
+        Self {
+            narration: SamplingSettings::narration(),
+            events: SamplingSettings::events(),
+        }
+    }
 }
 
 #[derive(Serialize)]
 pub struct ChatCompletionRequest {
     pub model: String,
     pub messages: Vec<ChatMessage>,
-    pub temperature: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response_format: Option<ResponseFormat>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_tokens: Option<u32>,
+    #[serde(flatten)]
+    pub sampling: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize)]
@@ -43,12 +247,22 @@ pub struct JsonSchemaWrapper {
     pub schema: serde_json::Value,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
 }
 
+impl ChatMessage {
+    pub fn system(content: String) -> Self {
+        Self { role: "system".into(), content }
+    }
+
+    pub fn user(content: String) -> Self {
+        Self { role: "user".into(), content }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ChatCompletionResponse {
     pub choices: Vec<Choice>,
@@ -67,9 +281,11 @@ pub struct ChatMessageResponse {
 #[derive(Serialize)]
 pub struct KoboldGenerateRequest {
     pub prompt: String,
-    pub temperature: f32,
+    /// GBNF grammar the output must follow
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_length: Option<u32>,
+    pub grammar: Option<String>,
+    #[serde(flatten)]
+    pub sampling: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Deserialize)]
@@ -82,24 +298,118 @@ pub struct KoboldGenerateResult {
     pub text: String,
 }
 
-pub fn call_llm(prompt: String, cfg: &LlmConfig) -> anyhow::Result<String> {
+#[derive(Serialize)]
+pub struct OllamaChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub stream: bool,
+    /// JSON schema the reply must follow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<serde_json::Value>,
+    /// Sampler settings (`temperature`, `num_predict`, ...)
+    pub options: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+pub struct OllamaChatResponse {
+    #[serde(default)]
+    pub message: Option<ChatMessageResponse>,
+    #[serde(default)]
+    pub done: bool,
+    /// Set instead of `message` when the generation fails
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LlamaCppCompletionRequest {
+    pub prompt: String,
+    pub stream: bool,
+    /// GBNF grammar the output must follow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grammar: Option<String>,
+    #[serde(flatten)]
+    pub sampling: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+pub struct LlamaCppCompletionResponse {
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub stop: bool,
+}
+
+/// Result of `test_connection`: a status line plus the models the backend
+/// reports, for the model dropdown.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionStatus {
+    pub message: String,
+    pub models: Vec<String>,
+}
+
+/// Stop button shared by the UI and the engine. Each call takes a token
+/// when it starts; `stop` aborts every token taken before it, and calls
+/// started afterwards run normally.
+#[derive(Clone, Debug, Default)]
+pub struct AbortSignal(Arc<AtomicU64>);
+
+impl AbortSignal {
+    pub fn stop(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn token(&self) -> AbortToken {
+        AbortToken {
+            signal: self.clone(),
+            epoch: self.0.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// One call's view of an `AbortSignal`.
+#[derive(Clone, Debug)]
+pub struct AbortToken {
+    signal: AbortSignal,
+    epoch: u64,
+}
+
+impl AbortToken {
+    /// For background calls the Stop button should not cancel.
+    pub fn never() -> Self {
+        AbortSignal::default().token()
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.signal.0.load(Ordering::SeqCst) != self.epoch
+    }
+}
+
+/// How often a stream waiting on the backend checks its abort token.
+const ABORT_POLL: Duration = Duration::from_millis(100);
+
+/// Generate narration. Chat backends receive `messages` as is; completion
+/// backends get their contents joined into one prompt. Streaming backends
+/// stop reading once `abort` fires, which closes the connection; Ollama and
+/// llama.cpp cancel the generation when the client disconnects.
+pub fn call_llm(
+    messages: Vec<ChatMessage>,
+    cfg: &LlmConfig,
+    abort: &AbortToken,
+) -> anyhow::Result<String> {
+    let settings = &cfg.generation.narration;
     let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(60))
+        .timeout(settings.timeout())
         .build()?;
+    let sampling = settings.params(cfg.api_mode);
 
     match cfg.api_mode {
         LlmApiMode::OpenAiChat => {
             let req = ChatCompletionRequest {
                 model: cfg.model.clone(),
-                temperature: 0.7,
                 response_format: None,
-                max_tokens: None,
-                messages: vec![
-                    ChatMessage {
-                        role: "system".into(),
-                        content: prompt,
-                    }
-                ],
+                sampling,
+                messages,
             };
 
             let url = join_url(&cfg.base_url, "chat/completions");
@@ -109,23 +419,112 @@ pub fn call_llm(prompt: String, cfg: &LlmConfig) -> anyhow::Result<String> {
             }
 
             let resp = request.send()?.json::<ChatCompletionResponse>()?;
-            let first = resp.choices.get(0).ok_or_else(|| anyhow!("LLM returned no choices"))?;
+            let first = resp.choices.first().ok_or_else(|| anyhow!("LLM returned no choices"))?;
             Ok(first.message.content.clone())
         }
         LlmApiMode::KoboldCpp => {
             let req = KoboldGenerateRequest {
-                prompt,
-                temperature: 0.7,
-                max_length: None,
+                prompt: join_messages(messages),
+                grammar: None,
+                sampling,
             };
             let url = join_url(&cfg.base_url, "api/v1/generate");
             let resp = client.post(url).json(&req).send()?.json::<KoboldGenerateResponse>()?;
             let first = resp
                 .results
-                .get(0)
+                .first()
                 .ok_or_else(|| anyhow!("KoboldCpp returned no results"))?;
             Ok(first.text.clone())
         }
+        LlmApiMode::Ollama => {
+            let req = OllamaChatRequest {
+                model: cfg.model.clone(),
+                messages,
+                stream: true,
+                format: None,
+                options: sampling,
+            };
+            let url = join_url(&cfg.base_url, "api/chat");
+            let resp = client.post(url).json(&req).send()?.error_for_status()?;
+            let mut text = String::new();
+            read_stream(resp, abort, |line| {
+                let chunk: OllamaChatResponse = serde_json::from_str(line)?;
+                if let Some(error) = chunk.error {
+                    return Err(anyhow!("Ollama error: {}", error));
+                }
+                if let Some(message) = chunk.message {
+                    text.push_str(&message.content);
+                }
+                Ok(chunk.done)
+            })?;
+            Ok(text)
+        }
+        LlmApiMode::LlamaCpp => {
+            let req = LlamaCppCompletionRequest {
+                prompt: join_messages(messages),
+                stream: true,
+                grammar: None,
+                sampling,
+            };
+            let url = join_url(&cfg.base_url, "completion");
+            let resp = client.post(url).json(&req).send()?.error_for_status()?;
+            let mut text = String::new();
+            read_stream(resp, abort, |line| {
+                let Some(data) = line.strip_prefix("data:") else {
+                    return Ok(false);
+                };
+                let chunk: LlamaCppCompletionResponse = serde_json::from_str(data.trim())?;
+                text.push_str(&chunk.content);
+                Ok(chunk.stop)
+            })?;
+            Ok(text)
+        }
+    }
+}
+
+fn join_messages(messages: Vec<ChatMessage>) -> String {
+    messages
+        .into_iter()
+        .map(|m| m.content)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Feed non-empty lines of a streamed body to `on_line` until it reports
+/// the end of the stream. Lines are read on a helper thread so a backend
+/// that goes quiet cannot hold off `abort`; the helper exits, closing the
+/// connection, as soon as it reads past an abort.
+fn read_stream(
+    resp: Response,
+    abort: &AbortToken,
+    mut on_line: impl FnMut(&str) -> Result<bool>,
+) -> Result<()> {
+    let (line_tx, line_rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(resp).lines() {
+            let failed = line.is_err();
+            if line_tx.send(line).is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    loop {
+        if abort.is_aborted() {
+            return Err(anyhow!("Generation aborted"));
+        }
+        let line = match line_rx.recv_timeout(ABORT_POLL) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if on_line(line)? {
+            return Ok(());
+        }
     }
 }
 
@@ -134,28 +533,119 @@ pub fn call_llm_events_structured(
     raw_events: &str,
     cfg: &LlmConfig,
 ) -> anyhow::Result<String> {
-    if !matches!(cfg.api_mode, LlmApiMode::OpenAiChat) {
-        return Err(anyhow!("Structured output is only supported for OpenAI-compatible mode"));
-    }
-
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(60))
-        .build()?;
-
-    let schema_value: serde_json::Value =
-        serde_json::from_str(EVENTS_SCHEMA).map_err(|e| anyhow!(e))?;
-
     let user_payload = format!(
         "NARRATIVE:\n{}\n\nRAW EVENTS (may be invalid):\n{}\n\nReturn ONLY the corrected EVENTS JSON array. Do not invent events.",
         narrative.trim(),
         raw_events.trim()
     );
 
+    let system = "You normalize the RAW EVENTS into a valid JSON array that matches the schema. Use the narrative only to resolve ambiguity. Never invent new events.";
+
+    call_events_json(system, user_payload, true, cfg)
+}
+
+/// Ask for a corrected EVENTS array after `events_json` failed to decode.
+/// The reply is schema/grammar constrained when structured EVENTS are on.
+pub fn call_llm_events_repair(
+    events_json: &str,
+    errors: &[String],
+    cfg: &LlmConfig,
+) -> anyhow::Result<String> {
+    let user_payload = format!(
+        "EVENTS:\n{}\n\nERRORS:\n- {}\n\nReturn ONLY the corrected EVENTS JSON array.",
+        events_json.trim(),
+        errors.join("\n- ")
+    );
+
+    let system = "You fix EVENTS JSON that failed to parse. Correct only what the errors point at: syntax, field names and value types. Keep every event's meaning. Never add new events. Drop an event only if it cannot be fixed.";
+
+    call_events_json(system, user_payload, cfg.use_structured_events, cfg)
+}
+
+/// Send an EVENTS-only request with the `events` sampling preset. When
+/// `constrained`, the reply must follow the EVENTS schema (or its grammar).
+fn call_events_json(
+    system: &str,
+    user_payload: String,
+    constrained: bool,
+    cfg: &LlmConfig,
+) -> anyhow::Result<String> {
+    let settings = &cfg.generation.events;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(settings.timeout())
+        .build()?;
+    let sampling = settings.params(cfg.api_mode);
+
+    let schema_value = events_json_schema();
+    let grammar = if constrained { Some(events_grammar()?) } else { None };
+
+    match cfg.api_mode {
+        LlmApiMode::Ollama => {
+            let req = OllamaChatRequest {
+                model: cfg.model.clone(),
+                messages: vec![
+                    ChatMessage::system(system.to_string()),
+                    ChatMessage::user(user_payload),
+                ],
+                stream: false,
+                format: constrained.then_some(schema_value),
+                options: sampling,
+            };
+            let url = join_url(&cfg.base_url, "api/chat");
+            let resp = client
+                .post(url)
+                .json(&req)
+                .send()?
+                .error_for_status()?
+                .json::<OllamaChatResponse>()?;
+            if let Some(error) = resp.error {
+                return Err(anyhow!("Ollama error: {}", error));
+            }
+            let message = resp.message.ok_or_else(|| anyhow!("Ollama returned no message"))?;
+            return Ok(message.content);
+        }
+        LlmApiMode::KoboldCpp => {
+            let req = KoboldGenerateRequest {
+                prompt: format!("{}\n\n{}\n", system, user_payload),
+                grammar,
+                sampling,
+            };
+            let url = join_url(&cfg.base_url, "api/v1/generate");
+            let resp = client
+                .post(url)
+                .json(&req)
+                .send()?
+                .error_for_status()?
+                .json::<KoboldGenerateResponse>()?;
+            let first = resp
+                .results
+                .first()
+                .ok_or_else(|| anyhow!("KoboldCpp returned no results"))?;
+            return Ok(first.text.clone());
+        }
+        LlmApiMode::LlamaCpp => {
+            let req = LlamaCppCompletionRequest {
+                prompt: format!("{}\n\n{}\n", system, user_payload),
+                stream: false,
+                grammar,
+                sampling,
+            };
+            let url = join_url(&cfg.base_url, "completion");
+            let resp = client
+                .post(url)
+                .json(&req)
+                .send()?
+                .error_for_status()?
+                .json::<LlamaCppCompletionResponse>()?;
+            return Ok(resp.content);
+        }
+        LlmApiMode::OpenAiChat => {}
+    }
+
     let req = ChatCompletionRequest {
         model: cfg.model.clone(),
-        temperature: 0.0,
-        max_tokens: Some(800),
-        response_format: Some(ResponseFormat {
+        sampling,
+        response_format: constrained.then(|| ResponseFormat {
             format_type: "json_schema".to_string(),
             json_schema: JsonSchemaWrapper {
                 name: "events_only".to_string(),
@@ -164,14 +654,8 @@ pub fn call_llm_events_structured(
             },
         }),
         messages: vec![
-            ChatMessage {
-                role: "system".into(),
-                content: "You normalize the RAW EVENTS into a valid JSON array that matches the schema. Use the narrative only to resolve ambiguity. Never invent new events.".to_string(),
-            },
-            ChatMessage {
-                role: "user".into(),
-                content: user_payload,
-            },
+            ChatMessage::system(system.to_string()),
+            ChatMessage::user(user_payload),
         ],
     };
 
@@ -182,463 +666,16 @@ pub fn call_llm_events_structured(
     }
 
     let resp = request.send()?.json::<ChatCompletionResponse>()?;
-    let first = resp.choices.get(0).ok_or_else(|| anyhow!("LLM returned no choices"))?;
+    let first = resp.choices.first().ok_or_else(|| anyhow!("LLM returned no choices"))?;
     Ok(first.message.content.clone())
 }
 
-const EVENTS_SCHEMA: &str = r#"{
-  "$schema": "https://json-schema.org/draft/2020-12/schema",
-  "title": "NarrativeEvents",
-  "type": "array",
-  "items": {
-    "oneOf": [
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id", "name", "description"],
-        "properties": {
-          "type": { "const": "grant_power" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "description": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "description"],
-        "properties": {
-          "type": { "const": "combat" },
-          "description": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "speaker", "text"],
-        "properties": {
-          "type": { "const": "dialogue" },
-          "speaker": { "type": "string" },
-          "text": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "from", "to"],
-        "properties": {
-          "type": { "const": "travel" },
-          "from": { "type": "string" },
-          "to": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "description"],
-        "properties": {
-          "type": { "const": "rest" },
-          "description": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "recipe"],
-        "properties": {
-          "type": { "const": "craft" },
-          "recipe": { "type": "string" },
-          "quantity": { "type": "integer", "minimum": 1 },
-          "quality": { "type": "string" },
-          "result": { "type": "string" },
-          "set_id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "resource"],
-        "properties": {
-          "type": { "const": "gather" },
-          "resource": { "type": "string" },
-          "quantity": { "type": "integer", "minimum": 1 },
-          "quality": { "type": "string" },
-          "set_id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id", "name", "role"],
-        "properties": {
-          "type": { "const": "add_party_member" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "role": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id"],
-        "properties": {
-          "type": { "const": "party_update" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "role": { "type": "string" },
-          "details": { "type": "string" },
-          "clothing_add": { "type": "array", "items": { "type": "string" } },
-          "clothing_remove": { "type": "array", "items": { "type": "string" } },
-          "weapons_add": { "type": "array", "items": { "type": "string" } },
-          "weapons_remove": { "type": "array", "items": { "type": "string" } },
-          "armor_add": { "type": "array", "items": { "type": "string" } },
-          "armor_remove": { "type": "array", "items": { "type": "string" } }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "section", "id", "name"],
-        "properties": {
-          "type": { "const": "section_card_upsert" },
-          "section": { "type": "string" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "role": { "type": "string" },
-          "status": { "type": "string" },
-          "details": { "type": "string" },
-          "notes": { "type": "string" },
-          "tags": { "type": "array", "items": { "type": "string" } },
-          "items": { "type": "array", "items": { "type": "string" } }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "section", "id"],
-        "properties": {
-          "type": { "const": "section_card_remove" },
-          "section": { "type": "string" },
-          "id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type"],
-        "properties": {
-          "type": { "const": "player_card_update" },
-          "name": { "type": "string" },
-          "role": { "type": "string" },
-          "status": { "type": "string" },
-          "details": { "type": "string" },
-          "notes": { "type": "string" },
-          "tags": { "type": "array", "items": { "type": "string" } },
-          "items": { "type": "array", "items": { "type": "string" } }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "minutes"],
-        "properties": {
-          "type": { "const": "time_passed" },
-          "minutes": { "type": "integer", "minimum": 1 },
-          "reason": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "name", "role"],
-        "properties": {
-          "type": { "const": "npc_spawn" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "role": { "type": "string" },
-          "details": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type"],
-        "properties": {
-          "type": { "const": "npc_join_party" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "role": { "type": "string" },
-          "details": { "type": "string" },
-          "clothing": { "type": "array", "items": { "type": "string" } },
-          "weapons": { "type": "array", "items": { "type": "string" } },
-          "armor": { "type": "array", "items": { "type": "string" } }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type"],
-        "properties": {
-          "type": { "const": "npc_update" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "role": { "type": "string" },
-          "details": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id"],
-        "properties": {
-          "type": { "const": "npc_despawn" },
-          "id": { "type": "string" },
-          "reason": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id"],
-        "properties": {
-          "type": { "const": "npc_leave_party" },
-          "id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "subject_id", "target_id", "delta"],
-        "properties": {
-          "type": { "const": "relationship_change" },
-          "subject_id": { "type": "string" },
-          "target_id": { "type": "string" },
-          "delta": { "type": "integer" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "stat_id", "delta"],
-        "properties": {
-          "type": { "const": "modify_stat" },
-          "stat_id": { "type": "string" },
-          "delta": { "type": "integer" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "amount"],
-        "properties": {
-          "type": { "const": "add_exp" },
-          "amount": { "type": "integer", "minimum": 1 }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "levels"],
-        "properties": {
-          "type": { "const": "level_up" },
-          "levels": { "type": "integer", "minimum": 1 }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "item_id", "slot"],
-        "properties": {
-          "type": { "const": "equip_item" },
-          "item_id": { "type": "string" },
-          "slot": { "type": "string" },
-          "set_id": { "type": "string" },
-          "description": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "item_id"],
-        "properties": {
-          "type": { "const": "unequip_item" },
-          "item_id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id", "title", "description"],
-        "properties": {
-          "type": { "const": "start_quest" },
-          "id": { "type": "string" },
-          "title": { "type": "string" },
-          "description": { "type": "string" },
-          "difficulty": { "type": "string" },
-          "negotiable": { "type": "boolean" },
-          "reward_options": { "type": "array", "items": { "type": "string" } },
-          "rewards": { "type": "array", "items": { "type": "string" } },
-          "sub_quests": {
-            "type": "array",
-            "items": {
-              "type": "object",
-              "additionalProperties": false,
-              "required": ["id", "description"],
-              "properties": {
-                "id": { "type": "string" },
-                "description": { "type": "string" },
-                "completed": { "type": "boolean" }
-              }
-            }
-          },
-          "declinable": { "type": "boolean" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id"],
-        "properties": {
-          "type": { "const": "update_quest" },
-          "id": { "type": "string" },
-          "title": { "type": "string" },
-          "description": { "type": "string" },
-          "status": { "type": "string", "enum": ["active", "completed", "failed"] },
-          "difficulty": { "type": "string" },
-          "negotiable": { "type": "boolean" },
-          "reward_options": { "type": "array", "items": { "type": "string" } },
-          "rewards": { "type": "array", "items": { "type": "string" } },
-          "sub_quests": {
-            "type": "array",
-            "items": {
-              "type": "object",
-              "additionalProperties": false,
-              "required": ["id"],
-              "properties": {
-                "id": { "type": "string" },
-                "description": { "type": "string" },
-                "completed": { "type": "boolean" }
-              }
-            }
-          }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "topics"],
-        "properties": {
-          "type": { "const": "request_context" },
-          "topics": {
-            "oneOf": [
-              { "type": "string" },
-              { "type": "array", "items": { "type": "string" } }
-            ]
-          }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "flag"],
-        "properties": {
-          "type": { "const": "set_flag" },
-          "flag": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "reason"],
-        "properties": {
-          "type": { "const": "request_retcon" },
-          "reason": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "item_id", "quantity"],
-        "properties": {
-          "type": { "const": "add_item" },
-          "item_id": { "type": "string" },
-          "quantity": { "type": "integer", "minimum": 1 },
-          "set_id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "item"],
-        "properties": {
-          "type": { "const": "drop" },
-          "item": { "type": "string" },
-          "quantity": { "type": "integer" },
-          "description": { "type": "string" },
-          "set_id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "item"],
-        "properties": {
-          "type": { "const": "spawn_loot" },
-          "item": { "type": "string" },
-          "quantity": { "type": "integer" },
-          "description": { "type": "string" },
-          "set_id": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "currency", "delta"],
-        "properties": {
-          "type": { "const": "currency_change" },
-          "currency": { "type": "string" },
-          "delta": { "type": "integer" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id", "name"],
-        "properties": {
-          "type": { "const": "faction_spawn" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "kind": { "type": "string" },
-          "description": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id"],
-        "properties": {
-          "type": { "const": "faction_update" },
-          "id": { "type": "string" },
-          "name": { "type": "string" },
-          "kind": { "type": "string" },
-          "description": { "type": "string" }
-        }
-      },
-      {
-        "type": "object",
-        "additionalProperties": false,
-        "required": ["type", "id", "delta"],
-        "properties": {
-          "type": { "const": "faction_rep_change" },
-          "id": { "type": "string" },
-          "delta": { "type": "integer" }
-        }
-      }
-    ]
-  }
-}"#;
+/// GBNF form of the EVENTS schema for KoboldCpp and llama.cpp.
+pub fn events_grammar() -> Result<String> {
+    crate::engine::gbnf::schema_to_gbnf(&events_json_schema()).map_err(|e| anyhow!(e))
+}
 
-pub fn test_connection(cfg: &LlmConfig) -> Result<String> {
+pub fn test_connection(cfg: &LlmConfig) -> Result<ConnectionStatus> {
     let client = Client::builder()
         .timeout(Duration::from_secs(10))
         .build()?;
@@ -651,11 +688,12 @@ pub fn test_connection(cfg: &LlmConfig) -> Result<String> {
                 request = request.bearer_auth(key);
             }
             let resp: serde_json::Value = request.send()?.json()?;
+            let models = string_field(&resp["data"], "id");
 
-            Ok(format!(
-                "Connected ({} models available)",
-                resp["data"].as_array().map(|a| a.len()).unwrap_or(0)
-            ))
+            Ok(ConnectionStatus {
+                message: format!("Connected ({} models available)", models.len()),
+                models,
+            })
         }
         LlmApiMode::KoboldCpp => {
             let url = join_url(&cfg.base_url, "api/v1/model");
@@ -663,11 +701,56 @@ pub fn test_connection(cfg: &LlmConfig) -> Result<String> {
             let name = resp["result"]
                 .as_str()
                 .unwrap_or("KoboldCpp");
-            Ok(format!("Connected ({})", name))
+            Ok(ConnectionStatus {
+                message: format!("Connected ({})", name),
+                models: vec![name.to_string()],
+            })
+        }
+        LlmApiMode::Ollama => {
+            let url = join_url(&cfg.base_url, "api/tags");
+            let resp: serde_json::Value = client.get(url).send()?.error_for_status()?.json()?;
+            let models = string_field(&resp["models"], "name");
+            Ok(ConnectionStatus {
+                message: format!("Connected to Ollama ({} models installed)", models.len()),
+                models,
+            })
+        }
+        LlmApiMode::LlamaCpp => {
+            // /health answers 503 while the model is still loading
+            let url = join_url(&cfg.base_url, "health");
+            let health = client.get(url).send()?;
+            if !health.status().is_success() {
+                return Err(anyhow!("llama.cpp server not ready ({})", health.status()));
+            }
+            let url = join_url(&cfg.base_url, "v1/models");
+            let models = client
+                .get(url)
+                .send()
+                .and_then(|resp| resp.json::<serde_json::Value>())
+                .map(|resp| string_field(&resp["data"], "id"))
+                .unwrap_or_default();
+            let name = models.first().cloned().unwrap_or_else(|| "llama.cpp".to_string());
+            Ok(ConnectionStatus {
+                message: format!("Connected ({})", name),
+                models,
+            })
         }
     }
 }
 
+/// Collect `field` from each object in a JSON array.
+fn string_field(list: &serde_json::Value, field: &str) -> Vec<String> {
+    list.as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item[field].as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 pub fn abort_generation(cfg: &LlmConfig) -> Result<()> {
     match cfg.api_mode {
         LlmApiMode::OpenAiChat => Ok(()),
@@ -679,6 +762,8 @@ pub fn abort_generation(cfg: &LlmConfig) -> Result<()> {
             let _ = client.post(url).send()?;
             Ok(())
         }
+        // Their streams stop on the call's abort token.
+        LlmApiMode::Ollama | LlmApiMode::LlamaCpp => Ok(()),
     }
 }
 
@@ -1,9 +1,13 @@
+#[allow(clippy::module_inception)]
 pub mod engine;
 pub mod protocol;
 pub mod apply_event;
+pub mod event_validator;
 
 pub mod prompt_builder;
+pub mod prompt_templates;
 pub mod llm_client;
+pub mod gbnf;
 pub mod narrative_parser;
 
 
@@ -1,4 +1,11 @@
+use crate::engine::llm_client::ChatMessage;
+use crate::engine::prompt_templates::{render_section, FREEFORM_SYSTEM_SECTIONS, GAME_SYSTEM_SECTIONS};
+use crate::model::calendar::format_elapsed;
 use crate::model::game_context::GameContext;
+use crate::model::game_state::{
+    party_morale, Npc, NpcExchange, NpcMemory, QuestStatus, ReputationTier, StoryChapter,
+};
+use crate::model::lorebook::contains_word;
 use crate::model::message::{Message, RoleplaySpeaker};
 
 /// Builds the full prompt sent to the LLM.
@@ -6,8 +13,56 @@ use crate::model::message::{Message, RoleplaySpeaker};
 /// No parsing, no networking, no engine logic.
 pub struct PromptBuilder;
 
+/// Prompt text plus the size accounting done while assembling it.
+pub struct BuiltPrompt {
+    pub text: String,
+    /// The same sections laid out as chat messages: the system rules and
+    /// world definition, the history as alternating user/assistant turns,
+    /// then the current turn as a user message
+    pub messages: Vec<ChatMessage>,
+    pub estimated_tokens: usize,
+    /// Tokens available to the prompt once the response reserve is taken
+    /// out of the context window; None when no window is configured.
+    pub budget: Option<usize>,
+    /// One note per section that was shrunk or dropped to fit the budget
+    pub trimmed: Vec<String>,
+    /// Template overrides that failed to render (their defaults were used)
+    pub warnings: Vec<String>,
+}
+
+impl BuiltPrompt {
+    /// Messages to send: the structured layout, or the whole prompt as one
+    /// system message.
+    pub fn chat_messages(&self, multi_message: bool) -> Vec<ChatMessage> {
+        if multi_message {
+            self.messages.clone()
+        } else {
+            vec![ChatMessage::system(self.text.clone())]
+        }
+    }
+
+    /// Debug line describing what was cut, if anything had to give.
+    pub fn trim_report(&self) -> Option<String> {
+        let budget = self.budget?;
+        if self.trimmed.is_empty() && self.estimated_tokens <= budget {
+            return None;
+        }
+        let mut report = format!(
+            "Prompt ~{} tokens (budget {}).",
+            self.estimated_tokens, budget
+        );
+        if !self.trimmed.is_empty() {
+            report.push_str(&format!(" Trimmed: {}.", self.trimmed.join(", ")));
+        }
+        if self.estimated_tokens > budget {
+            report.push_str(" Still over budget; raise the context window or shorten the world text.");
+        }
+        Some(report)
+    }
+}
+
 impl PromptBuilder {
-    pub fn build(context: &GameContext, player_input: &str) -> String {
+    pub fn build(context: &GameContext, player_input: &str) -> BuiltPrompt {
         if context.world.is_rpg_world {
             GamePromptBuilder::build(context, player_input)
         } else {
@@ -20,7 +75,7 @@ impl PromptBuilder {
         player_input: &str,
         requested_context: &str,
         recent_history: &[Message],
-    ) -> String {
+    ) -> BuiltPrompt {
         if context.world.is_rpg_world {
             GamePromptBuilder::build_with_requested_context(
                 context,
@@ -37,78 +92,112 @@ impl PromptBuilder {
             )
         }
     }
-}
 
-struct GamePromptBuilder;
-
-impl GamePromptBuilder {
-    pub fn build(context: &GameContext, player_input: &str) -> String {
+    /// Prompt for the background summarizer: condense `messages` into one
+    /// chapter, using the previous chapter only for continuity.
+    pub fn build_story_summary(previous_chapter: Option<&str>, messages: &[Message]) -> String {
         let mut prompt = String::new();
 
-        push_game_system_prompt(&mut prompt, context, false);
-        push_world_definition(&mut prompt, context, true);
-        push_party_section(&mut prompt, context);
-        push_npc_registry(&mut prompt, context);
-        push_time_section(&mut prompt, context);
-        push_history_section(&mut prompt, &context.history, "NARRATIVE HISTORY");
-        push_current_situation(&mut prompt, context);
-        push_power_use_intent(&mut prompt, player_input);
-        push_player_action(&mut prompt, player_input);
-        push_game_reminder(&mut prompt, false);
+        prompt.push_str("You condense part of an ongoing roleplay story into a chapter summary.\n");
+        prompt.push_str("The summary replaces these messages in future prompts, so keep what matters later:\n");
+        prompt.push_str("- Key events and the player's decisions\n");
+        prompt.push_str("- Named characters, what they did, and how they feel about the player\n");
+        prompt.push_str("- Places visited, items gained or lost, promises and debts\n");
+        prompt.push_str("- Unresolved threads\n");
+        prompt.push_str("Write past tense, third person, at most 150 words.\n");
+        prompt.push_str("Output ONLY the summary text. No headings, no NARRATIVE or EVENTS blocks.\n\n");
+
+        if let Some(previous) = previous_chapter.filter(|p| !p.trim().is_empty()) {
+            prompt.push_str("PREVIOUS CHAPTER (for continuity, do not repeat it):\n");
+            prompt.push_str(previous.trim());
+            prompt.push_str("\n\n");
+        }
+
+        prompt.push_str("MESSAGES TO SUMMARIZE:\n");
+        push_history_lines(&mut prompt, messages);
 
         prompt
     }
 
-    pub fn build_with_requested_context(
-        context: &GameContext,
-        player_input: &str,
-        requested_context: &str,
-        recent_history: &[Message],
+    /// Prompt that folds an NPC's older exchanges into their memory lists.
+    /// The engine reads back only the `FACT:`, `TOLD:` and `PROMISE:` lines.
+    pub fn build_npc_memory_summary(
+        name: &str,
+        memory: &NpcMemory,
+        exchanges: &[NpcExchange],
     ) -> String {
         let mut prompt = String::new();
 
-        push_game_system_prompt(&mut prompt, context, true);
-        push_world_definition(&mut prompt, context, true);
-        push_party_section(&mut prompt, context);
-        push_npc_registry(&mut prompt, context);
-        push_time_section(&mut prompt, context);
-        push_current_situation(&mut prompt, context);
-
-        if !requested_context.trim().is_empty() {
-            prompt.push_str("REQUESTED CONTEXT:\n");
-            prompt.push_str(requested_context);
-            prompt.push_str("\n\n");
+        prompt.push_str(&format!(
+            "You update what {} remembers about the player in an ongoing roleplay.\n",
+            name
+        ));
+        prompt.push_str("The exchanges below are about to be forgotten. Keep only what matters later, one item per line:\n");
+        prompt.push_str(&format!("FACT: something {} learned or that happened between them\n", name));
+        prompt.push_str(&format!("TOLD: something the player told {} about themselves or the world\n", name));
+        prompt.push_str("PROMISE: a promise or deal between them that is still open\n");
+        prompt.push_str("Skip small talk and anything already listed under ALREADY KNOWN. At most 6 lines.\n");
+        prompt.push_str("Output ONLY these lines. Output nothing if none of it matters.\n\n");
+
+        if !memory.key_facts.is_empty() || !memory.player_told.is_empty() || !memory.promises.is_empty() {
+            prompt.push_str("ALREADY KNOWN:\n");
+            push_memory_list(&mut prompt, "Knows", &memory.key_facts);
+            push_memory_list(&mut prompt, "Player told them", &memory.player_told);
+            push_memory_list(&mut prompt, "Promises", &memory.promises);
+            prompt.push('\n');
         }
 
-        if !recent_history.is_empty() {
-            prompt.push_str("RECENT HISTORY:\n");
-            push_history_lines(&mut prompt, recent_history);
+        prompt.push_str("EXCHANGES TO SUMMARIZE:\n");
+        for exchange in exchanges {
+            prompt.push_str(&format!("- {}\n", exchange.describe(name)));
         }
 
-        push_power_use_intent(&mut prompt, player_input);
-        push_player_action(&mut prompt, player_input);
-        push_game_reminder(&mut prompt, true);
-
         prompt
     }
 }
 
-struct FreeformPromptBuilder;
-
-impl FreeformPromptBuilder {
-    pub fn build(context: &GameContext, player_input: &str) -> String {
-        let mut prompt = String::new();
-
-        push_freeform_system_prompt(&mut prompt);
-        push_world_definition(&mut prompt, context, false);
-        push_player_section(&mut prompt, context);
-        push_history_section(&mut prompt, &context.history, "NARRATIVE HISTORY");
-        push_current_situation(&mut prompt, context);
-        push_power_use_intent(&mut prompt, player_input);
-        push_player_action(&mut prompt, player_input);
-        push_freeform_reminder(&mut prompt, false);
+struct GamePromptBuilder;
 
-        prompt
+impl GamePromptBuilder {
+    pub fn build(context: &GameContext, player_input: &str) -> BuiltPrompt {
+        let (chapters, history) = budgeted_story(context);
+        let sections = vec![
+            PromptSection::templates(GAME_SYSTEM_SECTIONS, context, false).system(),
+            PromptSection::required(|p| push_world_definition(p, context, true)).system(),
+            PromptSection::oldest_first("lore", PRIORITY_LORE, |p| {
+                push_lore_section(p, context, player_input)
+            }),
+            PromptSection::whole("party", PRIORITY_PARTY, |p| push_party_section(p, context)),
+            PromptSection::whole("npc registry", PRIORITY_NPC_REGISTRY, |p| {
+                push_npc_registry(p, context)
+            }),
+            PromptSection::whole("npc memory", PRIORITY_NPC_MEMORY, |p| {
+                push_npc_memory(p, context, player_input)
+            }),
+            PromptSection::whole("relationships", PRIORITY_RELATIONSHIPS, |p| {
+                push_relationships(p, context)
+            }),
+            PromptSection::required(|p| push_time_section(p, context)),
+            PromptSection::required(|p| push_world_events(p, context)),
+            PromptSection::required(|p| push_status_effects(p, context)),
+            PromptSection::required(|p| push_quest_offers(p, context)),
+            PromptSection::required(|p| push_reward_negotiations(p, context)),
+            PromptSection::oldest_first("story summaries", PRIORITY_STORY, |p| {
+                push_story_section(p, &chapters)
+            })
+            .system(),
+            PromptSection::oldest_first("history", PRIORITY_HISTORY, |p| {
+                push_history_section(p, history, "NARRATIVE HISTORY")
+            })
+            .history(),
+            PromptSection::required(|p| push_engine_feedback(p, context)),
+            PromptSection::required(|p| push_current_situation(p, context)),
+            PromptSection::required(|p| push_power_use_intent(p, player_input)),
+            PromptSection::required(|p| push_player_action(p, player_input)),
+            PromptSection::templates(&["game_reminder"], context, false),
+        ];
+
+        assemble_sections(sections, prompt_budget(context))
     }
 
     pub fn build_with_requested_context(
@@ -116,326 +205,343 @@ impl FreeformPromptBuilder {
         player_input: &str,
         requested_context: &str,
         recent_history: &[Message],
-    ) -> String {
-        let mut prompt = String::new();
-
-        push_freeform_system_prompt(&mut prompt);
-        push_world_definition(&mut prompt, context, false);
-        push_player_section(&mut prompt, context);
-        push_current_situation(&mut prompt, context);
-
-        if !requested_context.trim().is_empty() {
-            prompt.push_str("REQUESTED CONTEXT:\n");
-            prompt.push_str(requested_context);
-            prompt.push_str("\n\n");
-        }
+    ) -> BuiltPrompt {
+        let (chapters, _) = budgeted_story(context);
+        let sections = vec![
+            PromptSection::templates(GAME_SYSTEM_SECTIONS, context, true).system(),
+            PromptSection::required(|p| push_world_definition(p, context, true)).system(),
+            PromptSection::oldest_first("lore", PRIORITY_LORE, |p| {
+                push_lore_section(p, context, player_input)
+            }),
+            PromptSection::whole("party", PRIORITY_PARTY, |p| push_party_section(p, context)),
+            PromptSection::whole("npc registry", PRIORITY_NPC_REGISTRY, |p| {
+                push_npc_registry(p, context)
+            }),
+            PromptSection::whole("npc memory", PRIORITY_NPC_MEMORY, |p| {
+                push_npc_memory(p, context, player_input)
+            }),
+            PromptSection::whole("relationships", PRIORITY_RELATIONSHIPS, |p| {
+                push_relationships(p, context)
+            }),
+            PromptSection::required(|p| push_time_section(p, context)),
+            PromptSection::required(|p| push_world_events(p, context)),
+            PromptSection::required(|p| push_status_effects(p, context)),
+            PromptSection::required(|p| push_quest_offers(p, context)),
+            PromptSection::required(|p| push_reward_negotiations(p, context)),
+            PromptSection::required(|p| push_engine_feedback(p, context)),
+            PromptSection::required(|p| push_current_situation(p, context)),
+            PromptSection::required(|p| push_requested_context(p, requested_context)),
+            PromptSection::oldest_first("story summaries", PRIORITY_STORY, |p| {
+                push_story_section(p, &chapters)
+            })
+            .system(),
+            PromptSection::oldest_first("history", PRIORITY_HISTORY, |p| {
+                push_history_section(p, recent_history, "RECENT HISTORY")
+            })
+            .history(),
+            PromptSection::required(|p| push_power_use_intent(p, player_input)),
+            PromptSection::required(|p| push_player_action(p, player_input)),
+            PromptSection::templates(&["game_reminder"], context, true),
+        ];
+
+        assemble_sections(sections, prompt_budget(context))
+    }
+}
 
-        if !recent_history.is_empty() {
-            prompt.push_str("RECENT HISTORY:\n");
-            push_history_lines(&mut prompt, recent_history);
-        }
+struct FreeformPromptBuilder;
 
-        push_power_use_intent(&mut prompt, player_input);
-        push_player_action(&mut prompt, player_input);
-        push_freeform_reminder(&mut prompt, true);
+impl FreeformPromptBuilder {
+    pub fn build(context: &GameContext, player_input: &str) -> BuiltPrompt {
+        let (chapters, history) = budgeted_story(context);
+        let sections = vec![
+            PromptSection::templates(FREEFORM_SYSTEM_SECTIONS, context, false).system(),
+            PromptSection::required(|p| push_world_definition(p, context, false)).system(),
+            PromptSection::oldest_first("lore", PRIORITY_LORE, |p| {
+                push_lore_section(p, context, player_input)
+            }),
+            PromptSection::required(|p| push_player_section(p, context)),
+            PromptSection::required(|p| push_status_effects(p, context)),
+            PromptSection::whole("npc memory", PRIORITY_NPC_MEMORY, |p| {
+                push_npc_memory(p, context, player_input)
+            }),
+            PromptSection::oldest_first("story summaries", PRIORITY_STORY, |p| {
+                push_story_section(p, &chapters)
+            })
+            .system(),
+            PromptSection::oldest_first("history", PRIORITY_HISTORY, |p| {
+                push_history_section(p, history, "NARRATIVE HISTORY")
+            })
+            .history(),
+            PromptSection::required(|p| push_engine_feedback(p, context)),
+            PromptSection::required(|p| push_current_situation(p, context)),
+            PromptSection::required(|p| push_power_use_intent(p, player_input)),
+            PromptSection::required(|p| push_player_action(p, player_input)),
+            PromptSection::templates(&["freeform_reminder"], context, false),
+        ];
+
+        assemble_sections(sections, prompt_budget(context))
+    }
 
-        prompt
+    pub fn build_with_requested_context(
+        context: &GameContext,
+        player_input: &str,
+        requested_context: &str,
+        recent_history: &[Message],
+    ) -> BuiltPrompt {
+        let (chapters, _) = budgeted_story(context);
+        let sections = vec![
+            PromptSection::templates(FREEFORM_SYSTEM_SECTIONS, context, true).system(),
+            PromptSection::required(|p| push_world_definition(p, context, false)).system(),
+            PromptSection::oldest_first("lore", PRIORITY_LORE, |p| {
+                push_lore_section(p, context, player_input)
+            }),
+            PromptSection::required(|p| push_player_section(p, context)),
+            PromptSection::required(|p| push_status_effects(p, context)),
+            PromptSection::whole("npc memory", PRIORITY_NPC_MEMORY, |p| {
+                push_npc_memory(p, context, player_input)
+            }),
+            PromptSection::required(|p| push_engine_feedback(p, context)),
+            PromptSection::required(|p| push_current_situation(p, context)),
+            PromptSection::required(|p| push_requested_context(p, requested_context)),
+            PromptSection::oldest_first("story summaries", PRIORITY_STORY, |p| {
+                push_story_section(p, &chapters)
+            })
+            .system(),
+            PromptSection::oldest_first("history", PRIORITY_HISTORY, |p| {
+                push_history_section(p, recent_history, "RECENT HISTORY")
+            })
+            .history(),
+            PromptSection::required(|p| push_power_use_intent(p, player_input)),
+            PromptSection::required(|p| push_player_action(p, player_input)),
+            PromptSection::templates(&["freeform_reminder"], context, true),
+        ];
+
+        assemble_sections(sections, prompt_budget(context))
     }
 }
 
-fn push_game_system_prompt(prompt: &mut String, context: &GameContext, followup: bool) {
-    prompt.push_str(
-        "You are the narrator and all non-player characters in a roleplaying game.\n\n\
-Rules:\n\
-- You must never control or describe actions taken by the player beyond what the player explicitly states.\n\
-- You must never change game state directly.\n\
-- All game state changes must be expressed ONLY through structured EVENTS.\n\
-- If no state change is required, output an empty events array.\n\
-- When loot appears in the world, you MUST use a drop event to represent it.\n\
-- Do not use add_item unless the player explicitly picks up an item.\n\
-- Crafting and gathering outputs must follow loot rules and use drop/spawn_loot events.\n\
-- You MUST request context for any state-dependent detail you do not have.\n\
-- You must not infer loot, quest state, stats, inventory, currencies, flags, relationships, or NPC details without context.\n\
-\n\
-Narrative Rules:\n\
-- Write immersive narration and dialogue.\n\
-- Use explicit speaker tags for every narrative block.\n\
-- Never invent party members.\n\
-- Never speak as the player character.\n\
-\n\
-Output Format:\n\
-You MUST respond in exactly two sections:\n\n\
-NARRATIVE:\n\
-<text>\n\n\
-EVENTS:\n\
-<json array>\n\n\
-Do not add explanations, markdown, or extra sections.\n\n\
-Event Types (JSON array of objects with a \"type\" field):\n\
-- combat { description }\n\
-- dialogue { speaker, text }\n\
-- travel { from, to }\n\
-- rest { description }\n\
-- craft { recipe, quantity?, quality?, result?, set_id? }\n\
-- gather { resource, quantity?, quality?, set_id? }\n\
-- grant_power { id, name, description }\n\
-- modify_stat { stat_id, delta }\n\
-- start_quest { id, title, description, difficulty?, negotiable?, reward_options?, rewards?, sub_quests?, declinable? }\n\
-- update_quest { id, title?, description?, status?, difficulty?, negotiable?, reward_options?, rewards?, sub_quests? }\n\
-- set_flag { flag }\n\
-- add_party_member { id, name, role }\n\
-- npc_spawn { id?, name, role, details? }\n\
-- npc_update { id?, name?, role?, details? }\n\
-- npc_despawn { id, reason? }\n\
-- npc_join_party { id?, name?, role?, details?, weapons?, armor?, clothing? }\n\
-- npc_leave_party { id }\n\
-- party_update { id, name?, role?, details?, weapons_add?, weapons_remove?, armor_add?, armor_remove?, clothing_add?, clothing_remove? }\n\
-- section_card_upsert { section, id, name, role?, status?, details?, notes?, tags?, items? }\n\
-- section_card_remove { section, id }\n\
-- player_card_update { name?, role?, status?, details?, notes?, tags?, items? }\n\
-- time_passed { minutes, reason? }\n\
-- relationship_change { subject_id, target_id, delta }\n\
-- add_item { item_id, quantity, set_id? }\n\
-- add_exp { amount }\n\
-- level_up { levels }\n\
-- equip_item { item_id, slot, set_id?, description? }\n\
-- unequip_item { item_id }\n\
-- drop { item, quantity?, description?, set_id? }\n\
-- spawn_loot { item, quantity?, description?, set_id? }\n\
-- currency_change { currency, delta }\n\
-- faction_spawn { id, name, kind?, description? }\n\
-- faction_update { id, name?, kind?, description? }\n\
-- faction_rep_change { id, delta }\n\
-- request_context { topics }\n\n"
-    );
+/* =========================
+   Token budgeting
+   ========================= */
+
+// Section priorities: when the prompt does not fit the context window the
+// lowest priority gives way first. Rules, world, time and the player action
+// are never cut.
+const PRIORITY_HISTORY: u8 = 0;
+const PRIORITY_NPC_REGISTRY: u8 = 1;
+const PRIORITY_STORY: u8 = 2;
+const PRIORITY_LORE: u8 = 3;
+const PRIORITY_NPC_MEMORY: u8 = 4;
+const PRIORITY_RELATIONSHIPS: u8 = 5;
+const PRIORITY_PARTY: u8 = 6;
+
+/// Upper bound on the share of the context window kept free for the reply.
+const MAX_RESPONSE_RESERVE_TOKENS: usize = 1024;
+
+/// How a section gives way when the prompt is over budget.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Shrink {
+    Never,
+    /// Drop body lines from the top (oldest first), then the header
+    OldestFirst,
+    /// Drop the whole section at once
+    Whole,
+}
 
-    prompt.push_str(
-        "Event Notes:\n\
-- sub_quests is an array of objects like { id, description, completed? }\n\
-- start_quest should include rewards (can be empty) and may include declinable for world quests\n\
-- Use difficulty for quest challenge (e.g., easy, hard, extremely hard).\n\
-- If negotiable is true, include reward_options with alternatives the player can bargain for.\n\
-- update_quest may send partial updates for sub_quests (id required)\n\
-- Use add_exp for experience gains. Use modify_stat for stat changes.\n\
-- Use level_up to advance level without awarding experience.\n\n"
-    );
+/// Which chat message a section lands in when the prompt is sent as
+/// separate messages.
+#[derive(Clone, Copy, PartialEq)]
+enum Slot {
+    /// Stable rules and world text
+    System,
+    /// Split into user/assistant turns
+    History,
+    /// The current turn, sent as the last user message
+    Turn,
+}
 
-    prompt.push_str(
-        "NPC Tracking:\n\
-- When a new NPC is introduced or speaks for the first time, emit npc_spawn with name, role, and details. The id is optional.\n\
-- The engine will assign an id if you omit it. Use ids from the NPC REGISTRY for future updates.\n\
-- When you learn new NPC facts (real name, title, favorite drink, habits), emit npc_update with only the new fields.\n\
-- When an NPC leaves the scene or the player walks away, emit npc_despawn { id }.\n\
-- Do NOT reuse an existing NPC name. If a name is already in USED NPC NAMES, modify it (surname or last initial).\n\
-- Never mention NPC ids in the narrative.\n\n"
-    );
+struct PromptSection {
+    name: &'static str,
+    priority: u8,
+    shrink: Shrink,
+    slot: Slot,
+    text: String,
+    warnings: Vec<String>,
+}
 
-    prompt.push_str(
-        "Party Tracking:\n\
-- Only emit party_update when the player explicitly asks to examine/describe a party member.\n\
-- Use *_add for newly observed gear and *_remove when old gear is replaced.\n\
-- clothing/armor/weapons entries should be short strings; details should be a concise summary (1-3 sentences).\n\n"
-    );
+impl PromptSection {
+    fn new(
+        name: &'static str,
+        priority: u8,
+        shrink: Shrink,
+        push: impl FnOnce(&mut String),
+    ) -> Self {
+        let mut text = String::new();
+        push(&mut text);
+        Self { name, priority, shrink, slot: Slot::Turn, text, warnings: Vec::new() }
+    }
 
-    prompt.push_str(
-        "Card Tracking (Left Tabs + Player):\n\
-- Use section_card_upsert/section_card_remove for left-tab sections: slaves, property, bonded_servants,\n\
-  concubines, harem_members, prisoners, npcs_on_mission.\n\
-- Use player_card_update for persistent player narrative details not covered by stats.\n\
-- Do NOT use player_card_update to track quests or quest acceptance; use start_quest/update_quest.\n\
-- Keep ids stable and snake_case.\n\n"
-    );
+    fn system(self) -> Self {
+        Self { slot: Slot::System, ..self }
+    }
 
-    prompt.push_str(
-        "Equipment & Sets:\n\
-- Use equip_item/unequip_item to track equipped gear.\n\
-- If an item belongs to a set, include set_id so set bonuses can be tracked.\n\
-- Quest chains should drop items from the same set to enable set bonuses.\n\
-- Set bonuses: 2 pieces grant a minor bonus; 4 pieces grant a major bonus.\n\n"
-    );
+    fn history(self) -> Self {
+        Self { slot: Slot::History, ..self }
+    }
 
-    prompt.push_str(
-        "Power Gain Rules:\n\
-- Powers can be granted in three ways:\n\
-  1) Level-ups at levels that are multiples of 5.\n\
-  2) Rewards for extremely hard quests.\n\
-  3) Repeatedly performing the same or very similar actions (trainable skills).\n\
-- Level-up powers must be class-based, help the player perform tasks better, and scale with higher level.\n\
-- Extremely hard quest rewards should include a significant power or loot (not necessarily both).\n\
-- Repetition should create a relevant skill (e.g., jumping => jumping skill, mining => mining skill).\n\
-- Powers can evolve with repeated use; evolved powers have stronger effects and may gain a random multiplier (x1.1–x3.0). Use up to 5 evolution tiers.\n\
-- When naming new powers, reflect how they were used (e.g., wand healing => \"Directed Heal\"; hands healing => \"Greater Lay on Hands\").\n\
-- Use grant_power events for all new or evolved powers.\n\n"
-    );
+    fn required(push: impl FnOnce(&mut String)) -> Self {
+        Self::new("", u8::MAX, Shrink::Never, push)
+    }
 
-    prompt.push_str(
-        "Power Usage Rules:\n\
-- When the player uses a skill or power, base the result on that power's description and tier.\n\
-- Do not invent effects that contradict or exceed the listed power description.\n\
-- If a power implies sensing, scanning, measuring, or detection, you MUST output a concrete result (e.g., a number, size, distance, temperature) in the narrative.\n\
-- Avoid vague outcomes for power use; the narrative must include a specific effect or result.\n\
-- Do not narrate only the attempt; always include the outcome.\n\
-- If a power is mentioned but not listed, keep the effect minimal and request context if needed.\n\n"
-    );
+    /// A required section rendered from the named templates, in order.
+    fn templates(names: &[&str], context: &GameContext, followup: bool) -> Self {
+        let mut warnings = Vec::new();
+        let section = Self::required(|p| {
+            for name in names {
+                let (text, warning) = render_section(name, context, followup);
+                p.push_str(&text);
+                warnings.extend(warning);
+            }
+        });
+        Self { warnings, ..section }
+    }
 
-    prompt.push_str(
-        "Factions & Reputation:\n\
-- Track reputations with faction_spawn/faction_update/faction_rep_change.\n\
-- Common factions include caravans, guards, and cities, but new factions can be introduced as needed.\n\n"
-    );
+    fn whole(name: &'static str, priority: u8, push: impl FnOnce(&mut String)) -> Self {
+        Self::new(name, priority, Shrink::Whole, push)
+    }
 
-    prompt.push_str(
-        "Request Context:\n\
-- If you need more data, emit request_context { topics: [\"topic1\", \"topic2\"] }\n\
-- You can request location lore with topic \"locations\".\n\
-- Common topics: world, loot_rules, player, stats, powers, features, inventory, weapons, armor, clothing,\n\
-  currencies, party, quests, npcs, relationships, flags, locations, exp, level, skills, power_evolution,\n\
-  equipment, factions, reputation, sets, crafting, gathering, player_card, time,\n\
-  slaves, property, bonded_servants, concubines, harem_members, prisoners, npcs_on_mission.\n\
-- Do NOT add narrative when requesting context.\n\n"
-    );
+    fn oldest_first(name: &'static str, priority: u8, push: impl FnOnce(&mut String)) -> Self {
+        Self::new(name, priority, Shrink::OldestFirst, push)
+    }
+}
 
-    prompt.push_str(
-        "Optional Tabs (unlock via set_flag):\n\
-- unlock:slaves\n\
-- unlock:property\n\
-- unlock:bonded_servants (aliases: bonded_servants, hird)\n\
-- unlock:concubines\n\
-- unlock:harem_members\n\
-- unlock:prisoners\n\
-- unlock:npcs_on_mission\n\n"
-    );
+/// Tokens the prompt may use: the context window minus room for the reply.
+fn prompt_budget(context: &GameContext) -> Option<usize> {
+    context
+        .context_window
+        .map(|window| window.saturating_sub((window / 4).min(MAX_RESPONSE_RESERVE_TOKENS)))
+}
 
-    prompt.push_str("Quest Rules:\n");
-    if context.world.is_rpg_world {
-        prompt.push_str(
-            "- This world is an RPG simulation. Only the player knows it; NPCs believe it is real.\n",
-        );
-        prompt.push_str(
-            "- NPCs must follow world rules and formally offer quests with explicit rewards.\n",
-        );
-    }
-    if context.world.world_quests_enabled {
-        prompt.push_str("- World quests are ENABLED.\n");
-        prompt.push_str(
-            "- When the world offers a quest, you MUST include the exact line: \"*ding* the world is offering you a quest.\"\n",
-        );
-        prompt.push_str(
-            "- Example world offer line: [NARRATOR] *ding* the world is offering you a quest.\n",
-        );
-        if context.world.world_quests_mandatory {
-            prompt.push_str(
-                "- If the world quest is mandatory, set declinable: false and you may emit start_quest immediately.\n",
-            );
-        } else {
-            prompt.push_str(
-                "- Do NOT use declinable: false unless mandatory world quests are enabled.\n",
-            );
+/// Join the sections in order, first shrinking the lowest-priority ones
+/// until the estimate fits `budget`.
+fn assemble_sections(mut sections: Vec<PromptSection>, budget: Option<usize>) -> BuiltPrompt {
+    let mut trimmed = Vec::new();
+
+    if let Some(budget) = budget {
+        let mut total: usize = sections.iter().map(|s| estimate_tokens(&s.text)).sum();
+        let mut order: Vec<usize> = (0..sections.len())
+            .filter(|&i| sections[i].shrink != Shrink::Never && !sections[i].text.is_empty())
+            .collect();
+        order.sort_by_key(|&i| sections[i].priority);
+
+        for i in order {
+            if total <= budget {
+                break;
+            }
+            let section = &mut sections[i];
+            let before = estimate_tokens(&section.text);
+            if section.shrink == Shrink::OldestFirst {
+                let target = before - (total - budget).min(before);
+                let (text, dropped, kept) = shrink_oldest_first(&section.text, target);
+                if kept > 0 {
+                    trimmed.push(format!("{} -{} lines", section.name, dropped));
+                    section.text = text;
+                } else {
+                    trimmed.push(format!("{} dropped", section.name));
+                    section.text.clear();
+                }
+            } else {
+                trimmed.push(format!("{} dropped", section.name));
+                section.text.clear();
+            }
+            total = total - before + estimate_tokens(&section.text);
         }
-        prompt.push_str(
-            "- For declinable world quests, emit start_quest ONLY after the player explicitly accepts.\n",
-        );
-    } else {
-        prompt.push_str("- World quests are DISABLED.\n");
-    }
-    if context.world.npc_quests_enabled {
-        prompt.push_str("- NPC quests are ENABLED.\n");
-        prompt.push_str(
-            "- NPCs MUST explicitly say: \"I hereby offer you a quest.\" when offering.\n",
-        );
-        prompt.push_str(
-            "- Emit start_quest ONLY after the player explicitly accepts.\n",
-        );
-        prompt.push_str(
-            "- start_quest must include a title and rewards (can be an empty array).\n",
-        );
-        prompt.push_str(
-            "- If the quest giver is a craftsman, set negotiable: true and include reward_options for bargaining.\n",
-        );
-        prompt.push_str(
-            "- Use the exact offer sentence verbatim (case/punctuation) so the app can detect it.\n",
-        );
-        prompt.push_str(
-            "- Example NPC offer line: [NPC: Smith] I hereby offer you a quest.\n",
-        );
-    } else {
-        prompt.push_str("- NPC quests are DISABLED.\n");
     }
-    prompt.push('\n');
 
-    if followup {
-        prompt.push_str(
-            "Follow-up Rules:\n\
-- Requested context is now provided. Do NOT request more context.\n\
-- If you still cannot comply, output the failure response below.\n\n",
-        );
+    let mut text = String::new();
+    let mut slots = [String::new(), String::new(), String::new()];
+    let mut warnings = Vec::new();
+    for section in sections {
+        text.push_str(&section.text);
+        slots[section.slot as usize].push_str(&section.text);
+        warnings.extend(section.warnings);
+    }
+    let [system, history, turn] = slots;
+    BuiltPrompt {
+        estimated_tokens: estimate_tokens(&text),
+        messages: chat_messages(&system, &history, &turn),
+        text,
+        budget,
+        trimmed,
+        warnings,
     }
+}
 
-    prompt.push_str(
-        "Class Evolution Rules:\n\
-- At levels divisible by 15, present exactly three class evolution options.\n\
-- Options must be closely related to the current class and offer additional benefits/buffs.\n\
-- Wait for the player's choice before applying any change.\n\n"
-    );
+/// Lay out the assembled slots as chat messages. History lines tagged
+/// `[PLAYER]` become user turns and the other speaker lines assistant
+/// turns; consecutive lines from the same side are merged.
+fn chat_messages(system: &str, history: &str, turn: &str) -> Vec<ChatMessage> {
+    let mut messages = vec![ChatMessage::system(system.trim_end().to_string())];
+    for line in history.lines().filter(|line| !line.trim().is_empty()) {
+        if let Some(text) = line.strip_prefix("[PLAYER] ") {
+            push_turn(&mut messages, "user", text, "\n");
+        } else if line.starts_with('[') {
+            push_turn(&mut messages, "assistant", line, "\n");
+        } else if messages.len() > 1 {
+            // A multi-line message continues the previous turn; before the
+            // first turn this is the section header.
+            let last = messages.last_mut().expect("history turn");
+            last.content.push('\n');
+            last.content.push_str(line);
+        }
+    }
+    // Most chat templates expect a user turn before the first assistant one.
+    if messages.get(1).is_some_and(|m| m.role == "assistant") {
+        messages.insert(1, ChatMessage::user("Begin the story.".to_string()));
+    }
+    push_turn(&mut messages, "user", turn.trim_end(), "\n\n");
+    messages
+}
 
-    prompt.push_str(
-        "Failure Response (exact text, if rules cannot be followed):\n\
-NARRATIVE:\n\
-Model unable to generate appropriate output, please replace\n\n\
-EVENTS:\n\
-[]\n\n",
-    );
+fn push_turn(messages: &mut Vec<ChatMessage>, role: &str, content: &str, separator: &str) {
+    match messages.last_mut() {
+        Some(last) if last.role == role => {
+            last.content.push_str(separator);
+            last.content.push_str(content);
+        }
+        _ => messages.push(ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        }),
+    }
 }
 
-fn push_freeform_system_prompt(prompt: &mut String) {
-    prompt.push_str(
-        "You are the narrator and all non-player characters in a roleplaying game.\n\n\
-Rules:\n\
-- You must never control or describe actions taken by the player beyond what the player explicitly states.\n\
-- All game state changes must be expressed ONLY through structured EVENTS.\n\
-- If no state change is required, output an empty events array.\n\n\
-Narrative Rules:\n\
-- Write immersive narration and dialogue.\n\
-- Use explicit speaker tags for every narrative block.\n\
-- Never speak as the player character.\n\n\
-Power Usage Rules:\n\
-- When the player uses a skill or power, base the result on that power's description and tier.\n\
-- Do not invent effects that contradict or exceed the listed power description.\n\
-- If a power implies sensing, scanning, measuring, or detection, you MUST output a concrete result (e.g., a number, size, distance, temperature) in the narrative.\n\
-- Avoid vague outcomes for power use; the narrative must include a specific effect or result.\n\
-- Do not narrate only the attempt; always include the outcome.\n\n\
-Output Format:\n\
-You MUST respond in exactly two sections:\n\n\
-NARRATIVE:\n\
-<text>\n\n\
-EVENTS:\n\
-<json array>\n\n\
-Do not add explanations, markdown, or extra sections.\n\n\
-Event Types (JSON array of objects with a \"type\" field):\n\
-- combat { description }\n\
-- dialogue { speaker, text }\n\
-- travel { from, to }\n\
-- rest { description }\n\
-- npc_spawn { id?, name, role, details? }\n\
-- npc_update { id?, name?, role?, details? }\n\
-- npc_despawn { id, reason? }\n\
-- relationship_change { subject_id, target_id, delta }\n\
-- set_flag { flag }\n\
-- section_card_upsert { section, id, name, role?, status?, details?, notes?, tags?, items? }\n\
-- section_card_remove { section, id }\n\
-- player_card_update { name?, role?, status?, details?, notes?, tags?, items? }\n\
-- time_passed { minutes, reason? }\n\
-- request_context { topics }\n\n"
-    );
+/// Drop body lines from the top of a "HEADER:\n...lines...\n\n" section
+/// until it fits `target` tokens. Returns the new text and the number of
+/// lines dropped and kept.
+fn shrink_oldest_first(text: &str, target: usize) -> (String, usize, usize) {
+    let mut lines = text.lines();
+    let header = lines.next().unwrap_or_default();
+    let body: Vec<&str> = lines.filter(|line| !line.trim().is_empty()).collect();
+
+    let overhead = estimate_tokens(header);
+    let mut used: usize = overhead + body.iter().map(|l| estimate_tokens(l)).sum::<usize>();
+    let mut start = 0;
+    while start < body.len() && used > target {
+        used -= estimate_tokens(body[start]);
+        start += 1;
+    }
 
-    prompt.push_str(
-        "Request Context:\n\
-- If you need more data, emit request_context { topics: [\"topic1\", \"topic2\"] }\n\
-- You can request location lore with topic \"locations\".\n\
-- Common topics: world, player, player_card, npcs, relationships, flags, locations, party, inventory,\n\
-  time,\n\
-  slaves, property, bonded_servants, concubines, harem_members, prisoners, npcs_on_mission.\n\
-- Do NOT add narrative when requesting context.\n\n"
-    );
+    let kept = &body[start..];
+    let mut out = String::new();
+    out.push_str(header);
+    out.push('\n');
+    for line in kept {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push('\n');
+    (out, start, kept.len())
 }
 
 fn push_world_definition(prompt: &mut String, context: &GameContext, include_loot_rules: bool) {
@@ -515,7 +621,22 @@ fn push_player_section(prompt: &mut String, context: &GameContext) {
     prompt.push_str(&context.player.background);
     prompt.push_str("\n\n");
 
-    if !context.player.stats.is_empty() {
+    let effective_stats = context
+        .snapshot
+        .as_ref()
+        .map(|s| s.effective_stats.as_slice())
+        .unwrap_or_default();
+    if !effective_stats.is_empty() {
+        prompt.push_str("Stats:\n");
+        for stat in effective_stats {
+            if stat.contributions.len() > 1 {
+                prompt.push_str(&format!("- {}: {}\n", stat.id, stat.breakdown_text()));
+            } else {
+                prompt.push_str(&format!("- {}: {}\n", stat.id, stat.total));
+            }
+        }
+        prompt.push('\n');
+    } else if !context.player.stats.is_empty() {
         prompt.push_str("Stats:\n");
         for (k, v) in &context.player.stats {
             prompt.push_str(&format!("- {}: {}\n", k, v));
@@ -593,6 +714,12 @@ fn push_party_section(prompt: &mut String, context: &GameContext) {
                 "- [PARTY: {}] Role: {}\n  Details: {}\n",
                 member.name, member.role, member.details
             ));
+            if let (Some(id), Some(snapshot)) = (&member.id, &context.snapshot) {
+                let morale = party_morale(&snapshot.relationships, id);
+                let tier = ReputationTier::for_reputation(&context.world.relationship_tiers, morale)
+                    .map_or("", |t| t.name.as_str());
+                prompt.push_str(&format!("  Morale: {} {}\n", morale, tier));
+            }
             if !member.weapons.is_empty() {
                 prompt.push_str("  Weapons:\n");
                 for item in &member.weapons {
@@ -626,8 +753,13 @@ fn push_npc_registry(prompt: &mut String, context: &GameContext) {
         } else {
             let mut npcs = snapshot.npcs.clone();
             npcs.sort_by(|a, b| a.name.cmp(&b.name));
+            if let Some(location) = &snapshot.player_location {
+                prompt.push_str(&format!("Player location: {}\n", location));
+            }
             for npc in &npcs {
-                prompt.push_str(&format!("- {}: {} ({})\n", npc.id, npc.name, npc.role));
+                prompt.push_str(&format!("- {}: {} ({})", npc.id, npc.name, npc.role));
+                push_npc_whereabouts(prompt, npc, snapshot.world_time_minutes);
+                prompt.push('\n');
             }
             prompt.push('\n');
         }
@@ -653,22 +785,393 @@ fn push_npc_registry(prompt: &mut String, context: &GameContext) {
     }
 }
 
+/// Where an NPC is and what they are after, appended to their registry line.
+fn push_npc_whereabouts(prompt: &mut String, npc: &Npc, now: u64) {
+    let routine = &npc.routine;
+    if let Some(mission) = &routine.mission {
+        let hours_left = mission.return_minutes.saturating_sub(now).div_ceil(60);
+        prompt.push_str(&format!(
+            "; away on mission: {} ({}% done, back in ~{}h)",
+            mission.description,
+            mission.progress(now),
+            hours_left
+        ));
+    } else if let Some(location) = &routine.location {
+        prompt.push_str(&format!("; at {}", location));
+        if let Some(activity) = &routine.activity {
+            prompt.push_str(&format!(", {}", activity));
+        }
+    }
+    if !routine.goals.is_empty() {
+        prompt.push_str(&format!("; goals: {}", routine.goals.join(", ")));
+    }
+}
+
+/// Relationships involving the player or the party, with their tier and
+/// the latest reason they changed.
+fn push_relationships(prompt: &mut String, context: &GameContext) {
+    let Some(snapshot) = &context.snapshot else {
+        return;
+    };
+    let involved = |id: &str| {
+        id.eq_ignore_ascii_case("player") || snapshot.party.iter().any(|m| m.id == id)
+    };
+    let mut relationships: Vec<_> = snapshot
+        .relationships
+        .iter()
+        .filter(|r| involved(&r.subject_id) || involved(&r.target_id))
+        .collect();
+    if relationships.is_empty() {
+        return;
+    }
+    relationships.sort_by(|a, b| (&a.subject_id, &a.target_id).cmp(&(&b.subject_id, &b.target_id)));
+    let name_of = |id: &str| -> String {
+        if let Some(member) = snapshot.party.iter().find(|m| m.id == id) {
+            return member.name.clone();
+        }
+        snapshot
+            .npcs
+            .iter()
+            .find(|n| n.id == id)
+            .map_or_else(|| id.to_string(), |n| n.name.clone())
+    };
+    prompt.push_str("RELATIONSHIPS:\n");
+    for rel in relationships {
+        let tier = ReputationTier::for_reputation(&context.world.relationship_tiers, rel.value)
+            .map_or(String::new(), |t| format!(" ({})", t.name));
+        prompt.push_str(&format!(
+            "- {} -> {}: {}{}",
+            name_of(&rel.subject_id),
+            name_of(&rel.target_id),
+            rel.value,
+            tier
+        ));
+        if let Some(reason) = rel.history.iter().rev().find_map(|n| n.reason.as_deref()) {
+            prompt.push_str(&format!("; last change: {}", reason));
+        }
+        prompt.push('\n');
+    }
+    prompt.push('\n');
+}
+
+const NPC_MEMORY_LIMIT: usize = 6;
+
+fn push_npc_memory(prompt: &mut String, context: &GameContext, player_input: &str) {
+    let Some(snapshot) = &context.snapshot else {
+        return;
+    };
+    if snapshot.npc_memories.is_empty() {
+        return;
+    }
+    let input = player_input.to_lowercase();
+    let addressed = |name: &str| {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return false;
+        }
+        contains_word(&input, &name)
+            || name
+                .split_whitespace()
+                .next()
+                .is_some_and(|first| first.len() > 2 && contains_word(&input, first))
+    };
+
+    let mut entries: Vec<(&str, bool, &NpcMemory)> = Vec::new();
+    for npc in &snapshot.npcs {
+        let Some(memory) = snapshot.npc_memories.get(&npc.id) else { continue };
+        let is_addressed = addressed(&npc.name);
+        if npc.nearby || is_addressed {
+            entries.push((npc.name.as_str(), is_addressed, memory));
+        }
+    }
+    for member in &snapshot.party {
+        let Some(memory) = snapshot.npc_memories.get(&member.id) else { continue };
+        if addressed(&member.name) {
+            entries.push((member.name.as_str(), true, memory));
+        }
+    }
+    if entries.is_empty() {
+        return;
+    }
+    // Addressed characters first, then the most recently spoken to.
+    entries.sort_by(|a, b| {
+        b.1.cmp(&a.1).then_with(|| {
+            b.2.last_interaction_minutes
+                .cmp(&a.2.last_interaction_minutes)
+        })
+    });
+    entries.truncate(NPC_MEMORY_LIMIT);
+
+    prompt.push_str("NPC MEMORY (hidden):\n");
+    for (name, _, memory) in entries {
+        prompt.push_str(&format!("- {}\n", name));
+        if let Some(minutes) = memory.last_interaction_minutes {
+            prompt.push_str(&format!(
+                "  Last spoke with player: day {}, {:02}:{:02}\n",
+                minutes / (24 * 60),
+                (minutes / 60) % 24,
+                minutes % 60
+            ));
+        }
+        push_memory_list(prompt, "Knows", &memory.key_facts);
+        push_memory_list(prompt, "Player told them", &memory.player_told);
+        push_memory_list(prompt, "Promises", &memory.promises);
+        push_exchanges(prompt, "Earlier exchanges", name, &memory.earlier_exchanges);
+        push_exchanges(prompt, "Recent exchanges", name, &memory.recent_exchanges);
+    }
+    prompt.push('\n');
+}
+
+fn push_exchanges(prompt: &mut String, label: &str, name: &str, exchanges: &[NpcExchange]) {
+    if exchanges.is_empty() {
+        return;
+    }
+    prompt.push_str(&format!("  {}:\n", label));
+    for exchange in exchanges {
+        prompt.push_str(&format!("  - {}\n", exchange.describe(name)));
+    }
+}
+
+fn push_memory_list(prompt: &mut String, label: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    prompt.push_str(&format!("  {}: {}\n", label, items.join("; ")));
+}
+
 fn push_time_section(prompt: &mut String, context: &GameContext) {
     prompt.push_str("WORLD TIME (hidden):\n");
     if let Some(snapshot) = &context.snapshot {
         let total_minutes = snapshot.world_time_minutes;
-        let days = total_minutes / (24 * 60);
-        let hours = (total_minutes / 60) % 24;
-        let minutes = total_minutes % 60;
-        prompt.push_str(&format!(
-            "Elapsed time: {} days, {:02}:{:02}\n\n",
-            days, hours, minutes
-        ));
+        if let Some(moment) = context.world.calendar.moment(total_minutes) {
+            prompt.push_str(&format!("Date: {}\n", moment.describe()));
+        }
+        prompt.push_str(&format!("{} elapsed since the story began\n\n", format_elapsed(total_minutes)));
     } else {
         prompt.push_str("Unknown\n\n");
     }
 }
 
+/// How far ahead scheduled events and deadlines are listed.
+const UPCOMING_WINDOW_MINUTES: u64 = 3 * 24 * 60;
+const UPCOMING_LIMIT: usize = 5;
+
+/// World events that just fired, then what is coming up soon.
+fn push_world_events(prompt: &mut String, context: &GameContext) {
+    let Some(snapshot) = &context.snapshot else {
+        return;
+    };
+    if !snapshot.fired_world_events.is_empty() {
+        prompt.push_str("WORLD EVENTS (just happened; work them into the narration):\n");
+        for fired in &snapshot.fired_world_events {
+            prompt.push_str(&format!("- {}", fired.name));
+            if !fired.description.trim().is_empty() {
+                prompt.push_str(&format!(": {}", fired.description.trim()));
+            }
+            prompt.push('\n');
+        }
+        prompt.push('\n');
+    }
+
+    let now = snapshot.world_time_minutes;
+    let soon = |at: u64| at > now && at - now <= UPCOMING_WINDOW_MINUTES;
+    let mut upcoming: Vec<(u64, String)> = snapshot
+        .scheduled_events
+        .iter()
+        .filter(|e| soon(e.at_minutes))
+        .map(|e| (e.at_minutes, e.name.clone()))
+        .collect();
+    upcoming.extend(
+        snapshot
+            .quests
+            .iter()
+            .filter(|q| q.status == QuestStatus::Active)
+            .filter_map(|q| Some((q.deadline_minutes.filter(|at| soon(*at))?, format!("Deadline: {}", q.title)))),
+    );
+    if upcoming.is_empty() {
+        return;
+    }
+    upcoming.sort();
+    prompt.push_str("UPCOMING (hidden):\n");
+    for (at, name) in upcoming.into_iter().take(UPCOMING_LIMIT) {
+        let left = at - now;
+        prompt.push_str(&format!(
+            "- {} in {}h {:02}m ({})\n",
+            name,
+            left / 60,
+            left % 60,
+            context.world.calendar.describe(at)
+        ));
+    }
+    prompt.push('\n');
+}
+
+/// Active player effects; the one place they appear in any prompt layout.
+fn push_status_effects(prompt: &mut String, context: &GameContext) {
+    let Some(snapshot) = &context.snapshot else {
+        return;
+    };
+    if snapshot.status_effects.is_empty() {
+        return;
+    }
+    let mut effects = snapshot.status_effects.clone();
+    effects.sort_by(|a, b| a.name.cmp(&b.name));
+    prompt.push_str("PLAYER STATUS EFFECTS:\n");
+    for effect in &effects {
+        prompt.push_str(&format!("- {}\n", effect.summary(snapshot.world_time_minutes)));
+    }
+    prompt.push('\n');
+}
+
+/// Rough token estimate, not measured against any tokenizer: each run of
+/// letters/digits costs one token per four characters, and every other
+/// non-space character (punctuation, brackets, JSON syntax) costs one.
+/// Backends count differently, so treat it as a budget, not an exact figure.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut run = 0usize;
+    for ch in text.chars() {
+        if ch.is_alphanumeric() {
+            run += 1;
+            continue;
+        }
+        tokens += run.div_ceil(4);
+        run = 0;
+        if !ch.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + run.div_ceil(4)
+}
+
+/// Split `history_token_budget` between story chapters and verbatim history.
+/// The newest messages get two thirds of the budget, then chapters ending
+/// before the first of them get up to a third (newest first), and whatever
+/// is left goes to older messages the chapters do not cover. Chapters never
+/// repeat messages that are sent verbatim.
+fn budgeted_story(context: &GameContext) -> (Vec<&StoryChapter>, &[Message]) {
+    let chapters: &[StoryChapter] = context
+        .snapshot
+        .as_ref()
+        .map(|s| s.story_chapters.as_slice())
+        .unwrap_or(&[]);
+    let Some(budget) = context.history_token_budget else {
+        let kept = chapters
+            .iter()
+            .filter(|chapter| chapter.to_message <= context.history_start)
+            .collect();
+        return (kept, &context.history);
+    };
+    let cost = |msg: &Message| history_line(msg).map(|line| estimate_tokens(&line)).unwrap_or(0);
+
+    let mut used = 0;
+    let mut start = context.history.len();
+    while start > 0 && used + cost(&context.history[start - 1]) <= budget - budget / 3 {
+        used += cost(&context.history[start - 1]);
+        start -= 1;
+    }
+
+    let mut chapter_used = 0;
+    let mut kept = Vec::new();
+    for chapter in chapters.iter().rev() {
+        if chapter.to_message > context.history_start + start {
+            continue;
+        }
+        let cost = estimate_tokens(&chapter.summary) + 4;
+        if chapter_used + cost > budget / 3 {
+            break;
+        }
+        chapter_used += cost;
+        kept.push(chapter);
+    }
+    kept.reverse();
+    used += chapter_used;
+
+    let covered = kept.last().map_or(0, |chapter| chapter.to_message);
+    while start > 0
+        && context.history_start + start > covered
+        && used + cost(&context.history[start - 1]) <= budget
+    {
+        used += cost(&context.history[start - 1]);
+        start -= 1;
+    }
+
+    (kept, &context.history[start..])
+}
+
+fn push_story_section(prompt: &mut String, chapters: &[&StoryChapter]) {
+    if chapters.is_empty() {
+        return;
+    }
+
+    prompt.push_str("STORY SO FAR (summaries of earlier events):\n");
+    for chapter in chapters {
+        prompt.push_str(&format!("Chapter {}: {}\n", chapter.index, chapter.summary.trim()));
+    }
+    prompt.push('\n');
+}
+
+/// Lorebook entries triggered by the player input or the last `scan_depth`
+/// messages. Listed lowest priority first so budget trimming drops those.
+fn push_lore_section(prompt: &mut String, context: &GameContext, player_input: &str) {
+    let lorebook = &context.world.lorebook;
+    let recent: Vec<String> = context
+        .history
+        .iter()
+        .rev()
+        .filter_map(history_line)
+        .take(lorebook.scan_depth)
+        .collect();
+    let mut texts: Vec<&str> = recent.iter().map(String::as_str).collect();
+    texts.push(player_input);
+
+    let entries = lorebook.active_entries(&texts);
+    if entries.is_empty() {
+        return;
+    }
+
+    prompt.push_str("WORLD LORE (hidden):\n");
+    for entry in entries.iter().rev() {
+        let content = entry.content.split_whitespace().collect::<Vec<_>>().join(" ");
+        let content = if entry.token_cap > 0 {
+            truncate_to_tokens(&content, entry.token_cap)
+        } else {
+            content
+        };
+        prompt.push_str(&format!("- {}: {}\n", entry.name.trim(), content));
+    }
+    prompt.push('\n');
+}
+
+/// Cut `text` at a word boundary so it fits within `max_tokens`.
+fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    if estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for word in text.split(' ') {
+        let candidate = if out.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", out, word)
+        };
+        if estimate_tokens(&candidate) + 1 > max_tokens {
+            break;
+        }
+        out = candidate;
+    }
+    out.push('…');
+    out
+}
+
+fn push_requested_context(prompt: &mut String, requested_context: &str) {
+    if !requested_context.trim().is_empty() {
+        prompt.push_str("REQUESTED CONTEXT:\n");
+        prompt.push_str(requested_context);
+        prompt.push_str("\n\n");
+    }
+}
+
 fn push_history_section(prompt: &mut String, history: &[Message], label: &str) {
     if history.is_empty() {
         return;
@@ -681,33 +1184,117 @@ fn push_history_section(prompt: &mut String, history: &[Message], label: &str) {
 
 fn push_history_lines(prompt: &mut String, history: &[Message]) {
     for msg in history {
-        match msg {
-            Message::User(text) => {
-                prompt.push_str(&format!("[PLAYER] {}\n", text));
-            }
-            Message::Roleplay { speaker, text } => match speaker {
-                RoleplaySpeaker::Narrator => {
-                    prompt.push_str(&format!("[NARRATOR] {}\n", text));
-                }
-                RoleplaySpeaker::Npc => {
-                    if let Some((name, body)) = split_speaker_text(text) {
-                        prompt.push_str(&format!("[NPC: {}] {}\n", name, body));
-                    } else {
-                        prompt.push_str(&format!("[NPC] {}\n", text));
-                    }
+        if let Some(line) = history_line(msg) {
+            prompt.push_str(&line);
+        }
+    }
+
+    prompt.push('\n');
+}
+
+fn history_line(msg: &Message) -> Option<String> {
+    let line = match msg {
+        Message::User(text) => format!("[PLAYER] {}\n", text),
+        Message::Roleplay { speaker, text } => match speaker {
+            RoleplaySpeaker::Narrator => format!("[NARRATOR] {}\n", text),
+            RoleplaySpeaker::Npc => {
+                if let Some((name, body)) = split_speaker_text(text) {
+                    format!("[NPC: {}] {}\n", name, body)
+                } else {
+                    format!("[NPC] {}\n", text)
                 }
-                RoleplaySpeaker::PartyMember => {
-                    if let Some((name, body)) = split_speaker_text(text) {
-                        prompt.push_str(&format!("[PARTY: {}] {}\n", name, body));
-                    } else {
-                        prompt.push_str(&format!("[PARTY] {}\n", text));
-                    }
+            }
+            RoleplaySpeaker::PartyMember => {
+                if let Some((name, body)) = split_speaker_text(text) {
+                    format!("[PARTY: {}] {}\n", name, body)
+                } else {
+                    format!("[PARTY] {}\n", text)
                 }
-            },
-            Message::System(_) => {}
-        }
+            }
+        },
+        Message::System(_) => return None,
+    };
+    Some(line)
+}
+
+fn push_engine_feedback(prompt: &mut String, context: &GameContext) {
+    let Some(snapshot) = context.snapshot.as_ref() else {
+        return;
+    };
+    if snapshot.engine_feedback.is_empty() {
+        return;
+    }
+    prompt.push_str("ENGINE FEEDBACK (last turn's events that did NOT take effect):\n");
+    for line in &snapshot.engine_feedback {
+        prompt.push_str(&format!("- {}\n", line));
+    }
+    prompt.push_str("Narrate consistently with these outcomes; do not treat them as having happened.\n\n");
+}
+
+/// Quests offered but not yet answered by the player.
+fn push_quest_offers(prompt: &mut String, context: &GameContext) {
+    let Some(snapshot) = context.snapshot.as_ref() else {
+        return;
+    };
+    if snapshot.quest_offers.is_empty() {
+        return;
+    }
+    let mut offers: Vec<_> = snapshot.quest_offers.iter().collect();
+    offers.sort_by(|a, b| a.quest.id.cmp(&b.quest.id));
+    prompt.push_str("PENDING QUEST OFFERS (the engine starts them when the player accepts):\n");
+    for offer in offers {
+        let turns_left = context
+            .world
+            .quest_offer_turns
+            .saturating_sub(offer.turns_waiting);
+        prompt.push_str(&format!(
+            "- {} [{}]: {} turn(s) left to answer\n",
+            offer.label(),
+            offer.quest.id,
+            turns_left
+        ));
     }
+    prompt.push('\n');
+}
 
+/// Open reward bargaining, so the quest giver can answer counter-offers.
+fn push_reward_negotiations(prompt: &mut String, context: &GameContext) {
+    let Some(snapshot) = context.snapshot.as_ref() else {
+        return;
+    };
+    let mut quests: Vec<_> = snapshot
+        .quests
+        .iter()
+        .filter(|q| q.negotiable && !q.negotiation.locked)
+        .filter(|q| matches!(q.status, QuestStatus::Active | QuestStatus::Locked))
+        .collect();
+    if quests.is_empty() {
+        return;
+    }
+    quests.sort_by(|a, b| a.id.cmp(&b.id));
+    prompt.push_str("REWARD NEGOTIATIONS:\n");
+    for quest in quests {
+        let giver = quest
+            .giver_npc
+            .as_deref()
+            .or(quest.giver_faction.as_deref())
+            .unwrap_or("the giver");
+        match &quest.negotiation.counter_offer {
+            Some(counter) => prompt.push_str(&format!(
+                "- {} ({}): the player asks {} for {}. Answer in character and emit settle_reward.\n",
+                quest.title,
+                quest.id,
+                giver,
+                counter.join(", ")
+            )),
+            None => prompt.push_str(&format!(
+                "- {} ({}): rewards open; options: {}\n",
+                quest.title,
+                quest.id,
+                quest.reward_options.join(", ")
+            )),
+        }
+    }
     prompt.push('\n');
 }
 
@@ -747,7 +1334,7 @@ fn extract_power_use_intent(player_input: &str) -> Option<String> {
     }
     let slice = &player_input[start..];
     let end = slice
-        .find(|c: char| c == '.' || c == '!' || c == '?' || c == '\n')
+        .find(['.', '!', '?', '\n'])
         .unwrap_or(slice.len());
     let name = slice[..end].trim();
     if name.is_empty() {
@@ -763,39 +1350,6 @@ fn push_player_action(prompt: &mut String, player_input: &str) {
     prompt.push_str("\n\n");
 }
 
-fn push_game_reminder(prompt: &mut String, followup: bool) {
-    prompt.push_str(
-        "REMINDER:\n\
-- Use speaker tags like [NARRATOR], [PARTY: Name], [NPC: Name]\n\
-- Do NOT describe player actions beyond the input.\n\
-- EVENTS must be valid JSON (a JSON array only).\n\
-- Do NOT use bullet lists or \"type { key: value }\" shorthand.\n\
-- All keys and string values must use double quotes.\n\
-- Example:\n\
-  [ { \"type\": \"drop\", \"item\": \"Common Squirrel Fur\", \"quantity\": 1, \"description\": \"The soft fur of a common forest squirrel.\" } ]\n\
-- If no events occur, output: []\n",
-    );
-
-    if followup {
-        prompt.push_str("- Do NOT request more context in this response.\n");
-    }
-}
-
-fn push_freeform_reminder(prompt: &mut String, followup: bool) {
-    prompt.push_str(
-        "REMINDER:\n\
-- Use speaker tags like [NARRATOR], [NPC: Name]\n\
-- Do NOT describe player actions beyond the input.\n\
-- EVENTS must be valid JSON (a JSON array only).\n\
-- All keys and string values must use double quotes.\n\
-- If no events occur, output: []\n",
-    );
-
-    if followup {
-        prompt.push_str("- Do NOT request more context in this response.\n");
-    }
-}
-
 fn split_speaker_text(text: &str) -> Option<(&str, &str)> {
     let (name, body) = text.split_once(':')?;
     let name = name.trim();
@@ -902,3 +1456,172 @@ fn trim_multiplier(value: f32) -> String {
         s
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::game_state::GameStateSnapshot;
+    use crate::model::internal_game_state::InternalGameState;
+
+    #[test]
+    fn story_budget_keeps_newest_chapters_and_messages() {
+        let mut state = InternalGameState::default();
+        for index in 1..=3 {
+            state.story_chapters.push(StoryChapter {
+                index,
+                summary: "x".repeat(40),
+                from_message: 0,
+                to_message: 0,
+                world_time_minutes: 0,
+            });
+        }
+        let snapshot: GameStateSnapshot = (&state).into();
+        let history: Vec<Message> = (0..20)
+            .map(|i| Message::User(format!("message number {:02}", i)))
+            .collect();
+        let context = GameContext {
+            world: Default::default(),
+            player: Default::default(),
+            party: Vec::new(),
+            history,
+            history_limit: None,
+            history_start: 0,
+            snapshot: Some(snapshot),
+            history_token_budget: Some(90),
+            context_window: None,
+            prompt_templates: Default::default(),
+        };
+
+        let (chapters, history) = budgeted_story(&context);
+
+        // A third of the budget (30) fits two 14-token chapters; the remaining
+        // 62 tokens fit six 9-token messages.
+        assert_eq!(chapters.iter().map(|c| c.index).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(history.len(), 6);
+        assert!(matches!(history.last(), Some(Message::User(t)) if t.ends_with("19")));
+    }
+
+    #[test]
+    fn story_chapters_skip_messages_still_in_history() {
+        let mut state = InternalGameState::default();
+        for (index, to_message) in [(1, 10), (2, 20), (3, 30)] {
+            state.story_chapters.push(StoryChapter {
+                index,
+                summary: "x".repeat(40),
+                from_message: to_message - 10,
+                to_message,
+                world_time_minutes: 0,
+            });
+        }
+        let snapshot: GameStateSnapshot = (&state).into();
+        let history: Vec<Message> = (0..20)
+            .map(|i| Message::User(format!("message number {:02}", i)))
+            .collect();
+        let mut context = GameContext {
+            world: Default::default(),
+            player: Default::default(),
+            party: Vec::new(),
+            history,
+            history_limit: None,
+            history_start: 12,
+            snapshot: Some(snapshot),
+            history_token_budget: None,
+            context_window: None,
+            prompt_templates: Default::default(),
+        };
+
+        // Unbudgeted, the history starts at message 12.
+        let (chapters, history) = budgeted_story(&context);
+        assert_eq!(chapters.iter().map(|c| c.index).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(history.len(), 20);
+
+        // Eleven messages (21..32) fit two thirds of 150 tokens, so chapter 3
+        // is skipped. The leftover budget extends history back to message 20,
+        // where chapter 2 ends.
+        context.history_token_budget = Some(150);
+        let (chapters, history) = budgeted_story(&context);
+        assert_eq!(chapters.iter().map(|c| c.index).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(history.len(), 12);
+    }
+
+    #[test]
+    fn over_budget_prompt_cuts_history_before_registry_and_keeps_action() {
+        let sections = || {
+            vec![
+                PromptSection::required(|p| p.push_str("RULES:\nBe consistent.\n\n")),
+                PromptSection::whole("npc registry", PRIORITY_NPC_REGISTRY, |p| {
+                    p.push_str("NPC REGISTRY (hidden):\n- mira: Mira (smith)\n\n")
+                }),
+                PromptSection::oldest_first("history", PRIORITY_HISTORY, |p| {
+                    p.push_str("NARRATIVE HISTORY:\n");
+                    for i in 0..10 {
+                        p.push_str(&format!("[PLAYER] line {}\n", i));
+                    }
+                    p.push('\n');
+                }),
+                PromptSection::required(|p| push_player_action(p, "I open the door.")),
+            ]
+        };
+        let full = assemble_sections(sections(), None).estimated_tokens;
+
+        let built = assemble_sections(sections(), Some(full - 10));
+
+        assert!(built.estimated_tokens <= full - 10);
+        assert!(built.text.contains("PLAYER ACTION:\nI open the door."));
+        assert!(built.text.contains("NPC REGISTRY"));
+        assert!(built.text.contains("[PLAYER] line 9"));
+        assert!(!built.text.contains("[PLAYER] line 0"));
+        assert_eq!(built.trimmed, vec!["history -2 lines".to_string()]);
+    }
+
+    #[test]
+    fn multi_message_layout_alternates_history_turns() {
+        let history = vec![
+            Message::Roleplay {
+                speaker: RoleplaySpeaker::Narrator,
+                text: "You wake in a cell.".to_string(),
+            },
+            Message::User("I call the guard.".to_string()),
+            Message::Roleplay {
+                speaker: RoleplaySpeaker::Npc,
+                text: "Guard: Quiet!".to_string(),
+            },
+            Message::System("debug".to_string()),
+            Message::Roleplay {
+                speaker: RoleplaySpeaker::Narrator,
+                text: "Footsteps fade.".to_string(),
+            },
+            Message::User("I pick the lock.".to_string()),
+        ];
+        let context = GameContext {
+            world: Default::default(),
+            player: Default::default(),
+            party: Vec::new(),
+            history,
+            history_limit: None,
+            history_start: 0,
+            snapshot: None,
+            history_token_budget: None,
+            context_window: None,
+            prompt_templates: Default::default(),
+        };
+
+        let prompt = PromptBuilder::build(&context, "I pick the lock.");
+        let roles: Vec<&str> = prompt.messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["system", "user", "assistant", "user", "assistant", "user"]);
+
+        let system = &prompt.messages[0].content;
+        assert!(system.contains("WORLD DEFINITION"));
+        assert!(!system.contains("PLAYER ACTION"));
+        assert_eq!(prompt.messages[1].content, "Begin the story.");
+        assert_eq!(
+            prompt.messages[4].content,
+            "[NPC: Guard] Quiet!\n[NARRATOR] Footsteps fade."
+        );
+        let last = &prompt.messages[5].content;
+        assert!(last.starts_with("I pick the lock.\n\n"));
+        assert!(last.contains("PLAYER ACTION:\nI pick the lock."));
+
+        assert_eq!(prompt.chat_messages(false), vec![ChatMessage::system(prompt.text.clone())]);
+    }
+}
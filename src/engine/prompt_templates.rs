@@ -0,0 +1,182 @@
+use minijinja::{context, Environment};
+
+use crate::model::event_schema::{event_type_lines, FREEFORM_EVENTS};
+use crate::model::game_context::GameContext;
+
+/// Named prompt sections rendered from templates. Each template sees
+/// `world`, `player`, `party`, `snapshot` (may be none), `followup`,
+/// `event_types` (the event list generated from `NarrativeEvent`) and
+/// `freeform_event_types` (the subset freeform mode uses).
+/// Overrides come from the world file first, then the user config; anything
+/// not overridden uses the built-in default below.
+pub const TEMPLATE_DEFAULTS: &[(&str, &str)] = &[
+    ("game_rules", include_str!("prompt_templates/game_rules.jinja")),
+    ("game_event_notes", include_str!("prompt_templates/game_event_notes.jinja")),
+    ("game_npc_tracking", include_str!("prompt_templates/game_npc_tracking.jinja")),
+    ("game_party_tracking", include_str!("prompt_templates/game_party_tracking.jinja")),
+    ("game_card_tracking", include_str!("prompt_templates/game_card_tracking.jinja")),
+    ("game_equipment", include_str!("prompt_templates/game_equipment.jinja")),
+    ("game_power_gain", include_str!("prompt_templates/game_power_gain.jinja")),
+    ("game_power_usage", include_str!("prompt_templates/game_power_usage.jinja")),
+    ("game_factions", include_str!("prompt_templates/game_factions.jinja")),
+    ("game_request_context", include_str!("prompt_templates/game_request_context.jinja")),
+    ("game_optional_tabs", include_str!("prompt_templates/game_optional_tabs.jinja")),
+    ("game_quest_rules", include_str!("prompt_templates/game_quest_rules.jinja")),
+    ("game_followup_rules", include_str!("prompt_templates/game_followup_rules.jinja")),
+    ("game_class_evolution", include_str!("prompt_templates/game_class_evolution.jinja")),
+    ("game_failure_response", include_str!("prompt_templates/game_failure_response.jinja")),
+    ("game_reminder", include_str!("prompt_templates/game_reminder.jinja")),
+    ("freeform_rules", include_str!("prompt_templates/freeform_rules.jinja")),
+    ("freeform_request_context", include_str!("prompt_templates/freeform_request_context.jinja")),
+    ("freeform_reminder", include_str!("prompt_templates/freeform_reminder.jinja")),
+];
+
+/// Sections that make up the RPG system prompt, in order.
+pub const GAME_SYSTEM_SECTIONS: &[&str] = &[
+    "game_rules",
+    "game_event_notes",
+    "game_npc_tracking",
+    "game_party_tracking",
+    "game_card_tracking",
+    "game_equipment",
+    "game_power_gain",
+    "game_power_usage",
+    "game_factions",
+    "game_request_context",
+    "game_optional_tabs",
+    "game_quest_rules",
+    "game_followup_rules",
+    "game_class_evolution",
+    "game_failure_response",
+];
+
+/// Sections that make up the freeform system prompt, in order.
+pub const FREEFORM_SYSTEM_SECTIONS: &[&str] = &["freeform_rules", "freeform_request_context"];
+
+pub fn default_template(name: &str) -> Option<&'static str> {
+    TEMPLATE_DEFAULTS
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, source)| *source)
+}
+
+/// The template source in effect for `name`: world override, then user
+/// config override, then the default.
+pub fn template_source<'a>(name: &str, context: &'a GameContext) -> Option<&'a str> {
+    context
+        .world
+        .prompt_templates
+        .get(name)
+        .or_else(|| context.prompt_templates.get(name))
+        .map(String::as_str)
+        .or_else(|| default_template(name))
+}
+
+/// Render one section. An override that fails to render falls back to the
+/// default text; the error is returned alongside so it can be surfaced.
+pub fn render_section(
+    name: &str,
+    context: &GameContext,
+    followup: bool,
+) -> (String, Option<String>) {
+    let Some(source) = template_source(name, context) else {
+        return (String::new(), Some(format!("Unknown prompt template '{}'", name)));
+    };
+    match render_source(source, context, followup) {
+        Ok(text) => (text, None),
+        Err(err) => {
+            let warning = format!("Prompt template '{}' failed, using default: {}", name, err);
+            let fallback = default_template(name)
+                .and_then(|source| render_source(source, context, followup).ok())
+                .unwrap_or_default();
+            (fallback, Some(warning))
+        }
+    }
+}
+
+/// Check that a template parses; used by the editor before saving.
+pub fn validate_template(source: &str) -> Result<(), String> {
+    environment()
+        .template_from_str(source)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+fn render_source(source: &str, context: &GameContext, followup: bool) -> Result<String, String> {
+    environment()
+        .render_str(
+            source,
+            context! {
+                world => &context.world,
+                player => &context.player,
+                party => &context.party,
+                snapshot => &context.snapshot,
+                followup => followup,
+                event_types => event_type_lines(None),
+                freeform_event_types => event_type_lines(Some(FREEFORM_EVENTS)),
+            },
+        )
+        .map_err(|err| err.to_string())
+}
+
+/// Block tags on their own line leave no blank lines behind, so templates
+/// can be written one rule per line.
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> GameContext {
+        GameContext {
+            world: Default::default(),
+            player: Default::default(),
+            party: Vec::new(),
+            history: Vec::new(),
+            history_limit: None,
+            history_start: 0,
+            snapshot: None,
+            history_token_budget: None,
+            context_window: None,
+            prompt_templates: Default::default(),
+        }
+    }
+
+    #[test]
+    fn world_override_beats_user_config_and_bad_template_falls_back() {
+        let mut context = context();
+        context.world.title = "Ashfall".to_string();
+        context
+            .prompt_templates
+            .insert("game_factions".to_string(), "user text\n".to_string());
+        context.world.prompt_templates.insert(
+            "game_factions".to_string(),
+            "Factions of {{ world.title }}\n".to_string(),
+        );
+        let (text, warning) = render_section("game_factions", &context, false);
+        assert_eq!(text, "Factions of Ashfall\n");
+        assert!(warning.is_none());
+
+        context
+            .world
+            .prompt_templates
+            .insert("game_factions".to_string(), "{% if %}".to_string());
+        let (text, warning) = render_section("game_factions", &context, false);
+        assert!(text.starts_with("Factions & Reputation:"));
+        assert!(warning.is_some());
+    }
+
+    #[test]
+    fn freeform_event_list_is_generated_from_the_schema() {
+        let (text, warning) = render_section("freeform_rules", &context(), false);
+        assert!(warning.is_none());
+        assert!(text.contains("- npc_mission { id, mission, destination?, duration_minutes }"));
+        assert!(!text.contains("- add_exp"));
+    }
+}
@@ -3,6 +3,7 @@ use crate::model::event_result::NarrativeApplyReport;
 use crate::model::game_state::GameStateSnapshot;
 use crate::model::game_context::GameContext;
 use crate::model::game_save::GameSave;
+use crate::model::narrative_event::NarrativeEvent;
 use crate::engine::llm_client::LlmConfig;
 
 #[derive(Debug)]
@@ -71,6 +72,22 @@ pub enum EngineCommand {
         id: String,
     },
 
+    /// UI-driven: accept or decline a pending quest offer
+    AnswerQuestOffer {
+        quest_id: String,
+        accept: bool,
+    },
+    /// UI-driven: take one of a quest's reward options
+    PickQuestReward {
+        quest_id: String,
+        option: String,
+    },
+    /// UI-driven: ask the quest giver for different rewards
+    CounterQuestReward {
+        quest_id: String,
+        rewards: Vec<String>,
+    },
+
     /// UI-driven: lock party member fields to prevent LLM/engine edits
     SetPartyMemberLocks {
         id: String,
@@ -94,6 +111,19 @@ pub enum EngineCommand {
     SetNpcRecencyLimit {
         limit: usize,
     },
+    /// UI-driven: summarize older story messages every N turns (0 = off)
+    SetStorySummaryInterval {
+        turns: u32,
+    },
+    /// UI-driven: hold events the world doesn't auto-approve for review
+    SetEventReview {
+        enabled: bool,
+    },
+    /// UI-driven: the player's decision on `EventsAwaitingReview`
+    ResolveEventReview {
+        approved: Vec<NarrativeEvent>,
+        rejected: Vec<NarrativeEvent>,
+    },
 
     SaveGame {
         path: std::path::PathBuf,
@@ -113,6 +143,7 @@ pub enum EngineCommand {
 
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum EngineResponse {
     FullMessageHistory(Vec<Message>),
     AppendMessages(Vec<Message>),
@@ -128,5 +159,16 @@ pub enum EngineResponse {
     LlmConnectionResult {
         success: bool,
         message: String,
+        /// Models reported by the backend, empty on failure
+        models: Vec<String>,
+    },
+    /// Background summary finished; `note` is a debug line for the log
+    SummaryApplied {
+        snapshot: GameStateSnapshot,
+        note: String,
+    },
+    /// Events held back until the player approves them
+    EventsAwaitingReview {
+        events: Vec<NarrativeEvent>,
     },
 }
@@ -1,9 +1,14 @@
 mod ui;
 mod engine;
 mod model;
-use eframe;
 
 fn main() -> eframe::Result<()> {
+    if std::env::args().any(|arg| arg == "--print-schema") {
+        let schema = model::event_schema::events_json_schema();
+        println!("{}", serde_json::to_string_pretty(&schema).unwrap_or_default());
+        return Ok(());
+    }
+
     let options = eframe::NativeOptions::default();
 
     eframe::run_native(
@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::narrative_event::NarrativeEvent;
+
+pub const MINUTES_PER_DAY: u64 = 24 * 60;
+
+/// How a world names its dates: months, weekdays, seasons and the phases
+/// of the day. World time 0 is the `start` date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Calendar {
+    pub months: Vec<CalendarMonth>,
+    pub weekdays: Vec<String>,
+    /// Each season runs from its start month until the next one begins
+    pub seasons: Vec<Season>,
+    /// Each phase runs from its start hour until the next one begins
+    pub day_phases: Vec<DayPhase>,
+    pub start: CalendarStart,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarMonth {
+    pub name: String,
+    pub days: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Season {
+    pub name: String,
+    /// 1-based month the season begins in
+    pub start_month: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayPhase {
+    pub name: String,
+    pub start_hour: u32,
+}
+
+/// The date and hour the story begins at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarStart {
+    pub year: i32,
+    /// 1-based
+    pub month: u32,
+    /// 1-based
+    pub day: u32,
+    pub hour: u32,
+    /// 0-based index into `weekdays` of the start date
+    pub weekday: u32,
+}
+
+impl Default for CalendarStart {
+    fn default() -> Self {
+        Self { year: 1, month: 1, day: 1, hour: 8, weekday: 0 }
+    }
+}
+
+impl Default for Calendar {
+    /// No months, so dates fall back to elapsed time until a world
+    /// defines its calendar or picks the Gregorian preset.
+    fn default() -> Self {
+        Self {
+            months: Vec::new(),
+            weekdays: Vec::new(),
+            seasons: Vec::new(),
+            day_phases: Vec::new(),
+            start: CalendarStart::default(),
+        }
+    }
+}
+
+impl Calendar {
+    /// Real-world months and weekdays with northern seasons.
+    pub fn gregorian() -> Self {
+        let months = [
+            ("January", 31),
+            ("February", 28),
+            ("March", 31),
+            ("April", 30),
+            ("May", 31),
+            ("June", 30),
+            ("July", 31),
+            ("August", 31),
+            ("September", 30),
+            ("October", 31),
+            ("November", 30),
+            ("December", 31),
+        ];
+        let weekdays = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+        let seasons = [("Spring", 3), ("Summer", 6), ("Autumn", 9), ("Winter", 12)];
+        let day_phases = [
+            ("Night", 0),
+            ("Dawn", 5),
+            ("Morning", 7),
+            ("Midday", 11),
+            ("Afternoon", 13),
+            ("Evening", 17),
+            ("Dusk", 19),
+            ("Night", 21),
+        ];
+        Self {
+            months: months
+                .into_iter()
+                .map(|(name, days)| CalendarMonth { name: name.to_string(), days })
+                .collect(),
+            weekdays: weekdays.into_iter().map(str::to_string).collect(),
+            seasons: seasons
+                .into_iter()
+                .map(|(name, start_month)| Season { name: name.to_string(), start_month })
+                .collect(),
+            day_phases: day_phases
+                .into_iter()
+                .map(|(name, start_hour)| DayPhase { name: name.to_string(), start_hour })
+                .collect(),
+            start: CalendarStart::default(),
+        }
+    }
+}
+
+/// A duration in world minutes, e.g. "2d 4h 15m".
+pub fn format_elapsed(minutes: u64) -> String {
+    format!(
+        "{}d {}h {}m",
+        minutes / MINUTES_PER_DAY,
+        (minutes / 60) % 24,
+        minutes % 60
+    )
+}
+
+/// A world time expressed in calendar terms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarMoment {
+    pub year: i32,
+    pub month: String,
+    /// 1-based day of the month
+    pub day: u32,
+    pub weekday: Option<String>,
+    pub season: Option<String>,
+    pub phase: Option<String>,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl CalendarMoment {
+    /// e.g. "Morning, Tuesday 3 March, Year 1 (Spring), 08:15".
+    pub fn describe(&self) -> String {
+        let mut s = String::new();
+        if let Some(phase) = &self.phase {
+            s.push_str(&format!("{}, ", phase));
+        }
+        if let Some(weekday) = &self.weekday {
+            s.push_str(&format!("{} ", weekday));
+        }
+        s.push_str(&format!("{} {}, Year {}", self.day, self.month, self.year));
+        if let Some(season) = &self.season {
+            s.push_str(&format!(" ({})", season));
+        }
+        s.push_str(&format!(", {:02}:{:02}", self.hour, self.minute));
+        s
+    }
+}
+
+impl Calendar {
+    fn year_days(&self) -> u64 {
+        self.months.iter().map(|m| u64::from(m.days.max(1))).sum()
+    }
+
+    /// Minutes from the start of the start year to the start date and hour.
+    fn start_offset(&self) -> u64 {
+        let month = (self.start.month.max(1) as usize - 1).min(self.months.len().saturating_sub(1));
+        let days_before: u64 = self.months[..month].iter().map(|m| u64::from(m.days.max(1))).sum();
+        let day = u64::from(self.start.day.max(1) - 1);
+        (days_before + day) * MINUTES_PER_DAY + u64::from(self.start.hour.min(23)) * 60
+    }
+
+    /// The calendar date `world_minutes` after the start, or `None` when
+    /// the calendar has no months.
+    pub fn moment(&self, world_minutes: u64) -> Option<CalendarMoment> {
+        if self.months.is_empty() {
+            return None;
+        }
+        let total = self.start_offset() + world_minutes;
+        let day_index = total / MINUTES_PER_DAY;
+        let minute_of_day = total % MINUTES_PER_DAY;
+        let hour = (minute_of_day / 60) as u32;
+
+        let year_days = self.year_days();
+        let year = self.start.year + (day_index / year_days) as i32;
+        let mut day_of_year = day_index % year_days;
+        let mut month_number = 1;
+        let mut month = &self.months[0];
+        for (i, m) in self.months.iter().enumerate() {
+            let days = u64::from(m.days.max(1));
+            if day_of_year < days {
+                month_number = i as u32 + 1;
+                month = m;
+                break;
+            }
+            day_of_year -= days;
+        }
+
+        let weekday = (!self.weekdays.is_empty()).then(|| {
+            let days_since_start = (u64::from(self.start.hour.min(23)) * 60 + world_minutes) / MINUTES_PER_DAY;
+            let index = (u64::from(self.start.weekday) + days_since_start) % self.weekdays.len() as u64;
+            self.weekdays[index as usize].clone()
+        });
+        let season = latest_at_or_before(&self.seasons, month_number, |s| s.start_month)
+            .map(|s| s.name.clone());
+        let phase = latest_at_or_before(&self.day_phases, hour, |p| p.start_hour).map(|p| p.name.clone());
+
+        Some(CalendarMoment {
+            year,
+            month: month.name.clone(),
+            day: day_of_year as u32 + 1,
+            weekday,
+            season,
+            phase,
+            hour,
+            minute: (minute_of_day % 60) as u32,
+        })
+    }
+
+    /// The date for `world_minutes`, falling back to the time since the
+    /// story began.
+    pub fn describe(&self, world_minutes: u64) -> String {
+        match self.moment(world_minutes) {
+            Some(moment) => moment.describe(),
+            None => format!("{} in", format_elapsed(world_minutes)),
+        }
+    }
+
+    /// World minutes for `hour` o'clock on `day` (1 = the start date).
+    /// Times before the story starts clamp to 0.
+    pub fn minutes_at(&self, day: u64, hour: u32) -> u64 {
+        let at = day.saturating_sub(1) * MINUTES_PER_DAY + u64::from(hour.min(23)) * 60;
+        at.saturating_sub(u64::from(self.start.hour.min(23)) * 60)
+    }
+
+    /// The inverse of `minutes_at`: (day, hour) for a world time.
+    pub fn day_and_hour(&self, world_minutes: u64) -> (u64, u32) {
+        let total = world_minutes + u64::from(self.start.hour.min(23)) * 60;
+        (total / MINUTES_PER_DAY + 1, ((total % MINUTES_PER_DAY) / 60) as u32)
+    }
+}
+
+/// The entry with the largest key at or before `value`, wrapping around to
+/// the largest key overall (e.g. Winter in January).
+fn latest_at_or_before<T>(items: &[T], value: u32, key: impl Fn(&T) -> u32) -> Option<&T> {
+    items
+        .iter()
+        .filter(|item| key(item) <= value)
+        .max_by_key(|item| key(item))
+        .or_else(|| items.iter().max_by_key(|item| key(item)))
+}
+
+/// A world event that fires once world time reaches `at_minutes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// World time it next fires at
+    pub at_minutes: u64,
+    /// Fires again this long after each time; `None` fires once
+    #[serde(default)]
+    pub repeat_minutes: Option<u64>,
+    /// Engine events applied when it fires
+    #[serde(default)]
+    pub effects: Vec<NarrativeEvent>,
+}
+
+/// A scheduled event that fired, kept for the next prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiredWorldEvent {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub at_minutes: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moments_roll_over_months_years_and_weekdays() {
+        let calendar = Calendar::gregorian();
+        let start = calendar.moment(0).unwrap();
+        assert_eq!(start.describe(), "Morning, Monday 1 January, Year 1 (Winter), 08:00");
+
+        // 31 days and 13 hours later: 1 February, 21:00
+        let later = calendar.moment((31 * 24 + 13) * 60).unwrap();
+        assert_eq!((later.month.as_str(), later.day, later.hour), ("February", 1, 21));
+        assert_eq!(later.weekday.as_deref(), Some("Thursday"));
+        assert_eq!(later.phase.as_deref(), Some("Night"));
+
+        let next_year = calendar.moment(365 * MINUTES_PER_DAY).unwrap();
+        assert_eq!((next_year.year, next_year.day), (2, 1));
+
+        assert_eq!(calendar.minutes_at(2, 8), MINUTES_PER_DAY);
+        assert_eq!(calendar.day_and_hour(MINUTES_PER_DAY + 60), (2, 9));
+
+        let empty = Calendar::default();
+        assert!(empty.moment(0).is_none());
+        assert_eq!(empty.describe(MINUTES_PER_DAY + 15), "1d 0h 15m in");
+    }
+}
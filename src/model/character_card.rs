@@ -0,0 +1,204 @@
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD};
+use base64::engine::DecodePaddingMode;
+use base64::Engine as _;
+use serde_json::{json, Value};
+
+use crate::model::lorebook::Lorebook;
+
+/// PNG tEXt keyword SillyTavern uses for V1/V2 cards (base64 JSON).
+pub const CARD_V2_PNG_KEY: &str = "chara";
+/// PNG tEXt keyword for V3 cards; preferred over `chara` when both exist.
+pub const CARD_V3_PNG_KEY: &str = "ccv3";
+
+/// Cards in the wild come padded or not, and some use the URL-safe alphabet.
+const CARD_DECODE_CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const CARD_BASE64: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, CARD_DECODE_CONFIG);
+const CARD_BASE64_URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, CARD_DECODE_CONFIG);
+
+/// The parts of a community character card (V1, V2 or V3) we can use.
+#[derive(Debug, Clone, Default)]
+pub struct CharacterCard {
+    pub name: String,
+    pub description: String,
+    pub personality: String,
+    pub scenario: String,
+    pub first_message: String,
+    pub tags: Vec<String>,
+    pub creator_notes: String,
+    pub character_book: Option<Lorebook>,
+    /// Our own character JSON, when the card was exported by this app
+    pub upf_character: Option<Value>,
+}
+
+impl CharacterCard {
+    /// Parse card JSON. V2/V3 keep their fields under `data`; V1 is flat.
+    pub fn from_json_str(data: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        Self::from_json_value(&value)
+    }
+
+    pub fn from_json_value(value: &Value) -> Result<Self, String> {
+        let data = match value.get("spec").and_then(Value::as_str) {
+            Some("chara_card_v2") | Some("chara_card_v3") => value
+                .get("data")
+                .ok_or_else(|| "Character card has no data".to_string())?,
+            Some(other) => return Err(format!("Unsupported card spec '{}'", other)),
+            None => value,
+        };
+
+        let text = |key: &str| {
+            data.get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let name = text("name");
+        if name.is_empty() {
+            return Err("Character card has no name".to_string());
+        }
+
+        let tags = data
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(Value::as_str)
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let character_book = data
+            .get("character_book")
+            .filter(|book| !book.is_null())
+            .and_then(|book| Lorebook::from_json_value(book).ok())
+            .filter(|book| !book.entries.is_empty());
+
+        Ok(Self {
+            name,
+            description: text("description"),
+            personality: text("personality"),
+            scenario: text("scenario"),
+            first_message: text("first_mes"),
+            tags,
+            creator_notes: text("creator_notes"),
+            character_book,
+            upf_character: data.pointer("/extensions/upf/character").cloned(),
+        })
+    }
+
+    /// Decode the base64 payload of a `chara`/`ccv3` PNG text chunk.
+    pub fn from_png_text(text: &str) -> Result<Self, String> {
+        let compact: String = text.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+        let bytes = CARD_BASE64
+            .decode(&compact)
+            .or_else(|_| CARD_BASE64_URL_SAFE.decode(&compact))
+            .map_err(|e| format!("Card data is not valid base64: {}", e))?;
+        let json = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        Self::from_json_str(&json)
+    }
+
+    /// Free-form notes for an NPC or party member built from this card.
+    pub fn details(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.description.is_empty() {
+            parts.push(self.description.clone());
+        }
+        if !self.personality.is_empty() {
+            parts.push(format!("Personality: {}", self.personality));
+        }
+        if !self.scenario.is_empty() {
+            parts.push(format!("Scenario: {}", self.scenario));
+        }
+        parts.join("\n\n")
+    }
+
+    /// A short role label, taken from the first tag when there is one.
+    pub fn role(&self) -> String {
+        self.tags
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "Character".to_string())
+    }
+
+    /// Serialize as a Character Card V2. `upf_character` is stored under
+    /// `extensions.upf` so our own fields survive a round trip.
+    pub fn to_v2_json(&self) -> Value {
+        let mut data = json!({
+            "name": self.name,
+            "description": self.description,
+            "personality": self.personality,
+            "scenario": self.scenario,
+            "first_mes": self.first_message,
+            "mes_example": "",
+            "creator_notes": self.creator_notes,
+            "system_prompt": "",
+            "post_history_instructions": "",
+            "alternate_greetings": [],
+            "tags": self.tags,
+            "creator": "",
+            "character_version": "",
+            "extensions": {},
+        });
+        if let Some(character) = &self.upf_character {
+            data["extensions"]["upf"] = json!({ "character": character });
+        }
+        if let Some(book) = &self.character_book {
+            data["character_book"] = book.to_character_book(&format!("{} lore", self.name));
+        }
+        json!({
+            "spec": "chara_card_v2",
+            "spec_version": "2.0",
+            "data": data,
+        })
+    }
+
+    /// Base64 payload for a `chara` PNG text chunk.
+    pub fn to_png_text(&self) -> String {
+        STANDARD.encode(self.to_v2_json().to_string().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v2_card_round_trips_through_base64_with_its_lorebook() {
+        let card_json = r#"{
+            "spec": "chara_card_v2",
+            "spec_version": "2.0",
+            "data": {
+                "name": "Seraphina",
+                "description": "A forest guardian.",
+                "personality": "Kind, protective",
+                "first_mes": "You wake in a glade.",
+                "tags": ["Healer", "Fantasy"],
+                "character_book": {
+                    "entries": [
+                        { "keys": ["glade"], "content": "A hidden clearing.", "insertion_order": 5, "enabled": true }
+                    ]
+                }
+            }
+        }"#;
+        let card = CharacterCard::from_png_text(&STANDARD.encode(card_json.as_bytes())).unwrap();
+        assert_eq!(card.name, "Seraphina");
+        assert_eq!(card.role(), "Healer");
+        assert!(card.details().contains("Personality: Kind, protective"));
+        assert_eq!(card.character_book.as_ref().unwrap().entries[0].priority, 5);
+
+        let again = CharacterCard::from_png_text(&card.to_png_text()).unwrap();
+        assert_eq!(again.first_message, "You wake in a glade.");
+        assert_eq!(again.character_book.unwrap().entries[0].keywords, vec!["glade"]);
+
+        // Unpadded, URL-safe and line-wrapped payloads still decode.
+        let loose = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(card_json.as_bytes());
+        let (head, tail) = loose.split_at(40);
+        let loose = CharacterCard::from_png_text(&format!("{}\n{}", head, tail)).unwrap();
+        assert_eq!(loose.name, "Seraphina");
+        assert!(CharacterCard::from_png_text("not base64!").is_err());
+    }
+}
@@ -19,8 +19,49 @@ pub struct EventApplication {
     pub event: NarrativeEvent,
     pub outcome: EventApplyOutcome,
 }
+
+impl EventApplication {
+    /// One line such as "modify_stat rejected: <reason>".
+    pub fn summary(&self) -> String {
+        let event_type = self.event.type_name();
+        match &self.outcome {
+            EventApplyOutcome::Applied => format!("{} applied", event_type),
+            EventApplyOutcome::Rejected { reason } => format!("{} rejected: {}", event_type, reason),
+            EventApplyOutcome::Deferred { reason } => format!("{} deferred: {}", event_type, reason),
+        }
+    }
+
+    pub fn is_applied(&self) -> bool {
+        matches!(self.outcome, EventApplyOutcome::Applied)
+    }
+}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NarrativeApplyReport {
     /// One entry per requested event, in order
     pub applications: Vec<EventApplication>,
+    /// Factions whose named reputation tier changed while applying them
+    #[serde(default)]
+    pub tier_changes: Vec<FactionTierChange>,
+    /// Quests the engine failed on its own, e.g. when a deadline passed
+    #[serde(default)]
+    pub quest_failures: Vec<String>,
+}
+
+/// A faction's reputation crossed into a different named tier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactionTierChange {
+    pub faction_id: String,
+    pub faction_name: String,
+    pub from: String,
+    pub to: String,
+    pub reputation: i32,
+    /// The new tier is above the old one
+    pub improved: bool,
+}
+
+impl FactionTierChange {
+    /// One line such as "Iron Guild: Neutral -> Friendly (12)".
+    pub fn summary(&self) -> String {
+        format!("{}: {} -> {} ({})", self.faction_name, self.from, self.to, self.reputation)
+    }
 }
@@ -0,0 +1,536 @@
+use serde_json::{json, Map, Value};
+
+/// The shape of one event field, as the LLM should write it.
+#[derive(Debug, Clone, Copy)]
+pub enum FieldKind {
+    Text,
+    Integer,
+    /// Integer >= 1
+    Count,
+    Flag,
+    TextList,
+    /// Object of name -> integer (stat modifiers)
+    IntegerMap,
+    /// A single string or a list of strings
+    TextOrTextList,
+    QuestStatus,
+    /// `allied`, `hostile` or `neutral`
+    Stance,
+    /// Sub-quest steps; `description` is required when creating a quest
+    QuestSteps { description_required: bool },
+    /// NPC daily schedule: `{ hour, location, activity? }` slots
+    NpcSchedule,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+}
+
+/// One `NarrativeEvent` variant, by its serde tag.
+#[derive(Debug, Clone, Copy)]
+pub struct EventSpec {
+    pub name: &'static str,
+    pub fields: &'static [FieldSpec],
+}
+
+const fn req(name: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { name, kind, required: true }
+}
+
+const fn opt(name: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { name, kind, required: false }
+}
+
+use FieldKind::*;
+
+/// Every event the LLM may emit, in `NarrativeEvent` order. This table is
+/// the single source for the JSON schema, the GBNF grammar and the event
+/// list in the prompt; the test below keeps it in step with the enum.
+pub const EVENT_SPECS: &[EventSpec] = &[
+    EventSpec { name: "grant_power", fields: &[req("id", Text), req("name", Text), req("description", Text)] },
+    EventSpec { name: "combat", fields: &[req("description", Text)] },
+    EventSpec { name: "dialogue", fields: &[req("speaker", Text), req("text", Text)] },
+    EventSpec { name: "travel", fields: &[req("from", Text), req("to", Text)] },
+    EventSpec { name: "rest", fields: &[req("description", Text)] },
+    EventSpec {
+        name: "craft",
+        fields: &[
+            req("recipe", Text),
+            opt("quantity", Count),
+            opt("quality", Text),
+            opt("result", Text),
+            opt("set_id", Text),
+        ],
+    },
+    EventSpec {
+        name: "gather",
+        fields: &[req("resource", Text), opt("quantity", Count), opt("quality", Text), opt("set_id", Text)],
+    },
+    EventSpec { name: "add_party_member", fields: &[req("id", Text), req("name", Text), req("role", Text)] },
+    EventSpec {
+        name: "party_update",
+        fields: &[
+            req("id", Text),
+            opt("name", Text),
+            opt("role", Text),
+            opt("details", Text),
+            opt("clothing_add", TextList),
+            opt("clothing_remove", TextList),
+            opt("weapons_add", TextList),
+            opt("weapons_remove", TextList),
+            opt("armor_add", TextList),
+            opt("armor_remove", TextList),
+        ],
+    },
+    EventSpec {
+        name: "section_card_upsert",
+        fields: &[
+            req("section", Text),
+            req("id", Text),
+            req("name", Text),
+            opt("role", Text),
+            opt("status", Text),
+            opt("details", Text),
+            opt("notes", Text),
+            opt("tags", TextList),
+            opt("items", TextList),
+        ],
+    },
+    EventSpec { name: "section_card_remove", fields: &[req("section", Text), req("id", Text)] },
+    EventSpec {
+        name: "player_card_update",
+        fields: &[
+            opt("name", Text),
+            opt("role", Text),
+            opt("status", Text),
+            opt("details", Text),
+            opt("notes", Text),
+            opt("tags", TextList),
+            opt("items", TextList),
+        ],
+    },
+    EventSpec { name: "time_passed", fields: &[req("minutes", Count), opt("reason", Text)] },
+    EventSpec {
+        name: "schedule_event",
+        fields: &[
+            req("id", Text),
+            req("name", Text),
+            opt("description", Text),
+            req("in_minutes", Count),
+            opt("repeat_minutes", Count),
+        ],
+    },
+    EventSpec {
+        name: "apply_status_effect",
+        fields: &[
+            req("id", Text),
+            req("name", Text),
+            opt("description", Text),
+            opt("modifiers", IntegerMap),
+            opt("duration_minutes", Count),
+            opt("duration_turns", Count),
+        ],
+    },
+    EventSpec { name: "remove_status_effect", fields: &[req("id", Text)] },
+    EventSpec {
+        name: "npc_spawn",
+        fields: &[opt("id", Text), req("name", Text), req("role", Text), opt("details", Text)],
+    },
+    EventSpec {
+        name: "npc_join_party",
+        fields: &[
+            opt("id", Text),
+            opt("name", Text),
+            opt("role", Text),
+            opt("details", Text),
+            opt("clothing", TextList),
+            opt("weapons", TextList),
+            opt("armor", TextList),
+        ],
+    },
+    EventSpec {
+        name: "npc_update",
+        fields: &[
+            opt("id", Text),
+            opt("name", Text),
+            opt("role", Text),
+            opt("details", Text),
+            opt("facts", TextList),
+            opt("player_told", TextList),
+            opt("promises", TextList),
+            opt("promises_resolved", TextList),
+            opt("location", Text),
+            opt("schedule", NpcSchedule),
+            opt("goals", TextList),
+            opt("goals_completed", TextList),
+        ],
+    },
+    EventSpec {
+        name: "npc_mission",
+        fields: &[
+            req("id", Text),
+            req("mission", Text),
+            opt("destination", Text),
+            req("duration_minutes", Count),
+        ],
+    },
+    EventSpec { name: "npc_despawn", fields: &[req("id", Text), opt("reason", Text)] },
+    EventSpec { name: "npc_leave_party", fields: &[req("id", Text)] },
+    EventSpec {
+        name: "relationship_change",
+        fields: &[
+            req("subject_id", Text),
+            req("target_id", Text),
+            req("delta", Integer),
+            opt("reason", Text),
+        ],
+    },
+    EventSpec { name: "modify_stat", fields: &[req("stat_id", Text), req("delta", Integer)] },
+    EventSpec { name: "add_exp", fields: &[req("amount", Count)] },
+    EventSpec { name: "level_up", fields: &[req("levels", Count)] },
+    EventSpec {
+        name: "equip_item",
+        fields: &[
+            req("item_id", Text),
+            req("slot", Text),
+            opt("set_id", Text),
+            opt("description", Text),
+            opt("modifiers", IntegerMap),
+        ],
+    },
+    EventSpec { name: "unequip_item", fields: &[req("item_id", Text)] },
+    EventSpec {
+        name: "start_quest",
+        fields: &[
+            req("id", Text),
+            req("title", Text),
+            req("description", Text),
+            opt("difficulty", Text),
+            opt("negotiable", Flag),
+            opt("reward_options", TextList),
+            opt("rewards", TextList),
+            opt("sub_quests", QuestSteps { description_required: true }),
+            opt("declinable", Flag),
+            opt("prerequisites", TextList),
+            opt("follow_ups", TextList),
+            opt("giver_npc", Text),
+            opt("giver_faction", Text),
+            opt("location", Text),
+            opt("deadline_minutes", Count),
+            opt("failure_conditions", TextList),
+        ],
+    },
+    EventSpec {
+        name: "offer_quest",
+        fields: &[
+            req("id", Text),
+            req("title", Text),
+            req("description", Text),
+            opt("difficulty", Text),
+            opt("negotiable", Flag),
+            opt("reward_options", TextList),
+            opt("rewards", TextList),
+            opt("sub_quests", QuestSteps { description_required: true }),
+            opt("declinable", Flag),
+            opt("prerequisites", TextList),
+            opt("follow_ups", TextList),
+            opt("giver_npc", Text),
+            opt("giver_faction", Text),
+            opt("location", Text),
+            opt("deadline_minutes", Count),
+            opt("failure_conditions", TextList),
+        ],
+    },
+    EventSpec {
+        name: "update_quest",
+        fields: &[
+            req("id", Text),
+            opt("title", Text),
+            opt("description", Text),
+            opt("status", QuestStatus),
+            opt("difficulty", Text),
+            opt("negotiable", Flag),
+            opt("reward_options", TextList),
+            opt("rewards", TextList),
+            opt("sub_quests", QuestSteps { description_required: false }),
+            opt("follow_ups", TextList),
+            opt("location", Text),
+            opt("deadline_minutes", Count),
+            opt("failure_conditions", TextList),
+        ],
+    },
+    EventSpec { name: "choose_reward", fields: &[req("quest_id", Text), req("option", Text)] },
+    EventSpec { name: "counter_offer", fields: &[req("quest_id", Text), req("rewards", TextList)] },
+    EventSpec { name: "settle_reward", fields: &[req("quest_id", Text), req("accepted", Flag)] },
+    EventSpec { name: "request_context", fields: &[req("topics", TextOrTextList)] },
+    EventSpec { name: "set_flag", fields: &[req("flag", Text)] },
+    EventSpec { name: "request_retcon", fields: &[req("reason", Text)] },
+    EventSpec {
+        name: "add_item",
+        fields: &[req("item_id", Text), req("quantity", Count), opt("set_id", Text)],
+    },
+    EventSpec {
+        name: "drop",
+        fields: &[req("item", Text), opt("quantity", Integer), opt("description", Text), opt("set_id", Text)],
+    },
+    EventSpec {
+        name: "spawn_loot",
+        fields: &[req("item", Text), opt("quantity", Integer), opt("description", Text), opt("set_id", Text)],
+    },
+    EventSpec { name: "currency_change", fields: &[req("currency", Text), req("delta", Integer)] },
+    EventSpec {
+        name: "faction_spawn",
+        fields: &[req("id", Text), req("name", Text), opt("kind", Text), opt("description", Text)],
+    },
+    EventSpec {
+        name: "faction_update",
+        fields: &[req("id", Text), opt("name", Text), opt("kind", Text), opt("description", Text)],
+    },
+    EventSpec { name: "faction_rep_change", fields: &[req("id", Text), req("delta", Integer)] },
+    EventSpec {
+        name: "faction_stance_change",
+        fields: &[req("id", Text), req("other_id", Text), req("stance", Stance)],
+    },
+    EventSpec {
+        name: "faction_membership",
+        fields: &[req("id", Text), req("member", Flag), opt("rank", Text)],
+    },
+];
+
+/// JSON Schema (draft 2020-12) for the EVENTS array.
+pub fn events_json_schema() -> Value {
+    let variants: Vec<Value> = EVENT_SPECS.iter().map(event_schema).collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "NarrativeEvents",
+        "type": "array",
+        "items": { "oneOf": variants },
+    })
+}
+
+/// Events the freeform narrator may emit: scene, NPC and time events, no
+/// RPG mechanics.
+pub const FREEFORM_EVENTS: &[&str] = &[
+    "combat",
+    "dialogue",
+    "travel",
+    "rest",
+    "npc_spawn",
+    "npc_update",
+    "npc_mission",
+    "npc_despawn",
+    "relationship_change",
+    "set_flag",
+    "section_card_upsert",
+    "section_card_remove",
+    "player_card_update",
+    "time_passed",
+    "schedule_event",
+    "request_context",
+];
+
+/// The event list shown to the narrator, one `- name { field, optional? }`
+/// line per event, limited to `allowed` when given.
+pub fn event_type_lines(allowed: Option<&[&str]>) -> String {
+    EVENT_SPECS
+        .iter()
+        .filter(|spec| allowed.is_none_or(|names| names.contains(&spec.name)))
+        .map(|spec| {
+            let fields: Vec<String> = spec
+                .fields
+                .iter()
+                .map(|f| if f.required { f.name.to_string() } else { format!("{}?", f.name) })
+                .collect();
+            format!("- {} {{ {} }}\n", spec.name, fields.join(", "))
+        })
+        .collect()
+}
+
+fn event_schema(spec: &EventSpec) -> Value {
+    let mut properties = Map::new();
+    properties.insert("type".to_string(), json!({ "const": spec.name }));
+    let mut required = vec![json!("type")];
+    for field in spec.fields {
+        properties.insert(field.name.to_string(), field_schema(field.kind));
+        if field.required {
+            required.push(json!(field.name));
+        }
+    }
+    object_schema(required, properties)
+}
+
+fn object_schema(required: Vec<Value>, properties: Map<String, Value>) -> Value {
+    json!({
+        "type": "object",
+        "additionalProperties": false,
+        "required": required,
+        "properties": properties,
+    })
+}
+
+fn field_schema(kind: FieldKind) -> Value {
+    let text_list = json!({ "type": "array", "items": { "type": "string" } });
+    match kind {
+        Text => json!({ "type": "string" }),
+        Integer => json!({ "type": "integer" }),
+        Count => json!({ "type": "integer", "minimum": 1 }),
+        Flag => json!({ "type": "boolean" }),
+        TextList => text_list,
+        IntegerMap => json!({ "type": "object", "additionalProperties": { "type": "integer" } }),
+        TextOrTextList => json!({ "oneOf": [{ "type": "string" }, text_list] }),
+        QuestStatus => json!({ "type": "string", "enum": ["active", "completed", "failed"] }),
+        Stance => json!({ "type": "string", "enum": ["allied", "hostile", "neutral"] }),
+        QuestSteps { description_required } => {
+            let mut required = vec![json!("id")];
+            if description_required {
+                required.push(json!("description"));
+            }
+            let mut properties = Map::new();
+            properties.insert("id".to_string(), json!({ "type": "string" }));
+            properties.insert("description".to_string(), json!({ "type": "string" }));
+            properties.insert("completed".to_string(), json!({ "type": "boolean" }));
+            properties.insert("optional".to_string(), json!({ "type": "boolean" }));
+            json!({ "type": "array", "items": object_schema(required, properties) })
+        }
+        NpcSchedule => {
+            let mut properties = Map::new();
+            properties.insert("hour".to_string(), json!({ "type": "integer", "minimum": 0, "maximum": 23 }));
+            properties.insert("location".to_string(), json!({ "type": "string" }));
+            properties.insert("activity".to_string(), json!({ "type": "string" }));
+            json!({
+                "type": "array",
+                "items": object_schema(vec![json!("hour"), json!("location")], properties),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::narrative_event::NarrativeEvent;
+    use std::collections::BTreeSet;
+
+    fn sample(kind: FieldKind) -> Value {
+        match kind {
+            Text => json!("x"),
+            Integer | Count => json!(1),
+            Flag => json!(true),
+            TextList | TextOrTextList => json!(["x"]),
+            IntegerMap => json!({ "strength": 1 }),
+            QuestStatus => json!("active"),
+            Stance => json!("hostile"),
+            QuestSteps { .. } => json!([{ "id": "s1", "description": "x", "completed": false }]),
+            NpcSchedule => json!([{ "hour": 8, "location": "x", "activity": "x" }]),
+        }
+    }
+
+    fn sample_event(spec: &EventSpec, skip: Option<&str>) -> Value {
+        let mut event = Map::new();
+        event.insert("type".to_string(), json!(spec.name));
+        for field in spec.fields.iter().filter(|f| Some(f.name) != skip) {
+            event.insert(field.name.to_string(), sample(field.kind));
+        }
+        Value::Object(event)
+    }
+
+    /// Defines `TAGS` and `tag()` from one list. `tag()` matches without a
+    /// wildcard, so a new `NarrativeEvent` variant does not compile until
+    /// it is listed here, and then fails the test until it has a spec.
+    macro_rules! event_tags {
+        ($($variant:ident => $name:literal,)*) => {
+            const TAGS: &[&str] = &[$($name),*];
+
+            fn tag(event: &NarrativeEvent) -> Option<&'static str> {
+                match event {
+                    $(NarrativeEvent::$variant { .. } => Some($name),)*
+                    NarrativeEvent::Unknown { .. } => None,
+                }
+            }
+        };
+    }
+
+    event_tags! {
+        GrantPower => "grant_power",
+        Combat => "combat",
+        Dialogue => "dialogue",
+        Travel => "travel",
+        Rest => "rest",
+        Craft => "craft",
+        Gather => "gather",
+        AddPartyMember => "add_party_member",
+        PartyUpdate => "party_update",
+        SectionCardUpsert => "section_card_upsert",
+        SectionCardRemove => "section_card_remove",
+        PlayerCardUpdate => "player_card_update",
+        TimePassed => "time_passed",
+        ScheduleEvent => "schedule_event",
+        ApplyStatusEffect => "apply_status_effect",
+        RemoveStatusEffect => "remove_status_effect",
+        NpcSpawn => "npc_spawn",
+        NpcJoinParty => "npc_join_party",
+        NpcUpdate => "npc_update",
+        NpcMission => "npc_mission",
+        NpcDespawn => "npc_despawn",
+        NpcLeaveParty => "npc_leave_party",
+        RelationshipChange => "relationship_change",
+        ModifyStat => "modify_stat",
+        AddExp => "add_exp",
+        LevelUp => "level_up",
+        EquipItem => "equip_item",
+        UnequipItem => "unequip_item",
+        StartQuest => "start_quest",
+        OfferQuest => "offer_quest",
+        UpdateQuest => "update_quest",
+        ChooseReward => "choose_reward",
+        CounterOffer => "counter_offer",
+        SettleReward => "settle_reward",
+        RequestContext => "request_context",
+        SetFlag => "set_flag",
+        RequestRetcon => "request_retcon",
+        AddItem => "add_item",
+        Drop => "drop",
+        SpawnLoot => "spawn_loot",
+        CurrencyChange => "currency_change",
+        FactionSpawn => "faction_spawn",
+        FactionUpdate => "faction_update",
+        FactionRepChange => "faction_rep_change",
+        FactionStanceChange => "faction_stance_change",
+        FactionMembership => "faction_membership",
+    }
+
+    #[test]
+    fn every_event_variant_is_described_by_the_schema() {
+        let variants: BTreeSet<&str> = TAGS.iter().copied().collect();
+        let described: BTreeSet<&str> = EVENT_SPECS.iter().map(|spec| spec.name).collect();
+        assert_eq!(variants, described, "EVENT_SPECS must list every NarrativeEvent variant");
+
+        for spec in EVENT_SPECS {
+            let event: NarrativeEvent = serde_json::from_value(sample_event(spec, None))
+                .unwrap_or_else(|e| panic!("{}: {}", spec.name, e));
+            assert_eq!(tag(&event), Some(spec.name));
+            let serialized = serde_json::to_value(&event).unwrap();
+            let fields: BTreeSet<&str> = serialized
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(String::as_str)
+                .filter(|key| *key != "type")
+                .collect();
+            let spec_fields: BTreeSet<&str> = spec.fields.iter().map(|f| f.name).collect();
+            assert_eq!(fields, spec_fields, "fields of '{}'", spec.name);
+
+            for field in spec.fields.iter().filter(|f| !f.required) {
+                assert!(
+                    serde_json::from_value::<NarrativeEvent>(sample_event(spec, Some(field.name))).is_ok(),
+                    "'{}.{}' is optional in the schema but required by the enum",
+                    spec.name,
+                    field.name
+                );
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::model::message::Message;
 use crate::model::game_state::GameStateSnapshot;
@@ -9,6 +10,23 @@ pub struct GameContext {
     pub world: WorldDefinition,
     pub player: CharacterDefinition,
     pub party: Vec<PartyMember>,
+    /// Filled in by the engine from its own message log
     pub history: Vec<Message>,
+    /// Most messages the engine puts in `history` (None = all)
+    #[serde(default)]
+    pub history_limit: Option<usize>,
+    /// Engine message index of `history[0]`; story chapters ending after the
+    /// first message sent verbatim are left out of the prompt
+    #[serde(default)]
+    pub history_start: usize,
     pub snapshot: Option<GameStateSnapshot>,
+    /// Token budget shared by story summaries and verbatim history (None = unlimited)
+    #[serde(default)]
+    pub history_token_budget: Option<usize>,
+    /// Model context window in tokens; the prompt is trimmed to fit (None = no limit)
+    #[serde(default)]
+    pub context_window: Option<usize>,
+    /// Prompt template overrides from the user config (world overrides win)
+    #[serde(default)]
+    pub prompt_templates: HashMap<String, String>,
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::model::calendar::{FiredWorldEvent, ScheduledEvent, MINUTES_PER_DAY};
+
 /// A full snapshot of the game state sent to LLMs.
 /// This is READ-ONLY outside the engine.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +19,9 @@ pub struct GameStateSnapshot {
     pub equipment: Vec<EquippedItem>,
     pub party: Vec<PartyMember>,
     pub quests: Vec<Quest>,
+    /// Quests offered to the player and not yet answered
+    #[serde(default)]
+    pub quest_offers: Vec<QuestOffer>,
     pub inventory: Vec<ItemStack>,
     pub loot: Vec<LootDrop>,
     pub currencies: Vec<CurrencyBalance>,
@@ -30,6 +35,28 @@ pub struct GameStateSnapshot {
     pub player_card: Option<CardEntry>,
     #[serde(default)]
     pub world_time_minutes: u64,
+    #[serde(default)]
+    pub npc_memories: HashMap<String, NpcMemory>,
+    #[serde(default)]
+    pub status_effects: Vec<StatusEffect>,
+    /// Computed stat view (base + level + set + equipment + effects).
+    #[serde(default)]
+    pub effective_stats: Vec<EffectiveStat>,
+    /// Condensed summaries of older story messages, oldest first
+    #[serde(default)]
+    pub story_chapters: Vec<StoryChapter>,
+    /// Why the last turn's events were held back
+    #[serde(default)]
+    pub engine_feedback: Vec<String>,
+    /// Upcoming world events, soonest first
+    #[serde(default)]
+    pub scheduled_events: Vec<ScheduledEvent>,
+    /// World events that fired since the last prompt
+    #[serde(default)]
+    pub fired_world_events: Vec<FiredWorldEvent>,
+    /// Where the player is, if known
+    #[serde(default)]
+    pub player_location: Option<String>,
 
     pub flags: Vec<String>,
 }
@@ -39,6 +66,49 @@ pub struct Stat {
     pub id: String,
     pub value: i32, 
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatSource {
+    Base,
+    Level,
+    Set,
+    Equipment,
+    Effect,
+}
+
+/// One line of an effective stat breakdown, e.g. (Set, "iron_set (2)", +1).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatContribution {
+    pub source: StatSource,
+    pub label: String,
+    pub value: i32,
+}
+
+/// A stat as the game sees it: base plus every active modifier.
+/// Computed on demand; never persisted back into the base stats.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveStat {
+    pub id: String,
+    pub base: i32,
+    pub total: i32,
+    #[serde(default)]
+    pub contributions: Vec<StatContribution>,
+}
+
+impl EffectiveStat {
+    /// "12 (base 10, level +1, set +1)"
+    pub fn breakdown_text(&self) -> String {
+        let mut parts = vec![format!("base {}", self.base)];
+        for c in &self.contributions {
+            if c.source == StatSource::Base {
+                continue;
+            }
+            parts.push(format!("{} {:+}", c.label, c.value));
+        }
+        format!("{} ({})", self.total, parts.join(", "))
+    }
+}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerState {
     pub name: String,
@@ -122,6 +192,64 @@ pub struct Quest {
     pub sub_quests: Vec<QuestStep>,
     #[serde(default)]
     pub rewards_claimed: bool,
+    /// Quest ids that must be completed before this one becomes active
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    /// Quest ids unlocked when this one is completed
+    #[serde(default)]
+    pub follow_ups: Vec<String>,
+    #[serde(default)]
+    pub giver_npc: Option<String>,
+    #[serde(default)]
+    pub giver_faction: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// World time (minutes) at which the quest fails if still active
+    #[serde(default)]
+    pub deadline_minutes: Option<u64>,
+    /// What makes the quest fail, for the narrator
+    #[serde(default)]
+    pub failure_conditions: Vec<String>,
+    #[serde(default)]
+    pub negotiation: RewardNegotiation,
+}
+
+/// A quest offered to the player, waiting on an explicit accept or decline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestOffer {
+    /// The quest as it starts once accepted; a giver NPC makes it an NPC
+    /// quest, otherwise the world is offering it
+    pub quest: Quest,
+    /// World minutes the player gets once they accept
+    #[serde(default)]
+    pub deadline_after_minutes: Option<u32>,
+    /// Player turns since the offer was made
+    #[serde(default)]
+    pub turns_waiting: u32,
+}
+
+impl QuestOffer {
+    /// "Fetch the herbs (from npc_mira)"
+    pub fn label(&self) -> String {
+        match &self.quest.giver_npc {
+            Some(giver) => format!("{} (from {})", self.quest.title, giver),
+            None => format!("{} (from the world)", self.quest.title),
+        }
+    }
+}
+
+/// Engine-tracked bargaining over a quest's rewards.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RewardNegotiation {
+    /// The player's counter-offer, waiting on the giver's answer
+    #[serde(default)]
+    pub counter_offer: Option<Vec<String>>,
+    /// Counter-offers made so far
+    #[serde(default)]
+    pub counter_offers: u32,
+    /// `rewards` are agreed and no longer change before completion
+    #[serde(default)]
+    pub locked: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -130,6 +258,9 @@ pub struct QuestStep {
     pub description: String,
     #[serde(default)]
     pub completed: bool,
+    /// Optional steps don't count towards auto-completion
+    #[serde(default)]
+    pub optional: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,6 +295,171 @@ pub struct Npc {
     pub notes: String,
     #[serde(default = "default_true")]
     pub nearby: bool,
+    /// Where the NPC is, what they do during the day and what they want
+    #[serde(default)]
+    pub routine: NpcRoutine,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NpcRoutine {
+    /// Current location, if known
+    #[serde(default)]
+    pub location: Option<String>,
+    /// What they are doing there, from the schedule
+    #[serde(default)]
+    pub activity: Option<String>,
+    /// Daily schedule; each slot runs from its hour until the next slot
+    #[serde(default)]
+    pub schedule: Vec<ScheduleSlot>,
+    #[serde(default)]
+    pub goals: Vec<String>,
+    /// Away on a mission; the schedule is paused until it ends
+    #[serde(default)]
+    pub mission: Option<NpcMission>,
+    /// World time `location` was set by hand; it holds until the next
+    /// schedule slot begins
+    #[serde(default)]
+    pub location_held_from: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleSlot {
+    pub hour: u32,
+    pub location: String,
+    #[serde(default)]
+    pub activity: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NpcMission {
+    pub description: String,
+    #[serde(default)]
+    pub destination: Option<String>,
+    pub started_minutes: u64,
+    pub return_minutes: u64,
+}
+
+impl NpcMission {
+    /// Percent done at `now_minutes`, 0..=100.
+    pub fn progress(&self, now_minutes: u64) -> u64 {
+        let total = self.return_minutes.saturating_sub(self.started_minutes).max(1);
+        let done = now_minutes.saturating_sub(self.started_minutes).min(total);
+        done * 100 / total
+    }
+}
+
+impl Npc {
+    /// Whether the NPC is with the player, judged by location. `None`
+    /// when either location is unknown, so chat recency decides instead.
+    pub fn nearby_by_location(&self, player_location: Option<&str>) -> Option<bool> {
+        if self.routine.mission.is_some() {
+            return Some(false);
+        }
+        let here = player_location?.trim();
+        let there = self.routine.location.as_deref()?.trim();
+        Some(here.eq_ignore_ascii_case(there))
+    }
+}
+
+impl NpcRoutine {
+    /// The schedule slot for `hour`: the latest slot starting at or before
+    /// it, wrapping around to the last slot of the previous day.
+    pub fn slot_at(&self, hour: u32) -> Option<&ScheduleSlot> {
+        self.schedule
+            .iter()
+            .filter(|slot| slot.hour <= hour)
+            .max_by_key(|slot| slot.hour)
+            .or_else(|| self.schedule.iter().max_by_key(|slot| slot.hour))
+    }
+
+    /// When the slot running at `clock_minutes` began, in the same clock
+    /// (minutes since midnight of day 0). `None` without a schedule or
+    /// when it began before day 0.
+    pub fn slot_began_at(&self, clock_minutes: u64) -> Option<u64> {
+        let midnight = clock_minutes - clock_minutes % MINUTES_PER_DAY;
+        self.schedule
+            .iter()
+            .filter_map(|slot| {
+                let start = midnight + u64::from(slot.hour) * 60;
+                if start <= clock_minutes {
+                    Some(start)
+                } else {
+                    start.checked_sub(MINUTES_PER_DAY)
+                }
+            })
+            .max()
+    }
+}
+
+/// What an NPC remembers about the player, kept outside the chat history so
+/// it survives once the conversation scrolls out of the prompt window.
+/// Keyed by NPC id, so it follows the NPC into and out of the party.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NpcMemory {
+    #[serde(default)]
+    pub key_facts: Vec<String>,
+    #[serde(default)]
+    pub player_told: Vec<String>,
+    #[serde(default)]
+    pub promises: Vec<String>,
+    /// World time (minutes) of the last exchange with the player
+    #[serde(default)]
+    pub last_interaction_minutes: Option<u64>,
+    /// Engine-written log of the latest exchanges, newest last
+    #[serde(default)]
+    pub recent_exchanges: Vec<NpcExchange>,
+    /// Exchanges that left the recent log, shortened, until the summarizer
+    /// folds them into the lists above
+    #[serde(default)]
+    pub earlier_exchanges: Vec<NpcExchange>,
+}
+
+impl NpcMemory {
+    pub fn is_empty(&self) -> bool {
+        self.key_facts.is_empty()
+            && self.player_told.is_empty()
+            && self.promises.is_empty()
+            && self.recent_exchanges.is_empty()
+            && self.earlier_exchanges.is_empty()
+    }
+}
+
+/// One thing the player said and what the NPC answered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NpcExchange {
+    /// World time of the exchange
+    pub at_minutes: u64,
+    pub player: String,
+    pub reply: String,
+}
+
+impl NpcExchange {
+    /// e.g. `Day 2 14:05 — Player: "Hello" / Mira: "Welcome back."`
+    pub fn describe(&self, name: &str) -> String {
+        format!(
+            "Day {} {:02}:{:02} — Player: \"{}\" / {}: \"{}\"",
+            self.at_minutes / MINUTES_PER_DAY,
+            (self.at_minutes / 60) % 24,
+            self.at_minutes % 60,
+            self.player,
+            name,
+            self.reply
+        )
+    }
+}
+
+/// A summary of a stretch of older chat, written by the summarizer so the
+/// story keeps its shape once those messages leave the prompt window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoryChapter {
+    pub index: u32,
+    pub summary: String,
+    /// Range of engine message indices covered, end exclusive
+    pub from_message: usize,
+    pub to_message: usize,
+    /// World time (minutes) when the chapter was written
+    #[serde(default)]
+    pub world_time_minutes: u64,
 }
 
 fn default_true() -> bool {
@@ -175,6 +471,47 @@ pub struct Relationship {
     pub subject_id: String,
     pub target_id: String,
     pub value: i32,
+    /// Latest changes and their reasons, oldest first
+    #[serde(default)]
+    pub history: Vec<RelationshipNote>,
+    /// World time from which the next decay is measured
+    #[serde(default)]
+    pub decay_from_minutes: u64,
+}
+
+/// Why a relationship changed, and when.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationshipNote {
+    pub delta: i32,
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub at_minutes: u64,
+}
+
+impl Relationship {
+    /// Key used in `InternalGameState::relationships`.
+    pub fn key(subject_id: &str, target_id: &str) -> String {
+        format!("{}::{}", subject_id, target_id)
+    }
+}
+
+/// A party member's morale: how they feel about the player, or how the
+/// player feels about them when only that is known.
+pub fn party_morale<'a>(
+    relationships: impl IntoIterator<Item = &'a Relationship>,
+    member_id: &str,
+) -> i32 {
+    let mut toward_player = None;
+    let mut from_player = None;
+    for rel in relationships {
+        let player = |id: &str| id.eq_ignore_ascii_case("player");
+        if rel.subject_id == member_id && player(&rel.target_id) {
+            toward_player = Some(rel.value);
+        } else if player(&rel.subject_id) && rel.target_id == member_id {
+            from_player = Some(rel.value);
+        }
+    }
+    toward_player.or(from_player).unwrap_or(0)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -185,6 +522,9 @@ pub struct EquippedItem {
     pub set_id: Option<String>,
     #[serde(default)]
     pub description: Option<String>,
+    /// Key = stat id, value = delta while equipped
+    #[serde(default)]
+    pub modifiers: HashMap<String, i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -196,6 +536,137 @@ pub struct FactionRep {
     #[serde(default)]
     pub description: Option<String>,
     pub reputation: i32,
+    /// Named tier the engine last reported for `reputation`
+    #[serde(default)]
+    pub tier: Option<String>,
+    /// Whether the player has joined this faction
+    #[serde(default)]
+    pub member: bool,
+    /// The player's rank, while a member
+    #[serde(default)]
+    pub rank: Option<String>,
+    /// Faction ids allied with this one
+    #[serde(default)]
+    pub allies: Vec<String>,
+    /// Faction ids hostile to this one; they lose reputation when this one gains it
+    #[serde(default)]
+    pub enemies: Vec<String>,
+}
+
+/// A named reputation band; a faction or relationship sits in the highest
+/// tier whose `min` its value reaches, or the lowest tier when below them all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReputationTier {
+    pub name: String,
+    pub min: i32,
+}
+
+impl ReputationTier {
+    /// Hated through Exalted.
+    pub fn defaults() -> Vec<ReputationTier> {
+        [
+            ("Hated", -100),
+            ("Hostile", -50),
+            ("Unfriendly", -20),
+            ("Neutral", -5),
+            ("Friendly", 10),
+            ("Honored", 30),
+            ("Revered", 60),
+            ("Exalted", 100),
+        ]
+        .into_iter()
+        .map(|(name, min)| ReputationTier { name: name.to_string(), min })
+        .collect()
+    }
+
+    /// Nemesis through Lover, for relationships between characters.
+    pub fn relationship_defaults() -> Vec<ReputationTier> {
+        [
+            ("Nemesis", -60),
+            ("Rival", -25),
+            ("Wary", -10),
+            ("Stranger", -4),
+            ("Acquaintance", 5),
+            ("Friend", 20),
+            ("Close Friend", 45),
+            ("Lover", 75),
+        ]
+        .into_iter()
+        .map(|(name, min)| ReputationTier { name: name.to_string(), min })
+        .collect()
+    }
+
+    /// The tier `reputation` falls in, if any tiers are defined.
+    pub fn for_reputation(tiers: &[ReputationTier], reputation: i32) -> Option<&ReputationTier> {
+        let lowest = tiers.iter().min_by_key(|t| t.min);
+        tiers
+            .iter()
+            .filter(|t| t.min <= reputation)
+            .max_by_key(|t| t.min)
+            .or(lowest)
+    }
+}
+
+/// A temporary buff or debuff on the player (poisoned, blessed, exhausted...).
+/// Modifiers are applied on top of base stats when computing effective stats;
+/// the base values are never touched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Key = stat id, value = delta while the effect is active
+    #[serde(default)]
+    pub modifiers: HashMap<String, i32>,
+    /// World time (minutes) at which the effect wears off.
+    #[serde(default)]
+    pub expires_at_minutes: Option<u64>,
+    /// Player turns left before the effect wears off.
+    #[serde(default)]
+    pub remaining_turns: Option<u32>,
+}
+
+impl StatusEffect {
+    /// Sorted "stat +N" list, e.g. "constitution -1, strength -2".
+    pub fn modifiers_text(&self) -> String {
+        let mut mods: Vec<_> = self.modifiers.iter().collect();
+        mods.sort_by(|a, b| a.0.cmp(b.0));
+        mods.iter()
+            .map(|(stat, delta)| format!("{} {:+}", stat, delta))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn duration_text(&self, now_minutes: u64) -> String {
+        let mut parts = Vec::new();
+        if let Some(at) = self.expires_at_minutes {
+            parts.push(format!("{} min left", at.saturating_sub(now_minutes)));
+        }
+        if let Some(turns) = self.remaining_turns {
+            parts.push(format!("{} turns left", turns));
+        }
+        if parts.is_empty() {
+            "until removed".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    /// One-line description used in prompts.
+    pub fn summary(&self, now_minutes: u64) -> String {
+        let mut s = self.name.clone();
+        let mods = self.modifiers_text();
+        if mods.is_empty() {
+            s.push_str(&format!(" ({})", self.duration_text(now_minutes)));
+        } else {
+            s.push_str(&format!(" ({}; {})", mods, self.duration_text(now_minutes)));
+        }
+        if !self.description.trim().is_empty() {
+            s.push_str(&format!(": {}", self.description.trim()));
+        }
+        s
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -216,9 +687,20 @@ pub struct CardEntry {
     pub items: Vec<String>,
 }
 
+/// How two factions regard each other.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FactionStance {
+    Allied,
+    Hostile,
+    Neutral,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum QuestStatus {
+    /// Waiting on prerequisites
+    Locked,
     Active,
     Completed,
     Failed,
@@ -1,5 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::model::calendar::{CalendarStart, FiredWorldEvent, ScheduledEvent, MINUTES_PER_DAY};
+use crate::model::event_result::FactionTierChange;
 use crate::model::game_state::{
     GameStateSnapshot,
     PlayerState,
@@ -7,29 +9,68 @@ use crate::model::game_state::{
     Power,
     PartyMember,
     Quest,
+    QuestOffer,
+    QuestStatus,
     ItemStack,
     LootDrop,
     CurrencyBalance,
     Npc,
+    NpcMission,
     Relationship,
     EquippedItem,
     FactionRep,
+    ReputationTier,
     CardEntry,
+    StatusEffect,
+    NpcMemory,
+    StoryChapter,
+    EffectiveStat,
+    StatContribution,
+    StatSource,
 };
 
+fn default_relationship_decay_per_day() -> u32 {
+    1
+}
+
+fn default_start_hour() -> u32 {
+    CalendarStart::default().hour
+}
+
+/// Section that holds the cards of NPCs away on missions.
+pub const NPC_MISSION_SECTION: &str = "npcs_on_mission";
+
+/// How long a mission lasts when its card gives no duration.
+const DEFAULT_MISSION_MINUTES: u64 = MINUTES_PER_DAY;
+
+/// The NPC a mission card is about, by id or else by name.
+fn mission_card_npc<'a>(npcs: &'a HashMap<String, Npc>, card: &CardEntry) -> Option<&'a Npc> {
+    npcs.get(&card.id).or_else(|| {
+        npcs.values()
+            .find(|npc| npc.name.trim().eq_ignore_ascii_case(card.name.trim()))
+    })
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InternalGameState {
     pub version: u32,
 
     pub player: PlayerState,
 
-    /// Authoritative internal stats store
+    /// Authoritative base stats (player-authored plus permanent modify_stat changes)
     /// Key = stat id (e.g. "strength", "souls")
+    /// Derived bonuses live in their own stores; see `stat_breakdown`.
     pub stats: HashMap<String, i32>,
+    /// Stat growth accumulated from level-ups, kept apart from base stats
+    #[serde(default)]
+    pub level_stat_bonuses: HashMap<String, i32>,
 
     pub powers: HashMap<String, Power>,
     pub party: HashMap<String, PartyMember>,
     pub quests: HashMap<String, Quest>,
+    /// Pending quest offers, keyed by quest id
+    #[serde(default)]
+    pub quest_offers: HashMap<String, QuestOffer>,
     pub inventory: HashMap<String, ItemStack>,
     pub loot: Vec<LootDrop>,
     pub currencies: HashMap<String, i32>,
@@ -37,9 +78,21 @@ pub struct InternalGameState {
     pub relationships: HashMap<String, Relationship>,
     pub equipment: HashMap<String, EquippedItem>,
     pub factions: HashMap<String, FactionRep>,
+    /// The world's named reputation tiers, refreshed every turn
+    #[serde(default = "ReputationTier::defaults")]
+    pub reputation_tiers: Vec<ReputationTier>,
+    /// The world's named relationship tiers, refreshed every turn
+    #[serde(default = "ReputationTier::relationship_defaults")]
+    pub relationship_tiers: Vec<ReputationTier>,
+    /// Points per world day a relationship drifts back toward 0
+    #[serde(default = "default_relationship_decay_per_day")]
+    pub relationship_decay_per_day: u32,
     pub sections: HashMap<String, Vec<CardEntry>>,
     pub player_card: Option<CardEntry>,
     pub world_time_minutes: u64,
+    /// Per-NPC long-term memory, keyed by NPC id
+    #[serde(default)]
+    pub npc_memories: HashMap<String, NpcMemory>,
 
     pub flags: HashSet<String>,
     #[serde(default)]
@@ -50,6 +103,465 @@ pub struct InternalGameState {
     pub power_evolution_tiers: HashMap<String, u32>,
     #[serde(default)]
     pub set_bonus_tiers: HashMap<String, u32>,
+    #[serde(default)]
+    pub status_effects: HashMap<String, StatusEffect>,
+    /// Rolling story summaries, oldest first
+    #[serde(default)]
+    pub story_chapters: Vec<StoryChapter>,
+    /// Engine message index up to which the story has been summarized
+    #[serde(default)]
+    pub summarized_through: usize,
+    /// Why the last turn's events were held back, for the next prompt
+    #[serde(default)]
+    pub engine_feedback: Vec<String>,
+    /// Upcoming world events, keyed by id
+    #[serde(default)]
+    pub scheduled_events: HashMap<String, ScheduledEvent>,
+    /// Ids of one-off world-defined events that already fired
+    #[serde(default)]
+    pub finished_schedules: HashSet<String>,
+    /// World events that fired since the last prompt
+    #[serde(default)]
+    pub fired_world_events: Vec<FiredWorldEvent>,
+    /// Quests the engine failed (deadline or failed prerequisite) since
+    /// the last report, one line each
+    #[serde(default)]
+    pub quest_failures: Vec<String>,
+    /// Where the player is, from the last travel event
+    #[serde(default)]
+    pub player_location: Option<String>,
+    /// Hour of day at world time 0, refreshed every turn from the calendar
+    #[serde(default = "default_start_hour")]
+    pub start_hour: u32,
+}
+
+impl InternalGameState {
+    /// Compute every stat as base + level + set + equipment + effects, with
+    /// the source of each point. Never written back into `stats`; modifiers
+    /// for stats the player does not have are ignored.
+    pub fn stat_breakdown(&self) -> Vec<EffectiveStat> {
+        let mut out: Vec<EffectiveStat> = self
+            .stats
+            .iter()
+            .map(|(id, base)| EffectiveStat {
+                id: id.clone(),
+                base: *base,
+                total: *base,
+                contributions: vec![StatContribution {
+                    source: StatSource::Base,
+                    label: "base".to_string(),
+                    value: *base,
+                }],
+            })
+            .collect();
+        out.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut add = |stat_id: &str, source: StatSource, label: String, value: i32| {
+            if value == 0 {
+                return;
+            }
+            if let Some(stat) = out.iter_mut().find(|s| s.id == stat_id) {
+                stat.total += value;
+                stat.contributions.push(StatContribution { source, label, value });
+            }
+        };
+
+        let mut level: Vec<_> = self.level_stat_bonuses.iter().collect();
+        level.sort();
+        for (stat_id, value) in level {
+            add(stat_id, StatSource::Level, "level".to_string(), *value);
+        }
+
+        let mut sets: Vec<_> = self.set_bonus_tiers.iter().collect();
+        sets.sort();
+        for (set_id, tier) in sets {
+            for (stat_id, value) in set_bonus_deltas(*tier) {
+                add(stat_id, StatSource::Set, format!("{} set", set_id), value);
+            }
+        }
+
+        let mut equipment: Vec<_> = self.equipment.values().collect();
+        equipment.sort_by(|a, b| a.item_id.cmp(&b.item_id));
+        for item in equipment {
+            for (stat_id, value) in &item.modifiers {
+                add(stat_id, StatSource::Equipment, item.item_id.clone(), *value);
+            }
+        }
+
+        let mut effects: Vec<_> = self.status_effects.values().collect();
+        effects.sort_by(|a, b| a.id.cmp(&b.id));
+        for effect in effects {
+            for (stat_id, value) in &effect.modifiers {
+                add(stat_id, StatSource::Effect, effect.name.clone(), *value);
+            }
+        }
+
+        out
+    }
+
+    /// Drop effects whose world-time duration has run out.
+    /// Returns the removed effects.
+    pub fn expire_status_effects(&mut self) -> Vec<StatusEffect> {
+        let now = self.world_time_minutes;
+        let expired: Vec<String> = self
+            .status_effects
+            .values()
+            .filter(|e| e.expires_at_minutes.is_some_and(|at| at <= now))
+            .map(|e| e.id.clone())
+            .collect();
+        expired
+            .iter()
+            .filter_map(|id| self.status_effects.remove(id))
+            .collect()
+    }
+
+    /// Count down turn-based effects at the start of a player turn.
+    /// An effect applied with N turns stays visible for the next N turns.
+    pub fn tick_status_effect_turns(&mut self) -> Vec<StatusEffect> {
+        let mut expired = Vec::new();
+        for effect in self.status_effects.values_mut() {
+            if let Some(turns) = effect.remaining_turns.as_mut() {
+                if *turns == 0 {
+                    expired.push(effect.id.clone());
+                } else {
+                    *turns -= 1;
+                }
+            }
+        }
+        expired
+            .iter()
+            .filter_map(|id| self.status_effects.remove(id))
+            .collect()
+    }
+
+    /// Quest ids `id` waits on: its own prerequisites plus every quest that
+    /// lists it as a follow-up.
+    pub fn quest_prerequisites(&self, id: &str) -> Vec<String> {
+        let mut out = self
+            .quests
+            .get(id)
+            .map(|q| q.prerequisites.clone())
+            .unwrap_or_default();
+        let mut parents: Vec<String> = self
+            .quests
+            .values()
+            .filter(|q| q.follow_ups.iter().any(|f| f == id))
+            .map(|q| q.id.clone())
+            .collect();
+        parents.sort();
+        for parent in parents {
+            if !out.contains(&parent) {
+                out.push(parent);
+            }
+        }
+        out
+    }
+
+    /// Unknown prerequisite ids count as not completed.
+    pub fn quest_prerequisites_met(&self, id: &str) -> bool {
+        self.quest_prerequisites(id).iter().all(|p| {
+            self.quests
+                .get(p)
+                .is_some_and(|q| q.status == QuestStatus::Completed)
+        })
+    }
+
+    /// Activate locked quests whose prerequisites are all completed.
+    /// Returns the unlocked quest ids.
+    pub fn unlock_quests(&mut self) -> Vec<String> {
+        let ready: Vec<String> = self
+            .quests
+            .values()
+            .filter(|q| q.status == QuestStatus::Locked)
+            .map(|q| q.id.clone())
+            .filter(|id| self.quest_prerequisites_met(id))
+            .collect();
+        for id in &ready {
+            if let Some(quest) = self.quests.get_mut(id) {
+                quest.status = QuestStatus::Active;
+            }
+        }
+        ready
+    }
+
+    /// Age pending quest offers by one player turn and withdraw those left
+    /// unanswered for more than `max_turns`. Returns the withdrawn offers.
+    pub fn tick_quest_offers(&mut self, max_turns: u32) -> Vec<QuestOffer> {
+        for offer in self.quest_offers.values_mut() {
+            offer.turns_waiting = offer.turns_waiting.saturating_add(1);
+        }
+        let expired: Vec<String> = self
+            .quest_offers
+            .values()
+            .filter(|o| o.turns_waiting > max_turns)
+            .map(|o| o.quest.id.clone())
+            .collect();
+        expired
+            .iter()
+            .filter_map(|id| self.quest_offers.remove(id))
+            .collect()
+    }
+
+    /// Move every faction into the reputation tier its reputation now
+    /// falls in, returning the changes. A faction's first tier is recorded
+    /// without being reported.
+    pub fn update_faction_tiers(&mut self) -> Vec<FactionTierChange> {
+        let mut changes = Vec::new();
+        for faction in self.factions.values_mut() {
+            let Some(tier) = ReputationTier::for_reputation(&self.reputation_tiers, faction.reputation)
+            else {
+                continue;
+            };
+            if faction.tier.as_deref() == Some(tier.name.as_str()) {
+                continue;
+            }
+            if let Some(from) = faction.tier.replace(tier.name.clone()) {
+                let improved = self
+                    .reputation_tiers
+                    .iter()
+                    .find(|t| t.name == from)
+                    .is_none_or(|from| from.min < tier.min);
+                changes.push(FactionTierChange {
+                    faction_id: faction.id.clone(),
+                    faction_name: faction.name.clone(),
+                    from,
+                    to: tier.name.clone(),
+                    reputation: faction.reputation,
+                    improved,
+                });
+            }
+        }
+        changes.sort_by(|a, b| a.faction_id.cmp(&b.faction_id));
+        changes
+    }
+
+    /// Let every relationship drift toward 0 by the decay rate for each
+    /// whole world day since it last changed or decayed.
+    pub fn decay_relationships(&mut self) {
+        let now = self.world_time_minutes;
+        let rate = i64::from(self.relationship_decay_per_day);
+        for rel in self.relationships.values_mut() {
+            let days = now.saturating_sub(rel.decay_from_minutes) / MINUTES_PER_DAY;
+            if days == 0 {
+                continue;
+            }
+            rel.decay_from_minutes += days * MINUTES_PER_DAY;
+            let decay = (days as i64).saturating_mul(rate).min(i64::from(rel.value.unsigned_abs())) as i32;
+            rel.value -= rel.value.signum() * decay;
+        }
+    }
+
+    /// Add the world's scheduled events that are not already pending and,
+    /// for one-off events, have not fired yet.
+    pub fn sync_world_schedule(&mut self, events: &[ScheduledEvent]) {
+        for event in events {
+            if !self.scheduled_events.contains_key(&event.id)
+                && !self.finished_schedules.contains(&event.id)
+            {
+                self.scheduled_events.insert(event.id.clone(), event.clone());
+            }
+        }
+    }
+
+    /// Remove and return the scheduled events due by now, soonest first.
+    /// Repeating events are rescheduled past now instead of removed.
+    pub fn take_due_scheduled_events(&mut self) -> Vec<ScheduledEvent> {
+        let now = self.world_time_minutes;
+        let mut due: Vec<ScheduledEvent> = self
+            .scheduled_events
+            .values()
+            .filter(|e| e.at_minutes <= now)
+            .cloned()
+            .collect();
+        due.sort_by(|a, b| (a.at_minutes, &a.id).cmp(&(b.at_minutes, &b.id)));
+        for event in &due {
+            match event.repeat_minutes.filter(|r| *r > 0) {
+                Some(every) => {
+                    if let Some(pending) = self.scheduled_events.get_mut(&event.id) {
+                        let missed = (now - pending.at_minutes) / every + 1;
+                        pending.at_minutes += missed * every;
+                    }
+                }
+                None => {
+                    self.scheduled_events.remove(&event.id);
+                    self.finished_schedules.insert(event.id.clone());
+                }
+            }
+        }
+        due
+    }
+
+    /// The stored key for `currency`, matched case-insensitively, or the
+    /// trimmed name for a currency the player does not hold yet.
+    pub fn currency_key(&self, currency: &str) -> String {
+        let currency = currency.trim();
+        self.currencies
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(currency))
+            .cloned()
+            .unwrap_or_else(|| currency.to_string())
+    }
+
+    /// Hour of day (0..24) at the current world time.
+    pub fn hour_of_day(&self) -> u32 {
+        let minutes = self.world_time_minutes + u64::from(self.start_hour.min(23)) * 60;
+        ((minutes % MINUTES_PER_DAY) / 60) as u32
+    }
+
+    /// Off-screen tick: bring missions up to date and move NPCs to where
+    /// their schedule puts them, then refresh who is near the player.
+    /// Cards of NPCs back from their mission leave the mission section.
+    pub fn simulate_npcs(&mut self) {
+        self.adopt_mission_cards();
+        let now = self.world_time_minutes;
+        let clock = now + u64::from(self.start_hour.min(23)) * 60;
+        let hour = self.hour_of_day();
+        let mut returned = HashSet::new();
+        for npc in self.npcs.values_mut() {
+            let routine = &mut npc.routine;
+            if let Some(mission) = &routine.mission {
+                if now < mission.return_minutes {
+                    routine.location = mission.destination.clone();
+                    routine.activity = Some(mission.description.clone());
+                    npc.nearby = false;
+                    continue;
+                }
+                returned.insert(npc.id.clone());
+                routine.mission = None;
+                routine.location = None;
+                routine.activity = None;
+            }
+            let held = routine.location_held_from.is_some_and(|from| {
+                let from_clock = from + u64::from(self.start_hour.min(23)) * 60;
+                routine.slot_began_at(clock).is_none_or(|began| began <= from_clock)
+            });
+            if !held {
+                routine.location_held_from = None;
+                if let Some(slot) = routine.slot_at(hour).cloned() {
+                    routine.location = Some(slot.location);
+                    routine.activity = slot.activity;
+                }
+            }
+            if let Some(nearby) = npc.nearby_by_location(self.player_location.as_deref()) {
+                npc.nearby = nearby;
+            }
+        }
+        if let Some(cards) = self.sections.get_mut(NPC_MISSION_SECTION) {
+            cards.retain_mut(|card| {
+                let Some(npc) = mission_card_npc(&self.npcs, card) else {
+                    return true;
+                };
+                if returned.contains(&npc.id) {
+                    return false;
+                }
+                if let Some(mission) = &npc.routine.mission {
+                    card.status = format!("Underway ({}%)", mission.progress(now));
+                }
+                true
+            });
+        }
+    }
+
+    /// Send the NPC behind each mission card that has no mission yet (one
+    /// the narrator added with `section_card_upsert`) away for a day.
+    pub fn adopt_mission_cards(&mut self) {
+        let Some(cards) = self.sections.get(NPC_MISSION_SECTION) else {
+            return;
+        };
+        let now = self.world_time_minutes;
+        let adopted: Vec<(String, String)> = cards
+            .iter()
+            .filter_map(|card| {
+                let npc = mission_card_npc(&self.npcs, card)?;
+                if npc.routine.mission.is_some() {
+                    return None;
+                }
+                let description = [&card.details, &card.status, &card.role]
+                    .into_iter()
+                    .map(|text| text.trim())
+                    .find(|text| !text.is_empty())
+                    .unwrap_or("On a mission");
+                Some((npc.id.clone(), description.to_string()))
+            })
+            .collect();
+        for (id, description) in adopted {
+            if let Some(npc) = self.npcs.get_mut(&id) {
+                npc.routine.mission = Some(NpcMission {
+                    description,
+                    destination: None,
+                    started_minutes: now,
+                    return_minutes: now + DEFAULT_MISSION_MINUTES,
+                });
+                npc.routine.location_held_from = None;
+                npc.nearby = false;
+            }
+        }
+    }
+
+    /// Fail active quests whose world-time deadline has passed, then the
+    /// locked quests that can no longer unlock. Returns the failed quest ids.
+    pub fn fail_expired_quests(&mut self) -> Vec<String> {
+        let now = self.world_time_minutes;
+        let mut expired: Vec<String> = self
+            .quests
+            .values()
+            .filter(|q| q.status == QuestStatus::Active && q.deadline_minutes.is_some_and(|at| at <= now))
+            .map(|q| q.id.clone())
+            .collect();
+        expired.sort();
+        for id in &expired {
+            self.fail_quest(id, "its deadline passed");
+        }
+        let mut failed = expired;
+        failed.extend(self.fail_blocked_quests());
+        failed
+    }
+
+    /// Fail locked quests with a failed prerequisite, following the chain.
+    /// Returns the failed quest ids.
+    pub fn fail_blocked_quests(&mut self) -> Vec<String> {
+        let mut failed = Vec::new();
+        loop {
+            let mut blocked: Vec<(String, String)> = self
+                .quests
+                .values()
+                .filter(|q| q.status == QuestStatus::Locked)
+                .filter_map(|q| {
+                    self.quest_prerequisites(&q.id)
+                        .into_iter()
+                        .find(|p| self.quests.get(p).is_some_and(|p| p.status == QuestStatus::Failed))
+                        .map(|p| (q.id.clone(), p))
+                })
+                .collect();
+            if blocked.is_empty() {
+                return failed;
+            }
+            blocked.sort();
+            for (id, prerequisite) in blocked {
+                let title = self.quests.get(&prerequisite).map_or(prerequisite, |q| q.title.clone());
+                self.fail_quest(&id, &format!("'{}' before it failed", title));
+                failed.push(id);
+            }
+        }
+    }
+
+    /// Mark a quest failed by the engine and note why for the next report.
+    fn fail_quest(&mut self, id: &str, reason: &str) {
+        if let Some(quest) = self.quests.get_mut(id) {
+            quest.status = QuestStatus::Failed;
+            self.quest_failures
+                .push(format!("Quest '{}' failed: {}", quest.title, reason));
+        }
+    }
+}
+
+/// Stat bonus granted by a set bonus tier (1 = two pieces, 2 = four pieces).
+pub fn set_bonus_deltas(tier: u32) -> Vec<(&'static str, i32)> {
+    match tier {
+        1 => vec![("strength", 1), ("constitution", 1)],
+        2 => vec![("strength", 2), ("constitution", 2), ("agility", 1)],
+        _ => Vec::new(),
+    }
 }
 
 impl From<&InternalGameState> for GameStateSnapshot {
@@ -68,6 +580,7 @@ impl From<&InternalGameState> for GameStateSnapshot {
             equipment: state.equipment.values().cloned().collect(),
             party: state.party.values().cloned().collect(),
             quests: state.quests.values().cloned().collect(),
+            quest_offers: state.quest_offers.values().cloned().collect(),
             inventory: state.inventory.values().cloned().collect(),
             loot: state.loot.clone(),
             currencies: state.currencies
@@ -83,6 +596,18 @@ impl From<&InternalGameState> for GameStateSnapshot {
             sections: state.sections.clone(),
             player_card: state.player_card.clone(),
             world_time_minutes: state.world_time_minutes,
+            npc_memories: state.npc_memories.clone(),
+            status_effects: state.status_effects.values().cloned().collect(),
+            effective_stats: state.stat_breakdown(),
+            story_chapters: state.story_chapters.clone(),
+            engine_feedback: state.engine_feedback.clone(),
+            scheduled_events: {
+                let mut events: Vec<_> = state.scheduled_events.values().cloned().collect();
+                events.sort_by(|a, b| (a.at_minutes, &a.id).cmp(&(b.at_minutes, &b.id)));
+                events
+            },
+            fired_world_events: state.fired_world_events.clone(),
+            player_location: state.player_location.clone(),
             flags: state.flags.iter().cloned().collect(),
         }
     }
@@ -115,10 +640,12 @@ impl Default for InternalGameState {
             },
 
             stats,
+            level_stat_bonuses: HashMap::new(),
 
             powers: HashMap::new(),
             party: HashMap::new(),
             quests: HashMap::new(),
+            quest_offers: HashMap::new(),
             inventory: HashMap::new(),
             loot: Vec::new(),
             currencies: HashMap::new(),
@@ -126,15 +653,29 @@ impl Default for InternalGameState {
             relationships: HashMap::new(),
             equipment: HashMap::new(),
             factions: HashMap::new(),
+            reputation_tiers: ReputationTier::defaults(),
+            relationship_tiers: ReputationTier::relationship_defaults(),
+            relationship_decay_per_day: default_relationship_decay_per_day(),
             sections: HashMap::new(),
             player_card: None,
             world_time_minutes: 0,
+            npc_memories: HashMap::new(),
 
             flags: HashSet::new(),
             action_counts: HashMap::new(),
             power_usage_counts: HashMap::new(),
             power_evolution_tiers: HashMap::new(),
             set_bonus_tiers: HashMap::new(),
+            status_effects: HashMap::new(),
+            story_chapters: Vec::new(),
+            summarized_through: 0,
+            engine_feedback: Vec::new(),
+            scheduled_events: HashMap::new(),
+            finished_schedules: HashSet::new(),
+            fired_world_events: Vec::new(),
+            quest_failures: Vec::new(),
+            player_location: None,
+            start_hour: default_start_hour(),
         }
     }
 }
@@ -3,6 +3,54 @@ use serde_json::Value;
 
 /// Decode raw LLM JSON into typed NarrativeEvents
 pub fn decode_llm_events(json: &str) -> Result<Vec<NarrativeEvent>, String> {
+    let items = decode_items(json)?;
+    let mut events = Vec::new();
+    for item in items {
+        match serde_json::from_value::<NarrativeEvent>(item.clone()) {
+            Ok(event) => events.push(event),
+            Err(_) => {
+                let event_type = item
+                    .get("type")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                events.push(NarrativeEvent::Unknown {
+                    event_type,
+                    raw: item,
+                });
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// Like `decode_llm_events`, but items that don't match an event are
+/// errors instead of `Unknown`. Returns every problem found, worded for
+/// the LLM to fix.
+pub fn decode_llm_events_strict(json: &str) -> Result<Vec<NarrativeEvent>, Vec<String>> {
+    let items = decode_items(json).map_err(|e| vec![e])?;
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let event_type = item
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("(missing type)")
+            .to_string();
+        match serde_json::from_value::<NarrativeEvent>(item) {
+            Ok(event) => events.push(event),
+            Err(e) => errors.push(format!("item {} ({}): {}", index, event_type, e)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(events)
+    } else {
+        Err(errors)
+    }
+}
+
+fn decode_items(json: &str) -> Result<Vec<Value>, String> {
     let normalized = normalize_events_json(json);
     if normalized.trim().is_empty() {
         return Ok(Vec::new());
@@ -30,37 +78,17 @@ pub fn decode_llm_events(json: &str) -> Result<Vec<NarrativeEvent>, String> {
             }
         })?;
 
-    let items = match value {
-        Value::Array(items) => items,
+    match value {
+        Value::Array(items) => Ok(items),
         Value::Object(mut obj) => {
             if let Some(Value::Array(items)) = obj.remove("events") {
-                items
+                Ok(items)
             } else {
-                return Err("EVENTS must be a JSON array".to_string());
-            }
-        }
-        _ => return Err("EVENTS must be a JSON array".to_string()),
-    };
-
-    let mut events = Vec::new();
-    for item in items {
-        match serde_json::from_value::<NarrativeEvent>(item.clone()) {
-            Ok(event) => events.push(event),
-            Err(_) => {
-                let event_type = item
-                    .get("type")
-                    .and_then(|t| t.as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                events.push(NarrativeEvent::Unknown {
-                    event_type,
-                    raw: item,
-                });
+                Err("EVENTS must be a JSON array".to_string())
             }
         }
+        _ => Err("EVENTS must be a JSON array".to_string()),
     }
-
-    Ok(events)
 }
 
 fn normalize_events_json(raw: &str) -> String {
@@ -210,12 +238,12 @@ fn parse_value(raw: &str) -> Value {
 
 #[cfg(test)]
 mod tests {
-    use super::decode_llm_events;
+    use super::{decode_llm_events, decode_llm_events_strict};
     use crate::model::narrative_event::NarrativeEvent;
 
     #[test]
     fn decode_valid_json_array() {
-        let input = r#"[{\"type\":\"rest\",\"description\":\"Camp\"}]"#;
+        let input = r#"[{"type":"rest","description":"Camp"}]"#;
         let events = decode_llm_events(input).expect("decode");
         assert_eq!(events.len(), 1);
         assert!(matches!(events[0], NarrativeEvent::Rest { .. }));
@@ -228,4 +256,16 @@ mod tests {
         assert_eq!(events.len(), 1);
         assert!(matches!(events[0], NarrativeEvent::Rest { .. }));
     }
+
+    #[test]
+    fn strict_decode_reports_each_unmappable_item() {
+        let input = r#"[{"type":"rest","description":"Camp"},{"type":"add_exp"},{"type":"fly"}]"#;
+        let errors = decode_llm_events_strict(input).expect_err("invalid items");
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("item 1 (add_exp): missing field `amount`"));
+        assert!(errors[1].starts_with("item 2 (fly): unknown variant `fly`"));
+
+        assert!(decode_llm_events_strict("[oops").is_err());
+        assert_eq!(decode_llm_events_strict(r#"[{"type":"rest","description":"Camp"}]"#).unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,291 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Keyword-triggered world info. Entries whose keywords show up in the
+/// player input or the last `scan_depth` messages are injected into the
+/// prompt; `always_on` entries are injected every turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lorebook {
+    #[serde(default)]
+    pub entries: Vec<LoreEntry>,
+    #[serde(default = "default_scan_depth")]
+    pub scan_depth: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoreEntry {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub content: String,
+    /// Higher priority entries are kept first when the prompt is trimmed
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub always_on: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Maximum tokens of `content` to inject (0 = no cap)
+    #[serde(default)]
+    pub token_cap: usize,
+}
+
+fn default_scan_depth() -> usize {
+    4
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for Lorebook {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            scan_depth: default_scan_depth(),
+        }
+    }
+}
+
+impl Default for LoreEntry {
+    fn default() -> Self {
+        Self {
+            name: "New entry".to_string(),
+            keywords: Vec::new(),
+            content: String::new(),
+            priority: 100,
+            always_on: false,
+            enabled: true,
+            token_cap: 0,
+        }
+    }
+}
+
+impl LoreEntry {
+    /// True when any keyword appears as a whole word (case-insensitive).
+    pub fn matches(&self, lowercase_text: &str) -> bool {
+        self.keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .filter(|k| !k.is_empty())
+            .any(|k| contains_word(lowercase_text, &k))
+    }
+}
+
+impl Lorebook {
+    /// Enabled entries that are always on or triggered by `texts`, highest
+    /// priority first.
+    pub fn active_entries(&self, texts: &[&str]) -> Vec<&LoreEntry> {
+        let haystack = texts.join("\n").to_lowercase();
+        let mut active: Vec<&LoreEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.enabled && !e.content.trim().is_empty())
+            .filter(|e| e.always_on || e.matches(&haystack))
+            .collect();
+        active.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.name.cmp(&b.name)));
+        active
+    }
+
+    /// Import SillyTavern world info, Character Card V2 `character_book`,
+    /// NovelAI or Agnai lorebook JSON.
+    pub fn from_json_str(data: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        Self::from_json_value(&value)
+    }
+
+    pub fn from_json_value(value: &Value) -> Result<Self, String> {
+        let entries: Vec<&Value> = match value.get("entries") {
+            // SillyTavern world info: { "entries": { "0": {...}, "1": {...} } }
+            Some(Value::Object(map)) => map.values().collect(),
+            // Character book V2, NovelAI and Agnai use an array
+            Some(Value::Array(list)) => list.iter().collect(),
+            _ => return Err("No lorebook entries found".to_string()),
+        };
+
+        let scan_depth = value
+            .get("scan_depth")
+            .and_then(Value::as_u64)
+            .map(|d| d as usize)
+            .unwrap_or_else(default_scan_depth);
+
+        let entries = entries.into_iter().filter_map(import_entry).collect();
+        Ok(Self { entries, scan_depth })
+    }
+
+    /// Export as SillyTavern world info, which most front-ends import.
+    pub fn to_sillytavern_json(&self) -> Value {
+        let mut entries = serde_json::Map::new();
+        for (uid, entry) in self.entries.iter().enumerate() {
+            entries.insert(
+                uid.to_string(),
+                json!({
+                    "uid": uid,
+                    "key": entry.keywords,
+                    "keysecondary": [],
+                    "comment": entry.name,
+                    "content": entry.content,
+                    "constant": entry.always_on,
+                    "selective": false,
+                    "order": entry.priority,
+                    "position": 0,
+                    "disable": !entry.enabled,
+                    "depth": self.scan_depth,
+                    "extensions": { "upf_token_cap": entry.token_cap },
+                }),
+            );
+        }
+        json!({ "entries": entries })
+    }
+
+    /// Export as a Character Card V2 `character_book`.
+    pub fn to_character_book(&self, name: &str) -> Value {
+        let entries: Vec<Value> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| {
+                json!({
+                    "id": id,
+                    "keys": entry.keywords,
+                    "secondary_keys": [],
+                    "comment": entry.name,
+                    "name": entry.name,
+                    "content": entry.content,
+                    "constant": entry.always_on,
+                    "selective": false,
+                    "enabled": entry.enabled,
+                    "insertion_order": entry.priority,
+                    "priority": entry.priority,
+                    "case_sensitive": false,
+                    "position": "before_char",
+                    "extensions": { "upf_token_cap": entry.token_cap },
+                })
+            })
+            .collect();
+        json!({
+            "name": name,
+            "scan_depth": self.scan_depth,
+            "recursive_scanning": false,
+            "extensions": {},
+            "entries": entries,
+        })
+    }
+}
+
+/// Map one entry from any supported format. Field names differ per tool:
+/// keys (`key`/`keys`/`keywords`), text (`content`/`text`/`entry`),
+/// order (`order`/`insertion_order`/`priority`), disabled (`disable`/`enabled`).
+fn import_entry(value: &Value) -> Option<LoreEntry> {
+    let content = ["content", "text", "entry"]
+        .iter()
+        .find_map(|k| value.get(*k).and_then(Value::as_str))
+        .unwrap_or_default()
+        .to_string();
+    let keywords: Vec<String> = ["key", "keys", "keywords"]
+        .iter()
+        .find_map(|k| value.get(*k))
+        .map(string_list)
+        .unwrap_or_default();
+    if content.trim().is_empty() && keywords.is_empty() {
+        return None;
+    }
+
+    let name = ["comment", "name", "displayName"]
+        .iter()
+        .filter_map(|k| value.get(*k).and_then(Value::as_str))
+        .find(|s| !s.trim().is_empty())
+        .map(str::to_string)
+        .or_else(|| keywords.first().cloned())
+        .unwrap_or_else(|| "Entry".to_string());
+    let priority = ["order", "insertion_order", "priority"]
+        .iter()
+        .find_map(|k| value.get(*k).and_then(Value::as_i64))
+        .unwrap_or(100) as i32;
+    let enabled = match (value.get("disable"), value.get("enabled")) {
+        (Some(Value::Bool(disabled)), _) => !disabled,
+        (_, Some(Value::Bool(enabled))) => *enabled,
+        _ => true,
+    };
+    let always_on = value.get("constant").and_then(Value::as_bool).unwrap_or(false);
+    let token_cap = value
+        .pointer("/extensions/upf_token_cap")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as usize;
+
+    Some(LoreEntry {
+        name,
+        keywords,
+        content,
+        priority,
+        always_on,
+        enabled,
+        token_cap,
+    })
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Value::String(s) => s
+            .split(',')
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// True when `needle` appears in `haystack` with no letter or digit directly
+/// on either side, so "al" does not match "also".
+pub(crate) fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_sillytavern_world_info_and_triggers_on_whole_words() {
+        let data = r#"{
+            "entries": {
+                "0": { "uid": 0, "key": ["Ironhold"], "comment": "Ironhold", "content": "A dwarven fortress.", "constant": false, "order": 50, "disable": false },
+                "1": { "uid": 1, "key": [], "comment": "Magic", "content": "Magic costs blood.", "constant": true, "order": 10, "disable": false },
+                "2": { "uid": 2, "key": ["ash"], "comment": "Ash", "content": "Grey snow.", "constant": false, "order": 90, "disable": true }
+            }
+        }"#;
+        let book = Lorebook::from_json_str(data).expect("import");
+        assert_eq!(book.entries.len(), 3);
+
+        let active = book.active_entries(&["We ride for IRONHOLD at dawn", "crashing waves"]);
+        let names: Vec<&str> = active.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Ironhold", "Magic"]);
+
+        assert!(book.active_entries(&["Ironholds"]).iter().all(|e| e.name != "Ironhold"));
+
+        let round_trip = Lorebook::from_json_value(&book.to_sillytavern_json()).expect("re-import");
+        assert_eq!(round_trip.entries.len(), 3);
+        assert!(!round_trip.entries.iter().find(|e| e.name == "Ash").unwrap().enabled);
+    }
+
+    #[test]
+    fn contains_word_needs_word_boundaries() {
+        assert!(contains_word("i ask al about the map", "al"));
+        assert!(contains_word("al, wait!", "al"));
+        assert!(contains_word("talk to mira vale.", "mira vale"));
+        assert!(!contains_word("i also ask about the map", "al"));
+        assert!(!contains_word("the miracle cure", "mira"));
+    }
+}
@@ -2,7 +2,11 @@ pub mod message;
 pub mod game_state;
 pub mod internal_game_state;
 pub mod narrative_event;
+pub mod event_schema;
 pub mod event_result;
 pub mod llm_decode;
 pub mod game_context;
 pub mod game_save;
+pub mod lorebook;
+pub mod calendar;
+pub mod character_card;
@@ -1,14 +1,17 @@
 use serde::{Deserialize, Serialize};
 use serde::de::{self, Deserializer};
 use serde_json::Value;
+use std::collections::HashMap;
 
-use crate::model::game_state::QuestStatus;
+use crate::model::game_state::{FactionStance, QuestStatus, ScheduleSlot};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuestStepUpdate {
     pub id: String,
     pub description: Option<String>,
     pub completed: Option<bool>,
+    #[serde(default)]
+    pub optional: Option<bool>,
 }
 
 fn deserialize_topics<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
@@ -142,6 +145,31 @@ pub enum NarrativeEvent {
         #[serde(default)]
         reason: Option<String>,
     },
+    /// A world event the engine announces once `in_minutes` have passed
+    ScheduleEvent {
+        id: String,
+        name: String,
+        #[serde(default)]
+        description: Option<String>,
+        in_minutes: u32,
+        #[serde(default)]
+        repeat_minutes: Option<u32>,
+    },
+    ApplyStatusEffect {
+        id: String,
+        name: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        modifiers: Option<HashMap<String, i32>>,
+        #[serde(default)]
+        duration_minutes: Option<u32>,
+        #[serde(default)]
+        duration_turns: Option<u32>,
+    },
+    RemoveStatusEffect {
+        id: String,
+    },
     NpcSpawn {
         #[serde(default)]
         id: Option<String>,
@@ -171,6 +199,32 @@ pub enum NarrativeEvent {
         role: Option<String>,
         #[serde(alias = "notes")]
         details: Option<String>,
+        #[serde(default, alias = "key_facts")]
+        facts: Option<Vec<String>>,
+        #[serde(default)]
+        player_told: Option<Vec<String>>,
+        #[serde(default)]
+        promises: Option<Vec<String>>,
+        #[serde(default)]
+        promises_resolved: Option<Vec<String>>,
+        /// Where the NPC is now
+        #[serde(default)]
+        location: Option<String>,
+        /// Replaces the NPC's daily schedule
+        #[serde(default)]
+        schedule: Option<Vec<ScheduleSlot>>,
+        #[serde(default)]
+        goals: Option<Vec<String>>,
+        #[serde(default)]
+        goals_completed: Option<Vec<String>>,
+    },
+    /// Send an NPC away on an errand that resolves off-screen
+    NpcMission {
+        id: String,
+        mission: String,
+        #[serde(default)]
+        destination: Option<String>,
+        duration_minutes: u32,
     },
     NpcDespawn {
         id: String,
@@ -183,6 +237,9 @@ pub enum NarrativeEvent {
         subject_id: String,
         target_id: String,
         delta: i32,
+        /// Why it changed, kept in the relationship's history
+        #[serde(default)]
+        reason: Option<String>,
     },
 
     ModifyStat {
@@ -202,6 +259,8 @@ pub enum NarrativeEvent {
         set_id: Option<String>,
         #[serde(default)]
         description: Option<String>,
+        #[serde(default)]
+        modifiers: Option<HashMap<String, i32>>,
     },
     UnequipItem {
         item_id: String,
@@ -223,6 +282,55 @@ pub enum NarrativeEvent {
         sub_quests: Option<Vec<crate::model::game_state::QuestStep>>,
         #[serde(default)]
         declinable: Option<bool>,
+        #[serde(default)]
+        prerequisites: Option<Vec<String>>,
+        #[serde(default)]
+        follow_ups: Option<Vec<String>>,
+        #[serde(default)]
+        giver_npc: Option<String>,
+        #[serde(default)]
+        giver_faction: Option<String>,
+        #[serde(default)]
+        location: Option<String>,
+        /// World minutes from now until the quest fails
+        #[serde(default)]
+        deadline_minutes: Option<u32>,
+        #[serde(default)]
+        failure_conditions: Option<Vec<String>>,
+    },
+    /// A quest proposal; it only starts once the player accepts
+    OfferQuest {
+        id: String,
+        title: String,
+        description: String,
+        #[serde(default)]
+        difficulty: Option<String>,
+        #[serde(default)]
+        negotiable: Option<bool>,
+        #[serde(default)]
+        reward_options: Option<Vec<String>>,
+        #[serde(default)]
+        rewards: Option<Vec<String>>,
+        #[serde(default, rename = "sub_quests", alias = "subquests", alias = "objectives")]
+        sub_quests: Option<Vec<crate::model::game_state::QuestStep>>,
+        /// `false` starts a mandatory world quest right away
+        #[serde(default)]
+        declinable: Option<bool>,
+        #[serde(default)]
+        prerequisites: Option<Vec<String>>,
+        #[serde(default)]
+        follow_ups: Option<Vec<String>>,
+        #[serde(default)]
+        giver_npc: Option<String>,
+        #[serde(default)]
+        giver_faction: Option<String>,
+        #[serde(default)]
+        location: Option<String>,
+        /// World minutes after acceptance until the quest fails
+        #[serde(default)]
+        deadline_minutes: Option<u32>,
+        #[serde(default)]
+        failure_conditions: Option<Vec<String>>,
     },
     UpdateQuest {
         id: String,
@@ -238,6 +346,27 @@ pub enum NarrativeEvent {
         rewards: Option<Vec<String>>,
         #[serde(rename = "sub_quests", alias = "subquests", alias = "objectives")]
         sub_quests: Option<Vec<QuestStepUpdate>>,
+        #[serde(default)]
+        follow_ups: Option<Vec<String>>,
+        #[serde(default)]
+        location: Option<String>,
+        /// World minutes from now until the quest fails
+        #[serde(default)]
+        deadline_minutes: Option<u32>,
+        #[serde(default)]
+        failure_conditions: Option<Vec<String>>,
+    },
+    ChooseReward {
+        quest_id: String,
+        option: String,
+    },
+    CounterOffer {
+        quest_id: String,
+        rewards: Vec<String>,
+    },
+    SettleReward {
+        quest_id: String,
+        accepted: bool,
     },
     RequestContext {
         #[serde(default, alias = "topic", deserialize_with = "deserialize_topics")]
@@ -297,8 +426,77 @@ pub enum NarrativeEvent {
         id: String,
         delta: i32,
     },
+    /// Set how two factions regard each other (applies both ways)
+    FactionStanceChange {
+        id: String,
+        other_id: String,
+        stance: FactionStance,
+    },
+    /// The player joins, leaves or changes rank in a faction
+    FactionMembership {
+        id: String,
+        member: bool,
+        #[serde(default)]
+        rank: Option<String>,
+    },
     Unknown {
         event_type: String,
         raw: serde_json::Value,
     },
 }
+
+impl NarrativeEvent {
+    /// `start_quest` re-tagged as `offer_quest`; other events are unchanged.
+    pub fn into_quest_offer(self) -> NarrativeEvent {
+        let NarrativeEvent::StartQuest {
+            id,
+            title,
+            description,
+            difficulty,
+            negotiable,
+            reward_options,
+            rewards,
+            sub_quests,
+            declinable,
+            prerequisites,
+            follow_ups,
+            giver_npc,
+            giver_faction,
+            location,
+            deadline_minutes,
+            failure_conditions,
+        } = self
+        else {
+            return self;
+        };
+        NarrativeEvent::OfferQuest {
+            id,
+            title,
+            description,
+            difficulty,
+            negotiable,
+            reward_options,
+            rewards,
+            sub_quests,
+            declinable,
+            prerequisites,
+            follow_ups,
+            giver_npc,
+            giver_faction,
+            location,
+            deadline_minutes,
+            failure_conditions,
+        }
+    }
+
+    /// The `type` tag this event is written with in EVENTS JSON.
+    pub fn type_name(&self) -> String {
+        if let NarrativeEvent::Unknown { event_type, .. } = self {
+            return event_type.clone();
+        }
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
@@ -16,12 +16,21 @@ use super::center_panel::draw_center_panel;
 use super::right_panel::draw_right_panel;
 
 use crate::engine::engine::Engine;
-use crate::engine::llm_client::{LlmApiMode, LlmConfig};
+use crate::engine::llm_client::{
+    AbortSignal, GenerationPresets, LlmApiMode, LlmConfig, LlmEndpoint, LlmRole, SamplingSettings,
+};
+use crate::engine::event_validator::{EventLimits, EventReviewPolicy};
+use crate::engine::prompt_templates;
 use crate::engine::protocol::{EngineCommand, EngineResponse};
 
-use crate::model::game_state::GameStateSnapshot;
+use crate::model::calendar::{Calendar, ScheduledEvent};
+use crate::model::event_result::FactionTierChange;
+use crate::model::game_state::{GameStateSnapshot, ReputationTier};
 use crate::model::message::{Message,};
 use crate::model::game_context::GameContext;
+use crate::model::lorebook::Lorebook;
+use crate::model::narrative_event::NarrativeEvent;
+use crate::model::character_card::{CharacterCard, CARD_V2_PNG_KEY, CARD_V3_PNG_KEY};
 
 /* =========================
    World Definition
@@ -50,6 +59,28 @@ pub struct WorldDefinition {
     pub world_quests_mandatory: bool,
     #[serde(default)]
     pub npc_quests_enabled: bool,
+    /// Player turns a quest offer waits for an answer before it lapses
+    #[serde(default = "default_quest_offer_turns")]
+    pub quest_offer_turns: u32,
+    /// Named faction reputation bands, Hated through Exalted by default
+    #[serde(default = "ReputationTier::defaults")]
+    pub reputation_tiers: Vec<ReputationTier>,
+    /// Named relationship bands, Nemesis through Lover by default
+    #[serde(default = "ReputationTier::relationship_defaults")]
+    pub relationship_tiers: Vec<ReputationTier>,
+    /// Points per world day relationships drift back toward 0
+    #[serde(default = "default_relationship_decay_per_day")]
+    pub relationship_decay_per_day: u32,
+    /// Party members at or above this morale refuse `npc_leave_party`
+    /// (None = anyone may leave)
+    #[serde(default)]
+    pub loyal_morale: Option<i32>,
+    /// Month, weekday, season and day-phase names, and the start date
+    #[serde(default)]
+    pub calendar: Calendar,
+    /// World events that fire at set world times
+    #[serde(default)]
+    pub scheduled_events: Vec<ScheduledEvent>,
     #[serde(default)]
     pub is_rpg_world: bool,
     #[serde(default = "default_exp_multiplier")]
@@ -70,6 +101,20 @@ pub struct WorldDefinition {
     pub power_evolution_multiplier_min: f32,
     #[serde(default = "default_power_evolution_multiplier_max")]
     pub power_evolution_multiplier_max: f32,
+    /// Per-world prompt template overrides, keyed by section name
+    #[serde(default)]
+    pub prompt_templates: HashMap<String, String>,
+    #[serde(default)]
+    pub lorebook: Lorebook,
+    /// Replaces the user's generation presets while this world is loaded
+    #[serde(default)]
+    pub generation: Option<GenerationPresets>,
+    /// Bounds checked on LLM events before they are applied
+    #[serde(default)]
+    pub event_limits: EventLimits,
+    /// Event types applied without review when event review is on
+    #[serde(default)]
+    pub event_review: EventReviewPolicy,
 }
 
 impl Default for WorldDefinition {
@@ -97,6 +142,13 @@ impl Default for WorldDefinition {
             world_quests_enabled: false,
             world_quests_mandatory: false,
             npc_quests_enabled: false,
+            quest_offer_turns: default_quest_offer_turns(),
+            reputation_tiers: ReputationTier::defaults(),
+            relationship_tiers: ReputationTier::relationship_defaults(),
+            relationship_decay_per_day: default_relationship_decay_per_day(),
+            loyal_morale: None,
+            calendar: Calendar::default(),
+            scheduled_events: Vec::new(),
             is_rpg_world: false,
             exp_multiplier: 2.0,
             repetition_threshold: 5,
@@ -107,6 +159,11 @@ impl Default for WorldDefinition {
             power_evolution_step: 10,
             power_evolution_multiplier_min: 1.1,
             power_evolution_multiplier_max: 3.0,
+            prompt_templates: HashMap::new(),
+            lorebook: Lorebook::default(),
+            generation: None,
+            event_limits: EventLimits::default(),
+            event_review: EventReviewPolicy::default(),
         }
     }
 }
@@ -353,14 +410,29 @@ pub struct UiState {
     pub llm_model: String,
     pub llm_api_key: String,
     pub llm_api_mode: UiLlmApiMode,
+    /// Models reported by the last successful connection
+    pub llm_models: Vec<String>,
     pub ui_error: Option<String>,
     pub chat_log_limit: Option<usize>,
     pub save_full_chat_log: bool,
     pub prompt_history_limit: Option<usize>,
+    pub history_token_budget: Option<usize>,
+    pub context_window_tokens: Option<usize>,
+    pub story_summary_interval: u32,
+    pub prompt_templates: HashMap<String, String>,
+    pub template_editor: TemplateEditorState,
+    pub generation: GenerationPresets,
+    /// Result of the last lorebook import
+    pub lorebook_status: Option<String>,
     pub timing_enabled: bool,
     pub use_structured_events: bool,
+    pub multi_message_prompts: bool,
+    pub role_backends: RoleBackends,
     pub npc_recent_messages_limit: usize,
     pub debug_messages_enabled: bool,
+    /// Hold LLM events the world doesn't auto-approve for review
+    pub review_events: bool,
+    pub event_review: Option<EventReviewState>,
 
     pub left_tab: LeftTab,
     pub right_tab: RightTab,      // NEW: track which right panel tab is active
@@ -373,6 +445,10 @@ pub struct UiState {
     pub new_npc_name: String,
     pub new_npc_role: String,
     pub new_npc_notes: String,
+    /// Counter-offer drafts, keyed by quest id
+    pub quest_counter_offers: HashMap<String, String>,
+    /// Latest reputation tier change per faction id
+    pub faction_tier_changes: HashMap<String, FactionTierChange>,
 
     pub is_generating: bool,
 
@@ -413,14 +489,26 @@ impl Default for UiState {
             llm_model: "local-model".into(),
             llm_api_key: String::new(),
             llm_api_mode: UiLlmApiMode::OpenAiChat,
+            llm_models: Vec::new(),
             ui_error: None,
             chat_log_limit: None,
             save_full_chat_log: false,
             prompt_history_limit: Some(50),
+            history_token_budget: default_history_token_budget(),
+            context_window_tokens: default_context_window_tokens(),
+            story_summary_interval: default_story_summary_interval(),
+            prompt_templates: HashMap::new(),
+            template_editor: TemplateEditorState::default(),
+            generation: GenerationPresets::default(),
+            lorebook_status: None,
             timing_enabled: true,
             npc_recent_messages_limit: 10,
             use_structured_events: false,
+            multi_message_prompts: false,
+            role_backends: RoleBackends::default(),
             debug_messages_enabled: true,
+            review_events: false,
+            event_review: None,
 
             left_tab: LeftTab::Party,
             right_tab: RightTab::Player, // NEW: default tab
@@ -433,6 +521,8 @@ impl Default for UiState {
             new_npc_name: String::new(),
             new_npc_role: String::new(),
             new_npc_notes: String::new(),
+            quest_counter_offers: HashMap::new(),
+            faction_tier_changes: HashMap::new(),
 
             is_generating: false,
 
@@ -447,37 +537,74 @@ impl Default for UiState {
     }
 }
 
-impl UiState {
-    pub fn llm_config(&self) -> LlmConfig {
-        let base_url = if self.llm_base_url.trim().is_empty() {
-            match self.llm_api_mode {
-                UiLlmApiMode::OpenAiChat => "http://localhost:1234/v1".to_string(),
-                UiLlmApiMode::KoboldCpp => "http://localhost:5001".to_string(),
-            }
+/// Resolve one backend's settings, filling in the mode's default URL and
+/// a placeholder model name when left blank.
+fn llm_endpoint(mode: UiLlmApiMode, base_url: &str, model: &str, api_key: &str) -> LlmEndpoint {
+    let base_url = if base_url.trim().is_empty() {
+        mode.default_base_url().to_string()
+    } else {
+        base_url.trim().to_string()
+    };
+    let model = if model.trim().is_empty() {
+        "local-model".to_string()
+    } else {
+        model.trim().to_string()
+    };
+    let api_key = api_key.trim();
+    LlmEndpoint {
+        base_url,
+        model,
+        api_key: if api_key.is_empty() {
+            None
         } else {
-            self.llm_base_url.trim().to_string()
-        };
+            Some(api_key.to_string())
+        },
+        api_mode: match mode {
+            UiLlmApiMode::OpenAiChat => LlmApiMode::OpenAiChat,
+            UiLlmApiMode::KoboldCpp => LlmApiMode::KoboldCpp,
+            UiLlmApiMode::Ollama => LlmApiMode::Ollama,
+            UiLlmApiMode::LlamaCpp => LlmApiMode::LlamaCpp,
+        },
+    }
+}
 
-        let model = if self.llm_model.trim().is_empty() {
-            "local-model".to_string()
-        } else {
-            self.llm_model.trim().to_string()
-        };
+impl UiState {
+    pub fn llm_config(&self) -> LlmConfig {
+        let main = llm_endpoint(
+            self.llm_api_mode,
+            &self.llm_base_url,
+            &self.llm_model,
+            &self.llm_api_key,
+        );
+        let role_endpoints = self
+            .role_backends
+            .entries()
+            .into_iter()
+            .filter(|(_, backend)| backend.enabled)
+            .map(|(role, backend)| {
+                let endpoint = llm_endpoint(
+                    backend.api_mode,
+                    &backend.base_url,
+                    &backend.model,
+                    &backend.api_key,
+                );
+                (role, endpoint)
+            })
+            .collect();
 
-        let api_key = self.llm_api_key.trim();
         LlmConfig {
-            base_url,
-            model,
-            api_key: if api_key.is_empty() {
-                None
-            } else {
-                Some(api_key.to_string())
-            },
-            api_mode: match self.llm_api_mode {
-                UiLlmApiMode::OpenAiChat => LlmApiMode::OpenAiChat,
-                UiLlmApiMode::KoboldCpp => LlmApiMode::KoboldCpp,
-            },
+            base_url: main.base_url,
+            model: main.model,
+            api_key: main.api_key,
+            api_mode: main.api_mode,
+            role_endpoints,
             use_structured_events: self.use_structured_events,
+            multi_message_prompts: self.multi_message_prompts,
+            generation: self
+                .world
+                .generation
+                .clone()
+                .unwrap_or_else(|| self.generation.clone()),
         }
     }
 
@@ -574,6 +701,63 @@ impl Default for BaseTabs {
     }
 }
 
+/// Options-window editor for prompt template overrides.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateEditorState {
+    pub selected: String,
+    /// Edit the world's overrides instead of the user config's
+    pub world_scope: bool,
+    pub text: String,
+    /// Whether `text` was loaded for the current selection and scope
+    pub loaded: bool,
+    pub error: Option<String>,
+}
+
+/// Review window for events the engine is holding back.
+#[derive(Debug, Clone, Default)]
+pub struct EventReviewState {
+    pub items: Vec<EventReviewItem>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventReviewItem {
+    pub event_type: String,
+    /// The event as pretty JSON, edited in place before approval
+    pub json: String,
+    pub accepted: bool,
+}
+
+impl EventReviewState {
+    pub fn new(events: Vec<NarrativeEvent>) -> Self {
+        let items = events
+            .into_iter()
+            .map(|event| EventReviewItem {
+                event_type: event.type_name(),
+                json: serde_json::to_string_pretty(&event).unwrap_or_default(),
+                accepted: true,
+            })
+            .collect();
+        Self { items, error: None }
+    }
+
+    /// Split into approved and rejected events, parsing any edits.
+    pub fn decisions(&self) -> Result<(Vec<NarrativeEvent>, Vec<NarrativeEvent>), String> {
+        let mut approved = Vec::new();
+        let mut rejected = Vec::new();
+        for (index, item) in self.items.iter().enumerate() {
+            let event: NarrativeEvent = serde_json::from_str(&item.json)
+                .map_err(|err| format!("Event {} ({}): {}", index + 1, item.event_type, err))?;
+            if item.accepted {
+                approved.push(event);
+            } else {
+                rejected.push(event);
+            }
+        }
+        Ok((approved, rejected))
+    }
+}
+
 impl UiState {
     pub fn default_save_dir() -> PathBuf {
         let mut path = dirs::document_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -649,6 +833,139 @@ impl UiState {
         Some(character)
     }
 
+    /// Pick a Character Card V1/V2/V3 (PNG or JSON). Any embedded lorebook
+    /// is merged into the world lorebook. Errors go to `ui_error`.
+    pub fn pick_character_card(&mut self) -> Option<(CharacterCard, PathBuf)> {
+        let path = FileDialog::new()
+            .add_filter("Character Card", &["png", "json"])
+            .set_directory(Self::default_save_dir())
+            .pick_file()?;
+
+        let card = match path.extension().and_then(|s| s.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => {
+                png_text_chunk(&path, CARD_V3_PNG_KEY)
+                    .or_else(|| png_text_chunk(&path, CARD_V2_PNG_KEY))
+                    .ok_or_else(|| "No character card data in this PNG".to_string())
+                    .and_then(|text| CharacterCard::from_png_text(&text))
+            }
+            _ => fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| CharacterCard::from_json_str(&data)),
+        };
+        let card = match card {
+            Ok(card) => card,
+            Err(err) => {
+                self.ui_error = Some(format!("Could not import character card: {}", err));
+                return None;
+            }
+        };
+
+        if let Some(book) = &card.character_book {
+            self.merge_lorebook(book, &card.name);
+        }
+        Some((card, path))
+    }
+
+    /// Replace the player character with an imported card.
+    pub fn import_card_as_character(&mut self, ctx: &egui::Context) {
+        let Some((card, path)) = self.pick_character_card() else {
+            return;
+        };
+        let mut character = character_from_card(&card);
+        migrate_character_clothing(&mut character);
+        self.character = character;
+
+        let is_png = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+        if is_png {
+            if let Ok((width, height, rgba)) = load_image_rgba(&path) {
+                self.set_character_image_from_rgba(ctx, width, height, rgba);
+            }
+        }
+    }
+
+    /// Export the player character as a Character Card V2. PNG output needs
+    /// a portrait; otherwise the card is written as JSON. Errors go to
+    /// `ui_error`.
+    pub fn export_character_card(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("Character Card", &["png"])
+            .add_filter("Character Card Json", &["json"])
+            .set_file_name(format!("{}.png", self.character.name.trim()))
+            .set_directory(Self::default_save_dir())
+            .save_file()
+        else {
+            return;
+        };
+
+        let card = self.character_card();
+        let wants_json = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let image = self
+            .character_image_size
+            .zip(self.character_image_rgba.as_ref());
+
+        let result = match image {
+            Some(((width, height), rgba)) if !wants_json => {
+                let Some(path) = force_png_extension(path) else {
+                    return;
+                };
+                serde_json::to_string_pretty(&self.character)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json| {
+                        write_png_with_card(&path, width, height, rgba, &json, &card.to_png_text())
+                    })
+            }
+            _ => {
+                let mut path = path;
+                path.set_extension("json");
+                serde_json::to_string_pretty(&card.to_v2_json())
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json| fs::write(&path, json).map_err(anyhow::Error::from))
+            }
+        };
+        if let Err(err) = result {
+            self.ui_error = Some(format!("Could not export character card: {}", err));
+        }
+    }
+
+    fn character_card(&self) -> CharacterCard {
+        let c = &self.character;
+        let mut description = c.background.trim().to_string();
+        if !c.features.is_empty() {
+            description.push_str(&format!("\n\nFeatures: {}", c.features.join(", ")));
+        }
+        let lorebook = &self.world.lorebook;
+        CharacterCard {
+            name: c.name.clone(),
+            description,
+            tags: vec![c.class.clone()],
+            character_book: (!lorebook.entries.is_empty()).then(|| lorebook.clone()),
+            upf_character: serde_json::to_value(c).ok(),
+            ..CharacterCard::default()
+        }
+    }
+
+    /// Append entries from an imported lorebook, skipping exact duplicates.
+    pub fn merge_lorebook(&mut self, book: &Lorebook, source: &str) {
+        let entries = &mut self.world.lorebook.entries;
+        let mut added = 0;
+        for entry in &book.entries {
+            let duplicate = entries
+                .iter()
+                .any(|e| e.name == entry.name && e.content == entry.content);
+            if !duplicate {
+                entries.push(entry.clone());
+                added += 1;
+            }
+        }
+        self.lorebook_status = Some(format!("Imported {} lorebook entries from {}", added, source));
+    }
+
     pub fn save_world(&self) {
         let Some(path) = FileDialog::new()
             .add_filter("World", &["json"])
@@ -672,6 +989,34 @@ impl UiState {
         serde_json::from_str::<WorldDefinition>(&data).ok()
     }
 
+    /// Export the world lorebook as SillyTavern world info JSON.
+    pub fn export_lorebook(&self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("Lorebook", &["json"])
+            .set_file_name("lorebook.json")
+            .set_directory(Self::default_save_dir())
+            .save_file()
+        else {
+            return;
+        };
+        if let Ok(json) = serde_json::to_string_pretty(&self.world.lorebook.to_sillytavern_json()) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Import SillyTavern, Character Card V2, NovelAI or Agnai lorebook JSON.
+    pub fn import_lorebook_from_dialog() -> Option<Result<Lorebook, String>> {
+        let path = FileDialog::new()
+            .add_filter("Lorebook", &["json", "lorebook"])
+            .set_directory(Self::default_save_dir())
+            .pick_file()?;
+        Some(
+            fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| Lorebook::from_json_str(&data)),
+        )
+    }
+
     fn set_character_image_from_rgba(
         &mut self,
         ctx: &egui::Context,
@@ -695,7 +1040,6 @@ impl UiState {
         report: &crate::model::event_result::NarrativeApplyReport,
     ) {
         for app in &report.applications {
-            use crate::model::narrative_event::NarrativeEvent;
             match &app.event {
                 NarrativeEvent::AddPartyMember { id, name, role } => {
                     self.upsert_party_member(
@@ -799,6 +1143,7 @@ impl UiState {
         // not inferred from narration or dialogue.
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn upsert_party_member(
         &mut self,
         id: Option<&str>,
@@ -1019,7 +1364,7 @@ impl UiState {
 }
 
 fn matches_flag(flag: &str, aliases: &[&str]) -> bool {
-    aliases.iter().any(|alias| flag == *alias)
+    aliases.contains(&flag)
 }
 
 fn unlock_if_needed(
@@ -1278,14 +1623,30 @@ pub struct AppConfig {
     pub save_full_chat_log: bool,
     #[serde(default)]
     pub prompt_history_limit: Option<usize>,
+    #[serde(default = "default_history_token_budget")]
+    pub history_token_budget: Option<usize>,
+    #[serde(default = "default_context_window_tokens")]
+    pub context_window_tokens: Option<usize>,
+    #[serde(default = "default_story_summary_interval")]
+    pub story_summary_interval: u32,
+    #[serde(default)]
+    pub prompt_templates: HashMap<String, String>,
+    #[serde(default)]
+    pub generation: GenerationPresets,
     #[serde(default = "default_timing_enabled")]
     pub timing_enabled: bool,
     #[serde(default = "default_npc_recent_messages_limit")]
     pub npc_recent_messages_limit: usize,
     #[serde(default)]
     pub use_structured_events: bool,
+    #[serde(default)]
+    pub multi_message_prompts: bool,
+    #[serde(default)]
+    pub role_backends: RoleBackends,
     #[serde(default = "default_debug_messages_enabled")]
     pub debug_messages_enabled: bool,
+    #[serde(default)]
+    pub review_events: bool,
 }
 
 fn default_npc_recent_messages_limit() -> usize {
@@ -1296,6 +1657,26 @@ fn default_debug_messages_enabled() -> bool {
     true
 }
 
+fn default_history_token_budget() -> Option<usize> {
+    None
+}
+
+fn default_context_window_tokens() -> Option<usize> {
+    None
+}
+
+fn default_relationship_decay_per_day() -> u32 {
+    1
+}
+
+fn default_quest_offer_turns() -> u32 {
+    3
+}
+
+fn default_story_summary_interval() -> u32 {
+    10
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -1310,10 +1691,18 @@ impl Default for AppConfig {
             chat_log_limit: None,
             save_full_chat_log: false,
             prompt_history_limit: Some(50),
+            history_token_budget: default_history_token_budget(),
+            context_window_tokens: default_context_window_tokens(),
+            story_summary_interval: default_story_summary_interval(),
+            prompt_templates: HashMap::new(),
+            generation: GenerationPresets::default(),
             timing_enabled: default_timing_enabled(),
             npc_recent_messages_limit: default_npc_recent_messages_limit(),
             use_structured_events: false,
+            multi_message_prompts: false,
+            role_backends: RoleBackends::default(),
             debug_messages_enabled: default_debug_messages_enabled(),
+            review_events: false,
         }
     }
 }
@@ -1329,19 +1718,83 @@ pub struct SerializableColor {
     pub a: u8,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum UiLlmApiMode {
+    #[default]
     OpenAiChat,
     KoboldCpp,
+    Ollama,
+    LlamaCpp,
 }
 
-impl Default for UiLlmApiMode {
-    fn default() -> Self {
-        Self::OpenAiChat
+impl UiLlmApiMode {
+    pub const ALL: [UiLlmApiMode; 4] = [
+        UiLlmApiMode::OpenAiChat,
+        UiLlmApiMode::KoboldCpp,
+        UiLlmApiMode::Ollama,
+        UiLlmApiMode::LlamaCpp,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UiLlmApiMode::OpenAiChat => "OpenAI-compatible",
+            UiLlmApiMode::KoboldCpp => "KoboldCpp native",
+            UiLlmApiMode::Ollama => "Ollama native",
+            UiLlmApiMode::LlamaCpp => "llama.cpp server native",
+        }
+    }
+
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            UiLlmApiMode::OpenAiChat => "http://localhost:1234/v1",
+            UiLlmApiMode::KoboldCpp => "http://localhost:5001",
+            UiLlmApiMode::Ollama => "http://localhost:11434",
+            UiLlmApiMode::LlamaCpp => "http://localhost:8080",
+        }
+    }
+}
+
+/// A separate backend for one kind of LLM call. Disabled roles use the
+/// main connection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoleBackend {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default)]
+    pub api_mode: UiLlmApiMode,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoleBackends {
+    #[serde(default)]
+    pub narrator: RoleBackend,
+    #[serde(default)]
+    pub events: RoleBackend,
+    #[serde(default)]
+    pub summarizer: RoleBackend,
+    #[serde(default)]
+    pub followup: RoleBackend,
+}
+
+impl RoleBackends {
+    pub fn entries(&self) -> [(LlmRole, &RoleBackend); 4] {
+        [
+            (LlmRole::Narrator, &self.narrator),
+            (LlmRole::Events, &self.events),
+            (LlmRole::Summarizer, &self.summarizer),
+            (LlmRole::Followup, &self.followup),
+        ]
     }
 }
 
+
 impl From<SerializableColor> for egui::Color32 {
     fn from(c: SerializableColor) -> Self {
         egui::Color32::from_rgba_unmultiplied(c.r, c.g, c.b, c.a)
@@ -1363,6 +1816,8 @@ pub struct MyApp {
     pub ui: UiState,
     cmd_tx: mpsc::Sender<EngineCommand>,
     resp_rx: mpsc::Receiver<EngineResponse>,
+    /// Shared with the engine so Stop reaches calls it is blocked on
+    abort: AbortSignal,
 }
 
 impl MyApp {
@@ -1370,8 +1825,10 @@ impl MyApp {
         let (cmd_tx, cmd_rx) = mpsc::channel();
         let (resp_tx, resp_rx) = mpsc::channel();
 
+        let abort = AbortSignal::default();
+        let engine_abort = abort.clone();
         std::thread::spawn(move || {
-            let mut engine = Engine::new(cmd_rx, resp_tx);
+            let mut engine = Engine::new(cmd_rx, resp_tx, engine_abort);
             engine.run();
         });
 
@@ -1386,32 +1843,39 @@ impl MyApp {
         let _ = cmd_tx.send(EngineCommand::SetNpcRecencyLimit {
             limit: ui.npc_recent_messages_limit.max(1),
         });
+        let _ = cmd_tx.send(EngineCommand::SetStorySummaryInterval {
+            turns: ui.story_summary_interval,
+        });
+        let _ = cmd_tx.send(EngineCommand::SetEventReview {
+            enabled: ui.review_events,
+        });
 
-        Self { ui, cmd_tx, resp_rx }
+        Self { ui, cmd_tx, resp_rx, abort }
     }
 
     pub fn send_command(&self, cmd: EngineCommand) {
         let _ = self.cmd_tx.send(cmd);
     }
 
+    /// Abort the turn in flight, including calls the engine is blocked on.
+    pub fn stop_generation(&self) {
+        self.abort.stop();
+        self.send_command(EngineCommand::StopGeneration);
+    }
+
     pub fn build_game_context(&self) -> GameContext {
-        let history = match self.ui.prompt_history_limit {
-            Some(0) => Vec::new(),
-            Some(limit) => {
-                if self.ui.rendered_messages.len() > limit {
-                    self.ui.rendered_messages[self.ui.rendered_messages.len() - limit..].to_vec()
-                } else {
-                    self.ui.rendered_messages.clone()
-                }
-            }
-            None => self.ui.rendered_messages.clone(),
-        };
         GameContext {
             world: self.ui.world.clone(),
             player: self.ui.character.clone(),
             party: self.ui.party.clone(),
-            history,
+            // Set by the engine from its own log
+            history: Vec::new(),
+            history_limit: self.ui.prompt_history_limit,
+            history_start: 0,
             snapshot: self.ui.snapshot.clone(),
+            history_token_budget: self.ui.history_token_budget,
+            context_window: self.ui.context_window_tokens,
+            prompt_templates: self.ui.prompt_templates.clone(),
         }
     }
 }
@@ -1460,15 +1924,27 @@ impl eframe::App for MyApp {
                     self.ui.sync_party_from_messages();
                     self.ui.ensure_left_tab_visible();
                     if self.ui.debug_messages_enabled {
-                        for a in report.applications {
-                            let t = format!("{:?}", a.outcome);
-                            self.ui.rendered_messages.push(Message::System(t));
+                        for a in &report.applications {
+                            self.ui.rendered_messages.push(Message::System(a.summary()));
                         }
+                        for change in &report.tier_changes {
+                            self.ui
+                                .rendered_messages
+                                .push(Message::System(format!("Reputation tier: {}", change.summary())));
+                        }
+                    }
+                    for change in report.tier_changes {
+                        self.ui.faction_tier_changes.insert(change.faction_id.clone(), change);
+                    }
+                    for failure in report.quest_failures {
+                        self.ui.rendered_messages.push(Message::System(failure));
                     }
                     self.ui.apply_chat_log_limit();
                     self.ui.is_generating = false;
                 }
                 EngineResponse::GameLoaded { save, snapshot } => {
+                    self.ui.event_review = None;
+                    self.ui.faction_tier_changes.clear();
                     self.ui.world = save.world;
                     self.ui.character = save.player;
                     self.ui.party = Vec::new();
@@ -1489,9 +1965,20 @@ impl eframe::App for MyApp {
                     self.ui.sync_player_from_snapshot(&snapshot);
                     self.ui.ensure_left_tab_visible();
                 }
-                EngineResponse::LlmConnectionResult { success, message } => {
+                EngineResponse::LlmConnectionResult { success, message, models } => {
                     self.ui.llm_connected = success;
                     self.ui.llm_status = message;
+                    self.ui.llm_models = models;
+                }
+                EngineResponse::SummaryApplied { snapshot, note } => {
+                    self.ui.snapshot = Some(snapshot);
+                    if self.ui.debug_messages_enabled {
+                        self.ui.rendered_messages.push(Message::System(note));
+                        self.ui.apply_chat_log_limit();
+                    }
+                }
+                EngineResponse::EventsAwaitingReview { events } => {
+                    self.ui.event_review = Some(EventReviewState::new(events));
                 }
             }
         }
@@ -1510,6 +1997,7 @@ impl eframe::App for MyApp {
 
         draw_settings_window(ctx, &mut self.ui, &self.cmd_tx);
         draw_options_window(ctx, &mut self.ui, &self.cmd_tx);
+        draw_event_review_window(ctx, &mut self.ui, &self.cmd_tx);
     }
 }
 
@@ -1605,12 +2093,75 @@ fn draw_settings_window(
                     }
                 });
 
+            ui.label("Context Window (tokens)");
+            let mut context_window = ui_state.context_window_tokens;
+            let mut context_window_changed = false;
+            egui::ComboBox::from_id_salt("context_window_tokens")
+                .selected_text(match context_window {
+                    None => "Unlimited".to_string(),
+                    Some(value) => value.to_string(),
+                })
+                .show_ui(ui, |ui| {
+                    for value in [2048_usize, 4096, 8192, 16384, 32768, 65536, 131072] {
+                        if ui
+                            .selectable_label(context_window == Some(value), value.to_string())
+                            .clicked()
+                        {
+                            context_window = Some(value);
+                            context_window_changed = true;
+                        }
+                    }
+                    if ui.selectable_label(context_window.is_none(), "Unlimited").clicked() {
+                        context_window = None;
+                        context_window_changed = true;
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "When set, low-priority prompt sections are trimmed to fit. Prompt size is \
+                     estimated, not counted by the model's tokenizer: one token per 4 letters or \
+                     digits in a word, plus one per punctuation mark.",
+                );
+
+            ui.label("History Token Budget");
+            let mut token_budget = ui_state.history_token_budget;
+            let mut token_budget_changed = false;
+            egui::ComboBox::from_id_salt("history_token_budget")
+                .selected_text(match token_budget {
+                    None => "Unlimited".to_string(),
+                    Some(value) => value.to_string(),
+                })
+                .show_ui(ui, |ui| {
+                    for value in [1000_usize, 2000, 4000, 8000, 16000] {
+                        if ui
+                            .selectable_label(token_budget == Some(value), value.to_string())
+                            .clicked()
+                        {
+                            token_budget = Some(value);
+                            token_budget_changed = true;
+                        }
+                    }
+                    if ui.selectable_label(token_budget.is_none(), "Unlimited").clicked() {
+                        token_budget = None;
+                        token_budget_changed = true;
+                    }
+                });
+
+            ui.label("Summarize story every N turns (0 = off)");
+            let summary_interval_changed = ui
+                .add(egui::DragValue::new(&mut ui_state.story_summary_interval).range(0..=100))
+                .changed();
+
             let timing_changed = ui
                 .checkbox(&mut ui_state.timing_enabled, "Show timing debug lines")
                 .changed();
             let debug_messages_changed = ui
                 .checkbox(&mut ui_state.debug_messages_enabled, "Show debug system messages")
                 .changed();
+            let review_changed = ui
+                .checkbox(&mut ui_state.review_events, "Review LLM events before applying")
+                .on_hover_text("Event types the world auto-approves are still applied right away.")
+                .changed();
 
             ui.heading("Speaker Colors");
 
@@ -1626,8 +2177,12 @@ fn draw_settings_window(
                 || chat_limit_changed
                 || save_chat_log_changed
                 || prompt_history_changed
+                || token_budget_changed
+                || context_window_changed
+                || summary_interval_changed
                 || timing_changed
                 || debug_messages_changed
+                || review_changed
                 || ui.button("Save").clicked()
             {
                 if chat_limit_changed {
@@ -1637,6 +2192,17 @@ fn draw_settings_window(
                 if prompt_history_changed {
                     ui_state.prompt_history_limit = prompt_history;
                 }
+                if token_budget_changed {
+                    ui_state.history_token_budget = token_budget;
+                }
+                if context_window_changed {
+                    ui_state.context_window_tokens = context_window;
+                }
+                if summary_interval_changed {
+                    let _ = cmd_tx.send(EngineCommand::SetStorySummaryInterval {
+                        turns: ui_state.story_summary_interval,
+                    });
+                }
                 if timing_changed {
                     let _ = cmd_tx.send(EngineCommand::SetTimingEnabled {
                         enabled: ui_state.timing_enabled,
@@ -1647,6 +2213,11 @@ fn draw_settings_window(
                         enabled: ui_state.debug_messages_enabled,
                     });
                 }
+                if review_changed {
+                    let _ = cmd_tx.send(EngineCommand::SetEventReview {
+                        enabled: ui_state.review_events,
+                    });
+                }
                 save_config(ui_state);
             }
         });
@@ -1654,6 +2225,69 @@ fn draw_settings_window(
     ui_state.show_settings = open;
 }
 
+/// Accept/reject toggles for held-back events; only the approved set is
+/// applied by the engine.
+fn draw_event_review_window(
+    ctx: &egui::Context,
+    ui_state: &mut UiState,
+    cmd_tx: &mpsc::Sender<EngineCommand>,
+) {
+    let Some(review) = ui_state.event_review.as_mut() else {
+        return;
+    };
+    let mut submit = false;
+
+    egui::Window::new("📝 Review Events")
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label("The narrator proposed these changes. Edit, accept or reject each one.");
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    for (index, item) in review.items.iter_mut().enumerate() {
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut item.accepted, "Accept");
+                            ui.strong(&item.event_type);
+                        });
+                        ui.push_id(index, |ui| {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut item.json)
+                                    .code_editor()
+                                    .desired_rows(4)
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                    }
+                });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Accept all").clicked() {
+                    review.items.iter_mut().for_each(|item| item.accepted = true);
+                }
+                if ui.button("Reject all").clicked() {
+                    review.items.iter_mut().for_each(|item| item.accepted = false);
+                }
+                if ui.button("Apply").clicked() {
+                    submit = true;
+                }
+            });
+            if let Some(err) = &review.error {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+        });
+
+    if submit {
+        match review.decisions() {
+            Ok((approved, rejected)) => {
+                let _ = cmd_tx.send(EngineCommand::ResolveEventReview { approved, rejected });
+                ui_state.event_review = None;
+            }
+            Err(err) => review.error = Some(err),
+        }
+    }
+}
+
 fn draw_options_window(
     ctx: &egui::Context,
     ui_state: &mut UiState,
@@ -1682,6 +2316,26 @@ fn draw_options_window(
                                 .hint_text("local-model"),
                         )
                         .changed();
+                    if !ui_state.llm_models.is_empty() {
+                        let selected = if ui_state.llm_models.contains(&ui_state.llm_model) {
+                            ui_state.llm_model.clone()
+                        } else {
+                            "Pick a model".to_string()
+                        };
+                        egui::ComboBox::from_id_salt("llm_model_list")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for model in &ui_state.llm_models {
+                                    llm_changed |= ui
+                                        .selectable_value(
+                                            &mut ui_state.llm_model,
+                                            model.clone(),
+                                            model,
+                                        )
+                                        .changed();
+                                }
+                            });
+                    }
 
                     ui.label("LLM API Key (optional)");
                     llm_changed |= ui
@@ -1708,10 +2362,30 @@ fn draw_options_window(
                             "KoboldCpp native",
                         )
                         .changed();
+                    llm_changed |= ui
+                        .radio_value(&mut ui_state.llm_api_mode, UiLlmApiMode::Ollama, "Ollama native")
+                        .changed();
+                    llm_changed |= ui
+                        .radio_value(
+                            &mut ui_state.llm_api_mode,
+                            UiLlmApiMode::LlamaCpp,
+                            "llama.cpp server native",
+                        )
+                        .changed();
                     llm_changed |= ui
                         .checkbox(
                             &mut ui_state.use_structured_events,
-                            "Use structured EVENTS (LM Studio only)",
+                            "Use structured EVENTS (schema or grammar constrained)",
+                        )
+                        .changed();
+                    llm_changed |= ui
+                        .checkbox(
+                            &mut ui_state.multi_message_prompts,
+                            "Send prompt as separate chat messages",
+                        )
+                        .on_hover_text(
+                            "System rules and world first, then the history as user/assistant turns, \
+                             then your action. OpenAI-compatible and Ollama only.",
                         )
                         .changed();
 
@@ -1736,6 +2410,24 @@ fn draw_options_window(
                     ui.label("KoboldCpp native: POST /api/v1/generate");
                     ui.label("KoboldCpp abort: POST /api/extra/abort");
 
+                    ui.add_space(6.0);
+                    ui.label("Other Native Backends");
+                    ui.horizontal(|ui| {
+                        if ui.button("Use Ollama").clicked() {
+                            ui_state.llm_api_mode = UiLlmApiMode::Ollama;
+                            ui_state.llm_base_url = "http://localhost:11434".to_string();
+                            llm_changed = true;
+                        }
+                        if ui.button("Use llama.cpp").clicked() {
+                            ui_state.llm_api_mode = UiLlmApiMode::LlamaCpp;
+                            ui_state.llm_base_url = "http://localhost:8080".to_string();
+                            llm_changed = true;
+                        }
+                    });
+                    ui.label("Ollama: POST /api/chat (format = EVENTS schema)");
+                    ui.label("llama.cpp: POST /completion (grammar = EVENTS GBNF)");
+                    ui.label("Stop closes the stream, which cancels both.");
+
                     if llm_changed {
                         save_config(ui_state);
                     }
@@ -1746,6 +2438,26 @@ fn draw_options_window(
                         });
                     }
 
+                    ui.add_space(6.0);
+                    ui.separator();
+                    ui.heading("Backends by Role");
+                    ui.label("Send some calls to another backend, e.g. a small fast model for EVENTS.");
+                    let mut roles_changed = false;
+                    let roles = &mut ui_state.role_backends;
+                    for (id, title, backend) in [
+                        ("narrator", "Narrator (story turns)", &mut roles.narrator),
+                        ("events", "Structured EVENTS extraction", &mut roles.events),
+                        ("summarizer", "Story summaries", &mut roles.summarizer),
+                        ("followup", "Follow-up after request_context", &mut roles.followup),
+                    ] {
+                        ui.collapsing(title, |ui| {
+                            roles_changed |= draw_role_backend(ui, id, backend);
+                        });
+                    }
+                    if roles_changed {
+                        save_config(ui_state);
+                    }
+
                     ui.add_space(6.0);
                     ui.separator();
                     ui.heading("NPC Proximity");
@@ -1762,6 +2474,33 @@ fn draw_options_window(
                         save_config(ui_state);
                     }
 
+                    ui.add_space(6.0);
+                    ui.separator();
+                    ui.heading("Generation Presets");
+                    ui.label("Worlds can override these in the World tab.");
+                    let mut generation_changed = false;
+                    ui.collapsing("Narration", |ui| {
+                        generation_changed |= draw_sampling_settings(
+                            ui,
+                            "options_narration",
+                            &mut ui_state.generation.narration,
+                        );
+                    });
+                    ui.collapsing("Structured EVENTS extraction", |ui| {
+                        generation_changed |= draw_sampling_settings(
+                            ui,
+                            "options_events",
+                            &mut ui_state.generation.events,
+                        );
+                    });
+                    if generation_changed {
+                        save_config(ui_state);
+                    }
+
+                    ui.add_space(6.0);
+                    ui.separator();
+                    draw_prompt_template_editor(ui, ui_state);
+
                     let status_color = if ui_state.llm_connected {
                         egui::Color32::GREEN
                     } else {
@@ -1814,6 +2553,225 @@ fn draw_options_window(
     ui_state.show_options = open;
 }
 
+/// Edit one role's backend. Returns true when anything changed.
+fn draw_role_backend(ui: &mut egui::Ui, id_salt: &str, backend: &mut RoleBackend) -> bool {
+    let mut changed = ui
+        .checkbox(&mut backend.enabled, "Use a separate backend")
+        .changed();
+    if !backend.enabled {
+        return changed;
+    }
+
+    egui::ComboBox::from_id_salt(("role_backend_mode", id_salt))
+        .selected_text(backend.api_mode.label())
+        .show_ui(ui, |ui| {
+            for mode in UiLlmApiMode::ALL {
+                changed |= ui
+                    .selectable_value(&mut backend.api_mode, mode, mode.label())
+                    .changed();
+            }
+        });
+    ui.label("Base URL");
+    changed |= ui
+        .add(
+            egui::TextEdit::singleline(&mut backend.base_url)
+                .hint_text(backend.api_mode.default_base_url()),
+        )
+        .changed();
+    ui.label("Model");
+    changed |= ui
+        .add(egui::TextEdit::singleline(&mut backend.model).hint_text("local-model"))
+        .changed();
+    ui.label("API Key (optional)");
+    changed |= ui
+        .add(egui::TextEdit::singleline(&mut backend.api_key).password(true))
+        .changed();
+    changed
+}
+
+/// Largest seed the editor offers; `DragValue` edits through f64, which
+/// holds integers exactly only up to 2^53.
+const MAX_SEED: u64 = 1 << 53;
+
+/// Editor for one set of sampler settings. Optional values get a checkbox;
+/// unchecked leaves the backend default. Returns true when anything changed.
+pub fn draw_sampling_settings(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    settings: &mut SamplingSettings,
+) -> bool {
+    let mut changed = false;
+
+    egui::ComboBox::from_id_salt((id_salt, "preset"))
+        .selected_text("Load preset…")
+        .show_ui(ui, |ui| {
+            for (name, preset) in SamplingSettings::presets() {
+                if ui.selectable_label(false, name).clicked() {
+                    let timeout_secs = settings.timeout_secs;
+                    *settings = SamplingSettings { timeout_secs, ..preset };
+                    changed = true;
+                }
+            }
+        });
+
+    egui::Grid::new((id_salt, "grid")).num_columns(2).show(ui, |ui| {
+        ui.label("Temperature");
+        changed |= ui
+            .add(egui::DragValue::new(&mut settings.temperature).speed(0.05).range(0.0..=2.0))
+            .changed();
+        ui.end_row();
+
+        changed |= optional_value(ui, "Top P", &mut settings.top_p, 0.95, 0.01, 0.0..=1.0);
+        changed |= optional_value(ui, "Top K", &mut settings.top_k, 40, 1.0, 1..=500);
+        changed |= optional_value(ui, "Min P", &mut settings.min_p, 0.05, 0.01, 0.0..=1.0);
+        changed |= optional_value(
+            ui,
+            "Repetition penalty",
+            &mut settings.repetition_penalty,
+            1.1,
+            0.01,
+            0.5..=2.0,
+        );
+        changed |= optional_value(ui, "Max tokens", &mut settings.max_tokens, 512, 8.0, 1..=32768);
+        changed |= optional_value(ui, "Seed", &mut settings.seed, 1, 1.0, 0..=MAX_SEED);
+
+        ui.label("Timeout (s)");
+        changed |= ui
+            .add(egui::DragValue::new(&mut settings.timeout_secs).range(5..=1800))
+            .changed();
+        ui.end_row();
+    });
+
+    ui.label("Stop sequences (one per line, \\n for newline)");
+    let mut stop_text = settings.stop.join("\n");
+    if ui
+        .add(egui::TextEdit::multiline(&mut stop_text).desired_rows(2))
+        .changed()
+    {
+        settings.stop = stop_text.split('\n').map(str::to_string).collect();
+        if settings.stop.len() == 1 && settings.stop[0].is_empty() {
+            settings.stop.clear();
+        }
+        changed = true;
+    }
+
+    changed
+}
+
+fn optional_value<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<T>,
+    default: T,
+    speed: f64,
+    range: std::ops::RangeInclusive<T>,
+) -> bool {
+    let mut changed = false;
+    let mut enabled = value.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *value = enabled.then_some(default);
+        changed = true;
+    }
+    if let Some(v) = value.as_mut() {
+        changed |= ui
+            .add(egui::DragValue::new(v).speed(speed).range(range))
+            .changed();
+    } else {
+        ui.label("backend default");
+    }
+    ui.end_row();
+    changed
+}
+
+fn draw_prompt_template_editor(ui: &mut egui::Ui, ui_state: &mut UiState) {
+    ui.heading("Prompt Templates");
+    ui.label("Override prompt sections. Templates see world, player, party, snapshot and followup.");
+
+    let editor = &mut ui_state.template_editor;
+    if editor.selected.is_empty() {
+        editor.selected = prompt_templates::TEMPLATE_DEFAULTS[0].0.to_string();
+    }
+    egui::ComboBox::from_id_salt("prompt_template_name")
+        .selected_text(editor.selected.clone())
+        .show_ui(ui, |ui| {
+            for (name, _) in prompt_templates::TEMPLATE_DEFAULTS {
+                if ui
+                    .selectable_label(editor.selected == *name, *name)
+                    .clicked()
+                {
+                    editor.selected = name.to_string();
+                    editor.loaded = false;
+                }
+            }
+        });
+    ui.horizontal(|ui| {
+        if ui.radio_value(&mut editor.world_scope, false, "My config").changed() {
+            editor.loaded = false;
+        }
+        if ui.radio_value(&mut editor.world_scope, true, "This world").changed() {
+            editor.loaded = false;
+        }
+    });
+
+    let name = editor.selected.clone();
+    let world_scope = editor.world_scope;
+    let overrides = if world_scope {
+        &mut ui_state.world.prompt_templates
+    } else {
+        &mut ui_state.prompt_templates
+    };
+    let editor = &mut ui_state.template_editor;
+    if !editor.loaded {
+        editor.text = overrides
+            .get(&name)
+            .cloned()
+            .or_else(|| prompt_templates::default_template(&name).map(str::to_string))
+            .unwrap_or_default();
+        editor.error = None;
+        editor.loaded = true;
+    }
+    ui.label(if overrides.contains_key(&name) {
+        "Overridden"
+    } else {
+        "Using default"
+    });
+    if world_scope {
+        ui.label("World overrides are stored with the world file and game saves.");
+    }
+
+    ui.add(
+        egui::TextEdit::multiline(&mut editor.text)
+            .code_editor()
+            .desired_rows(10)
+            .desired_width(f32::INFINITY),
+    );
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        if ui.button("Save override").clicked() {
+            match prompt_templates::validate_template(&editor.text) {
+                Ok(()) => {
+                    overrides.insert(name.clone(), editor.text.clone());
+                    editor.error = None;
+                    changed = true;
+                }
+                Err(err) => editor.error = Some(err),
+            }
+        }
+        if ui.button("Reset to default").clicked() {
+            overrides.remove(&name);
+            editor.loaded = false;
+            changed = true;
+        }
+    });
+    if let Some(err) = &editor.error {
+        ui.colored_label(egui::Color32::from_rgb(220, 80, 80), err);
+    }
+    if changed && !world_scope {
+        save_config(ui_state);
+    }
+}
+
 fn optional_tabs_status(ui_state: &UiState) -> String {
     let mut unlocked = Vec::new();
     if ui_state.optional_tabs.slaves.unlocked {
@@ -1903,10 +2861,18 @@ pub(crate) fn save_config(ui: &UiState) {
         chat_log_limit: ui.chat_log_limit,
         save_full_chat_log: ui.save_full_chat_log,
         prompt_history_limit: ui.prompt_history_limit,
+        history_token_budget: ui.history_token_budget,
+        context_window_tokens: ui.context_window_tokens,
+        story_summary_interval: ui.story_summary_interval,
+        prompt_templates: ui.prompt_templates.clone(),
+        generation: ui.generation.clone(),
         timing_enabled: ui.timing_enabled,
         npc_recent_messages_limit: ui.npc_recent_messages_limit.max(1),
         use_structured_events: ui.use_structured_events,
+        multi_message_prompts: ui.multi_message_prompts,
+        role_backends: ui.role_backends.clone(),
         debug_messages_enabled: ui.debug_messages_enabled,
+        review_events: ui.review_events,
     };
     if let Ok(json) = serde_json::to_string_pretty(&cfg) {
         let _ = fs::write(config_path(), json);
@@ -1935,10 +2901,18 @@ fn load_config(ui: &mut UiState) {
             ui.chat_log_limit = cfg.chat_log_limit;
             ui.save_full_chat_log = cfg.save_full_chat_log;
             ui.prompt_history_limit = cfg.prompt_history_limit;
+            ui.history_token_budget = cfg.history_token_budget;
+            ui.context_window_tokens = cfg.context_window_tokens;
+            ui.story_summary_interval = cfg.story_summary_interval;
+            ui.prompt_templates = cfg.prompt_templates;
+            ui.generation = cfg.generation;
             ui.timing_enabled = cfg.timing_enabled;
             ui.npc_recent_messages_limit = cfg.npc_recent_messages_limit.max(1);
             ui.use_structured_events = cfg.use_structured_events;
+            ui.multi_message_prompts = cfg.multi_message_prompts;
+            ui.role_backends = cfg.role_backends;
             ui.debug_messages_enabled = cfg.debug_messages_enabled;
+            ui.review_events = cfg.review_events;
             sanitize_ui_scales(ui);
             ui.apply_chat_log_limit();
         }
@@ -2030,6 +3004,30 @@ fn extract_character_json_from_png(path: &Path) -> Option<String> {
     None
 }
 
+/// Read a tEXt chunk by walking the PNG chunk list directly. Card tools
+/// often write their chunk after the image data, where `read_info` stops.
+fn png_text_chunk(path: &Path, keyword: &str) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let mut pos = 8;
+    while pos + 8 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().ok()?) as usize;
+        let kind = &bytes[pos + 4..pos + 8];
+        let data = bytes.get(pos + 8..pos + 8 + len)?;
+        if kind == b"tEXt" {
+            if let Some(split) = data.iter().position(|b| *b == 0) {
+                if &data[..split] == keyword.as_bytes() {
+                    // tEXt is Latin-1; card payloads are base64 so this is lossless
+                    return Some(data[split + 1..].iter().map(|b| *b as char).collect());
+                }
+            }
+        } else if kind == b"IEND" {
+            break;
+        }
+        pos += 12 + len;
+    }
+    None
+}
+
 fn force_png_extension(mut path: PathBuf) -> Option<PathBuf> {
     let needs_png = match path.extension().and_then(|ext| ext.to_str()) {
         Some(ext) => !ext.eq_ignore_ascii_case("png"),
@@ -2057,3 +3055,41 @@ fn write_png_with_character_json(
     writer.write_image_data(rgba)?;
     Ok(())
 }
+
+/// Like `write_png_with_character_json`, plus a V2 `chara` chunk so other
+/// front-ends can read the card.
+fn write_png_with_card(
+    path: &Path,
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+    json: &str,
+    card_base64: &str,
+) -> anyhow::Result<()> {
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.add_itxt_chunk(CHARACTER_PNG_KEY.to_string(), json.to_string())?;
+    encoder.add_text_chunk(CARD_V2_PNG_KEY.to_string(), card_base64.to_string())?;
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgba)?;
+    Ok(())
+}
+
+/// Cards exported by this app carry the full character; anything else maps
+/// description, personality and scenario into the background.
+fn character_from_card(card: &CharacterCard) -> CharacterDefinition {
+    if let Some(character) = card
+        .upf_character
+        .as_ref()
+        .and_then(|value| serde_json::from_value::<CharacterDefinition>(value.clone()).ok())
+    {
+        return character;
+    }
+    CharacterDefinition {
+        name: card.name.clone(),
+        background: card.details(),
+        ..CharacterDefinition::default()
+    }
+}
@@ -140,6 +140,8 @@ pub fn draw_center_panel(ctx: &egui::Context, app: &mut MyApp) {
             let opening_message = app.ui.world.opening_message.clone();
             app.send_command(EngineCommand::InitializeNarrative { opening_message });
         } else if reset_session {
+            app.ui.event_review = None;
+            app.ui.faction_tier_changes.clear();
             let opening_message = app.ui.world.opening_message.clone();
             app.send_command(EngineCommand::InitializeNarrative { opening_message });
         }
@@ -358,7 +360,7 @@ pub fn draw_center_panel(ctx: &egui::Context, app: &mut MyApp) {
                         .clicked()
                     {
                         app.ui.is_generating = false;
-                        app.send_command(EngineCommand::StopGeneration);
+                        app.stop_generation();
                     }
                 });
         }
@@ -2,8 +2,12 @@ use eframe::egui;
 use std::sync::mpsc::Sender;
 
 use crate::engine::protocol::EngineCommand;
+use crate::model::game_state::{
+    party_morale, NpcMemory, NpcRoutine, Quest, QuestOffer, QuestStatus, Relationship,
+    ReputationTier,
+};
 use crate::ui::app::{LeftTab, PartyMember, UiState};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub fn draw_left_panel(
     ctx: &egui::Context,
@@ -60,7 +64,7 @@ pub fn draw_left_panel(
             egui::ScrollArea::vertical().show(ui, |ui| match ui_state.left_tab {
                 LeftTab::Party => draw_party(ui, ui_state, cmd_tx),
                 LeftTab::Npcs => draw_local_npcs(ui, ui_state, cmd_tx),
-                LeftTab::Quests => draw_quests(ui, ui_state),
+                LeftTab::Quests => draw_quests(ui, ui_state, cmd_tx),
                 LeftTab::Factions => draw_factions(ui, ui_state),
                 LeftTab::Slaves => draw_section_cards(ui, ui_state, "slaves", "Slaves"),
                 LeftTab::Property => draw_section_cards(ui, ui_state, "property", "Property"),
@@ -89,20 +93,50 @@ pub fn draw_left_panel(
 fn draw_party(ui: &mut egui::Ui, state: &mut UiState, cmd_tx: &Sender<EngineCommand>) {
     ui.heading("Party");
 
-    if ui.button("➕ Add Member").clicked() {
-        let _ = cmd_tx.send(EngineCommand::AddPartyMember {
-            name: "New Member".to_string(),
-            role: "Unknown".to_string(),
-            details: String::new(),
-            weapons: Vec::new(),
-            armor: Vec::new(),
-            clothing: Vec::new(),
-        });
-    }
+    ui.horizontal(|ui| {
+        if ui.button("➕ Add Member").clicked() {
+            let _ = cmd_tx.send(EngineCommand::AddPartyMember {
+                name: "New Member".to_string(),
+                role: "Unknown".to_string(),
+                details: String::new(),
+                weapons: Vec::new(),
+                armor: Vec::new(),
+                clothing: Vec::new(),
+            });
+        }
+        if ui
+            .button("🃏 Import Card")
+            .on_hover_text("Add a member from a Character Card (PNG or JSON)")
+            .clicked()
+        {
+            if let Some((card, _)) = state.pick_character_card() {
+                let _ = cmd_tx.send(EngineCommand::AddPartyMember {
+                    name: card.name.clone(),
+                    role: card.role(),
+                    details: card.details(),
+                    weapons: Vec::new(),
+                    armor: Vec::new(),
+                    clothing: Vec::new(),
+                });
+            }
+        }
+    });
 
     ui.separator();
 
     let mut remove_index: Option<usize> = None;
+    let morale_labels: Vec<Option<String>> = state
+        .party
+        .iter()
+        .map(|member| {
+            let id = member.id.as_deref()?;
+            let snapshot = state.snapshot.as_ref()?;
+            let morale = party_morale(&snapshot.relationships, id);
+            let tier = ReputationTier::for_reputation(&state.world.relationship_tiers, morale)
+                .map_or(String::new(), |t| format!(" ({})", t.name));
+            Some(format!("Morale: {}{}", morale, tier))
+        })
+        .collect();
 
     for (i, member) in state.party.iter_mut().enumerate() {
         ui.group(|ui| {
@@ -116,6 +150,9 @@ fn draw_party(ui: &mut egui::Ui, state: &mut UiState, cmd_tx: &Sender<EngineComm
                     }
                 }
             });
+            if let Some(label) = &morale_labels[i] {
+                ui.label(label);
+            }
 
             ui.label("Name");
             let mut lock_changed = false;
@@ -215,6 +252,9 @@ struct LocalNpc {
     name: String,
     role: String,
     notes: String,
+    memory: Option<NpcMemory>,
+    relationships: Vec<Relationship>,
+    routine: NpcRoutine,
 }
 
 fn draw_local_npcs(
@@ -252,6 +292,19 @@ fn draw_local_npcs(
             state.new_npc_role.clear();
             state.new_npc_notes.clear();
         }
+        if ui
+            .button("🃏 Import Card")
+            .on_hover_text("Create an NPC from a Character Card (PNG or JSON)")
+            .clicked()
+        {
+            if let Some((card, _)) = state.pick_character_card() {
+                let _ = cmd_tx.send(EngineCommand::CreateNpc {
+                    name: card.name.clone(),
+                    role: card.role(),
+                    details: card.details(),
+                });
+            }
+        }
     });
 
     ui.add_space(8.0);
@@ -306,12 +359,91 @@ fn draw_local_npcs(
                     if !npc.notes.is_empty() {
                         ui.label(format!("Notes: {}", npc.notes));
                     }
+                    if let Some(location) = &npc.routine.location {
+                        match &npc.routine.activity {
+                            Some(activity) => ui.label(format!("At: {} ({})", location, activity)),
+                            None => ui.label(format!("At: {}", location)),
+                        };
+                    }
+                    if !npc.routine.goals.is_empty() {
+                        ui.label(format!("Goals: {}", npc.routine.goals.join(", ")));
+                    }
+                    if !npc.routine.schedule.is_empty() {
+                        egui::CollapsingHeader::new("Schedule")
+                            .id_salt(("npc_schedule", &npc.id))
+                            .show(ui, |ui| {
+                                for slot in &npc.routine.schedule {
+                                    let activity = slot
+                                        .activity
+                                        .as_deref()
+                                        .map_or(String::new(), |a| format!(" ({})", a));
+                                    ui.label(format!("{:02}:00 {}{}", slot.hour, slot.location, activity));
+                                }
+                            });
+                    }
+                    if let Some(memory) = &npc.memory {
+                        egui::CollapsingHeader::new("Memory")
+                            .id_salt(("npc_memory", &npc.id))
+                            .show(ui, |ui| draw_npc_memory(ui, &npc.name, memory));
+                    }
+                    if !npc.relationships.is_empty() {
+                        egui::CollapsingHeader::new("Relationships")
+                            .id_salt(("npc_relationships", &npc.id))
+                            .show(ui, |ui| {
+                                draw_relationships(ui, &npc.relationships, &state.world.relationship_tiers)
+                            });
+                    }
         });
 
         ui.add_space(6.0);
     }
 }
 
+fn draw_npc_memory(ui: &mut egui::Ui, name: &str, memory: &NpcMemory) {
+    let sections = [
+        ("Knows", &memory.key_facts),
+        ("Player told them", &memory.player_told),
+        ("Promises", &memory.promises),
+    ];
+    for (label, items) in sections {
+        if items.is_empty() {
+            continue;
+        }
+        ui.label(egui::RichText::new(label).strong());
+        for item in items {
+            ui.label(format!("• {}", item));
+        }
+    }
+    let exchanges = [
+        ("Earlier exchanges", &memory.earlier_exchanges),
+        ("Recent exchanges", &memory.recent_exchanges),
+    ];
+    for (label, items) in exchanges {
+        if items.is_empty() {
+            continue;
+        }
+        ui.label(egui::RichText::new(label).strong());
+        for exchange in items {
+            ui.label(exchange.describe(name));
+        }
+    }
+}
+
+fn draw_relationships(ui: &mut egui::Ui, relationships: &[Relationship], tiers: &[ReputationTier]) {
+    for rel in relationships {
+        let tier = ReputationTier::for_reputation(tiers, rel.value)
+            .map_or(String::new(), |t| format!(" ({})", t.name));
+        ui.label(
+            egui::RichText::new(format!("{} → {}: {}{}", rel.subject_id, rel.target_id, rel.value, tier))
+                .strong(),
+        );
+        for note in rel.history.iter().rev() {
+            let reason = note.reason.as_deref().unwrap_or("no reason given");
+            ui.label(format!("• {:+} {}", note.delta, reason));
+        }
+    }
+}
+
 fn collect_local_npcs(state: &UiState) -> Vec<LocalNpc> {
     let mut map: HashMap<String, LocalNpc> = HashMap::new();
 
@@ -327,6 +459,18 @@ fn collect_local_npcs(state: &UiState) -> Vec<LocalNpc> {
                     name: npc.name.clone(),
                     role: npc.role.clone(),
                     notes: npc.notes.clone(),
+                    memory: snapshot
+                        .npc_memories
+                        .get(&npc.id)
+                        .filter(|m| !m.is_empty())
+                        .cloned(),
+                    relationships: snapshot
+                        .relationships
+                        .iter()
+                        .filter(|r| r.subject_id == npc.id || r.target_id == npc.id)
+                        .cloned()
+                        .collect(),
+                    routine: npc.routine.clone(),
                 },
             );
         }
@@ -339,7 +483,7 @@ fn collect_local_npcs(state: &UiState) -> Vec<LocalNpc> {
    Quest UI
    ========================= */
 
-fn draw_quests(ui: &mut egui::Ui, state: &UiState) {
+fn draw_quests(ui: &mut egui::Ui, state: &mut UiState, cmd_tx: &Sender<EngineCommand>) {
     ui.heading("Quests");
     ui.set_width(ui.available_width());
 
@@ -348,62 +492,247 @@ fn draw_quests(ui: &mut egui::Ui, state: &UiState) {
         return;
     };
 
-    if snapshot.quests.is_empty() {
+    if snapshot.quests.is_empty() && snapshot.quest_offers.is_empty() {
         ui.label("No quests yet.");
         return;
     }
 
-    let mut quests = snapshot.quests.clone();
-    quests.sort_by(|a, b| a.title.cmp(&b.title));
+    draw_quest_offers(ui, &snapshot.quest_offers, cmd_tx);
+
+    let mut tree = QuestTree {
+        quests: snapshot.quests.clone(),
+        now_minutes: snapshot.world_time_minutes,
+        shown: HashSet::new(),
+    };
+    tree.quests.sort_by(|a, b| a.title.cmp(&b.title));
+
+    // Chains are drawn as trees: each quest under the first quest it waits on.
+    let quests = tree.quests.clone();
+    for quest in quests.iter().filter(|q| quest_parents(&quests, q).is_empty()) {
+        tree.draw_node(ui, quest, &mut state.quest_counter_offers, cmd_tx);
+    }
+    for quest in &quests {
+        tree.draw_node(ui, quest, &mut state.quest_counter_offers, cmd_tx);
+    }
+}
 
-    for quest in quests {
+fn draw_quest_offers(ui: &mut egui::Ui, offers: &[QuestOffer], cmd_tx: &Sender<EngineCommand>) {
+    if offers.is_empty() {
+        return;
+    }
+    let mut offers: Vec<&QuestOffer> = offers.iter().collect();
+    offers.sort_by(|a, b| a.quest.title.cmp(&b.quest.title));
+    ui.label("Offered to you:");
+    for offer in offers {
         ui.group(|ui| {
-            ui.horizontal_wrapped(|ui| {
-                ui.add(egui::Label::new(&quest.title).wrap());
-                ui.add_space(4.0);
-                ui.add(egui::Label::new(format!("({})", quest_status_label(&quest.status))).wrap());
+            ui.add(egui::Label::new(offer.label()).wrap());
+            if !offer.quest.description.trim().is_empty() {
+                ui.add(egui::Label::new(&offer.quest.description).wrap());
+            }
+            if !offer.quest.rewards.is_empty() {
+                ui.add(egui::Label::new(format!("Rewards: {}", offer.quest.rewards.join(", "))).wrap());
+            }
+            ui.horizontal(|ui| {
+                for (label, accept) in [("Accept", true), ("Decline", false)] {
+                    if ui.button(label).clicked() {
+                        let _ = cmd_tx.send(EngineCommand::AnswerQuestOffer {
+                            quest_id: offer.quest.id.clone(),
+                            accept,
+                        });
+                    }
+                }
             });
+        });
+    }
+    ui.add_space(6.0);
+}
+
+struct QuestTree {
+    quests: Vec<Quest>,
+    now_minutes: u64,
+    shown: HashSet<String>,
+}
+
+/// Known quests `quest` waits on: its prerequisites plus quests listing it
+/// as a follow-up.
+fn quest_parents<'a>(quests: &'a [Quest], quest: &Quest) -> Vec<&'a Quest> {
+    quests
+        .iter()
+        .filter(|q| quest.prerequisites.contains(&q.id) || q.follow_ups.contains(&quest.id))
+        .collect()
+}
+
+impl QuestTree {
+    fn draw_node(
+        &mut self,
+        ui: &mut egui::Ui,
+        quest: &Quest,
+        drafts: &mut HashMap<String, String>,
+        cmd_tx: &Sender<EngineCommand>,
+    ) {
+        if !self.shown.insert(quest.id.clone()) {
+            return;
+        }
+        draw_quest_card(ui, &self.quests, quest, self.now_minutes);
+        draw_reward_negotiation(ui, quest, drafts, cmd_tx);
+        ui.add_space(6.0);
 
-            if !quest.description.trim().is_empty() {
-                ui.add(egui::Label::new(&quest.description).wrap());
+        let children: Vec<Quest> = self
+            .quests
+            .iter()
+            .filter(|q| !self.shown.contains(&q.id))
+            .filter(|q| quest_parents(&self.quests, q).iter().any(|p| p.id == quest.id))
+            .cloned()
+            .collect();
+        if children.is_empty() {
+            return;
+        }
+        ui.indent(("quest_chain", &quest.id), |ui| {
+            for child in &children {
+                self.draw_node(ui, child, drafts, cmd_tx);
             }
-            if let Some(diff) = &quest.difficulty {
-                let trimmed = diff.trim();
-                if !trimmed.is_empty() {
-                    ui.add(egui::Label::new(format!("Difficulty: {}", trimmed)).wrap());
+        });
+    }
+}
+
+/// Pick a reward option or send a counter-offer until rewards are agreed.
+fn draw_reward_negotiation(
+    ui: &mut egui::Ui,
+    quest: &Quest,
+    drafts: &mut HashMap<String, String>,
+    cmd_tx: &Sender<EngineCommand>,
+) {
+    let open = matches!(quest.status, QuestStatus::Active | QuestStatus::Locked);
+    if !open || quest.negotiation.locked || (quest.reward_options.is_empty() && !quest.negotiable) {
+        return;
+    }
+    ui.push_id(("reward_negotiation", &quest.id), |ui| {
+        for option in &quest.reward_options {
+            ui.horizontal_wrapped(|ui| {
+                if ui.small_button("Take").clicked() {
+                    let _ = cmd_tx.send(EngineCommand::PickQuestReward {
+                        quest_id: quest.id.clone(),
+                        option: option.clone(),
+                    });
                 }
-            }
-            if quest.negotiable {
-                ui.add(egui::Label::new("Negotiable rewards: yes").wrap());
-            }
-            if !quest.reward_options.is_empty() {
-                ui.add(egui::Label::new("Reward options:").wrap());
-                for opt in &quest.reward_options {
-                    ui.add(egui::Label::new(format!("- {}", opt)).wrap());
+                ui.add(egui::Label::new(option).wrap());
+            });
+        }
+        if !quest.negotiable {
+            return;
+        }
+        if let Some(counter) = &quest.negotiation.counter_offer {
+            ui.add(egui::Label::new(format!("Waiting on counter-offer: {}", counter.join(", "))).wrap());
+            return;
+        }
+        let draft = drafts.entry(quest.id.clone()).or_default();
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(draft)
+                    .hint_text("Counter-offer, comma separated")
+                    .desired_width(120.0),
+            );
+            if ui.small_button("Offer").clicked() {
+                let rewards: Vec<String> = draft
+                    .split(',')
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty())
+                    .collect();
+                if !rewards.is_empty() {
+                    let _ = cmd_tx.send(EngineCommand::CounterQuestReward {
+                        quest_id: quest.id.clone(),
+                        rewards,
+                    });
+                    draft.clear();
                 }
             }
+        });
+    });
+}
 
-            if !quest.rewards.is_empty() {
-                ui.add(egui::Label::new("Rewards:").wrap());
-                for reward in &quest.rewards {
-                    ui.add(egui::Label::new(format!("- {}", reward)).wrap());
-                }
+fn draw_quest_card(ui: &mut egui::Ui, quests: &[Quest], quest: &Quest, now_minutes: u64) {
+    ui.group(|ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.add(egui::Label::new(&quest.title).wrap());
+            ui.add_space(4.0);
+            ui.add(egui::Label::new(format!("({})", quest_status_label(&quest.status))).wrap());
+        });
+
+        if !quest.description.trim().is_empty() {
+            ui.add(egui::Label::new(&quest.description).wrap());
+        }
+        if let Some(diff) = &quest.difficulty {
+            let trimmed = diff.trim();
+            if !trimmed.is_empty() {
+                ui.add(egui::Label::new(format!("Difficulty: {}", trimmed)).wrap());
             }
+        }
+        let giver = [&quest.giver_npc, &quest.giver_faction]
+            .into_iter()
+            .flatten()
+            .map(|g| g.trim())
+            .filter(|g| !g.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !giver.is_empty() {
+            ui.add(egui::Label::new(format!("Given by: {}", giver)).wrap());
+        }
+        if let Some(location) = quest.location.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+            ui.add(egui::Label::new(format!("Location: {}", location)).wrap());
+        }
+        if let Some(deadline) = quest.deadline_minutes {
+            if quest.status == QuestStatus::Active {
+                let left = deadline.saturating_sub(now_minutes);
+                ui.add(egui::Label::new(format!("Deadline: {} min left", left)).wrap());
+            }
+        }
+        if quest.status == QuestStatus::Locked {
+            let waiting: Vec<&str> = quest_parents(quests, quest)
+                .into_iter()
+                .filter(|p| p.status != QuestStatus::Completed)
+                .map(|p| p.title.as_str())
+                .collect();
+            if !waiting.is_empty() {
+                ui.add(egui::Label::new(format!("Requires: {}", waiting.join(", "))).wrap());
+            }
+        }
+        if !quest.failure_conditions.is_empty() {
+            ui.add(egui::Label::new("Fails if:").wrap());
+            for condition in &quest.failure_conditions {
+                ui.add(egui::Label::new(format!("- {}", condition)).wrap());
+            }
+        }
+        if quest.negotiable {
+            ui.add(egui::Label::new("Negotiable rewards: yes").wrap());
+        }
+        if !quest.reward_options.is_empty() {
+            ui.add(egui::Label::new("Reward options:").wrap());
+            for opt in &quest.reward_options {
+                ui.add(egui::Label::new(format!("- {}", opt)).wrap());
+            }
+        }
 
-            if !quest.sub_quests.is_empty() {
-                ui.add(egui::Label::new("Sub-quests:").wrap());
-                for step in &quest.sub_quests {
-                    let mut completed = step.completed;
-                    ui.add_enabled(
-                        false,
-                        egui::Checkbox::new(&mut completed, step.description.as_str()),
-                    );
-                }
+        if !quest.rewards.is_empty() {
+            let label = if quest.negotiation.locked { "Rewards (agreed):" } else { "Rewards:" };
+            ui.add(egui::Label::new(label).wrap());
+            for reward in &quest.rewards {
+                ui.add(egui::Label::new(format!("- {}", reward)).wrap());
             }
-        });
+        }
 
-        ui.add_space(6.0);
-    }
+        if !quest.sub_quests.is_empty() {
+            ui.add(egui::Label::new("Sub-quests:").wrap());
+            for step in &quest.sub_quests {
+                let mut completed = step.completed;
+                let label = if step.optional {
+                    format!("{} (optional)", step.description)
+                } else {
+                    step.description.clone()
+                };
+                ui.add_enabled(false, egui::Checkbox::new(&mut completed, label));
+            }
+        }
+    });
 }
 
 fn draw_factions(ui: &mut egui::Ui, state: &UiState) {
@@ -422,12 +751,47 @@ fn draw_factions(ui: &mut egui::Ui, state: &UiState) {
 
     let mut factions = snapshot.factions.clone();
     factions.sort_by(|a, b| a.name.cmp(&b.name));
+    let faction_names = |ids: &[String]| {
+        ids.iter()
+            .map(|id| {
+                snapshot
+                    .factions
+                    .iter()
+                    .find(|f| &f.id == id)
+                    .map_or(id.as_str(), |f| f.name.as_str())
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
     for faction in factions {
         ui.group(|ui| {
             let kind = faction.kind.as_deref().unwrap_or("unknown");
             ui.label(format!("{} ({})", faction.name, kind));
-            ui.label(format!("Reputation: {}", faction.reputation));
+            match &faction.tier {
+                Some(tier) => ui.label(format!("Reputation: {} ({})", faction.reputation, tier)),
+                None => ui.label(format!("Reputation: {}", faction.reputation)),
+            };
+            if let Some(change) = state.faction_tier_changes.get(&faction.id) {
+                let (arrow, color) = if change.improved {
+                    ("▲", egui::Color32::from_rgb(90, 200, 120))
+                } else {
+                    ("▼", egui::Color32::from_rgb(220, 110, 110))
+                };
+                ui.colored_label(color, format!("{} {} -> {}", arrow, change.from, change.to));
+            }
+            if faction.member {
+                match &faction.rank {
+                    Some(rank) => ui.label(format!("Member, rank: {}", rank)),
+                    None => ui.label("Member"),
+                };
+            }
+            if !faction.allies.is_empty() {
+                ui.label(format!("Allies: {}", faction_names(&faction.allies)));
+            }
+            if !faction.enemies.is_empty() {
+                ui.label(format!("Enemies: {}", faction_names(&faction.enemies)));
+            }
             if let Some(desc) = &faction.description {
                 let trimmed = desc.trim();
                 if !trimmed.is_empty() {
@@ -501,6 +865,7 @@ fn bonded_servants_label(state: &UiState) -> &str {
 
 fn quest_status_label(status: &crate::model::game_state::QuestStatus) -> &'static str {
     match status {
+        crate::model::game_state::QuestStatus::Locked => "locked",
         crate::model::game_state::QuestStatus::Active => "active",
         crate::model::game_state::QuestStatus::Completed => "completed",
         crate::model::game_state::QuestStatus::Failed => "failed",
@@ -513,9 +878,9 @@ fn editable_list_with_id<T: std::hash::Hash>(
     id_key: T,
 ) {
     let mut remove_index: Option<usize> = None;
-    for i in 0..items.len() {
+    for (i, item) in items.iter_mut().enumerate() {
         ui.horizontal(|ui| {
-            ui.text_edit_singleline(&mut items[i]);
+            ui.text_edit_singleline(item);
             if ui.small_button("❌").clicked() {
                 remove_index = Some(i);
             }
@@ -2,7 +2,10 @@ use eframe::egui;
 use std::sync::mpsc::Sender;
 
 use crate::engine::protocol::EngineCommand;
-use crate::ui::app::{PowerEntry, RightTab, UiState};
+use crate::model::calendar::{Calendar, CalendarMonth, DayPhase, ScheduledEvent, Season};
+use crate::model::game_state::{EffectiveStat, GameStateSnapshot, ReputationTier, StatSource};
+use crate::model::lorebook::{LoreEntry, Lorebook};
+use crate::ui::app::{draw_sampling_settings, PowerEntry, RightTab, UiState};
 
 /// Draws the right-hand panel for editing Player or World info.
 pub fn draw_right_panel(
@@ -44,6 +47,8 @@ fn draw_player(ui: &mut egui::Ui, state: &mut UiState) {
     let mut do_save = false;
     let mut do_load = false;
     let mut do_upload = false;
+    let mut do_import_card = false;
+    let mut do_export_card = false;
 
     if ui.button("🖼 Upload Image").clicked() {
         do_upload = true;
@@ -60,10 +65,33 @@ fn draw_player(ui: &mut egui::Ui, state: &mut UiState) {
             do_load = true;
         }
     });
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(!state.player_locked, egui::Button::new("🃏 Import Card"))
+            .on_hover_text("SillyTavern / Character Card V2 or V3 (PNG or JSON)")
+            .on_disabled_hover_text("Character is locked in")
+            .clicked()
+        {
+            do_import_card = true;
+        }
+        if ui
+            .button("🃏 Export V2 Card")
+            .on_hover_text("PNG with a portrait, JSON otherwise")
+            .clicked()
+        {
+            do_export_card = true;
+        }
+    });
 
     if do_save {
         state.save_character();
     }
+    if do_import_card {
+        state.import_card_as_character(ui.ctx());
+    }
+    if do_export_card {
+        state.export_character_card();
+    }
     if do_load {
         if let Some(c) = state.load_character_from_dialog(ui.ctx()) {
             state.character = c;
@@ -94,7 +122,9 @@ fn draw_player(ui: &mut egui::Ui, state: &mut UiState) {
                 .text(format!("EXP: {}/{}", exp, exp_to_next)),
         );
         ui.label(format!("EXP to next level: {}", exp_to_next));
+        ui.label(format!("🕒 {}", state.world.calendar.describe(snapshot.world_time_minutes)));
         ui.add_space(6.0);
+        draw_status_effect_chips(ui, snapshot);
     }
 
     ui.separator();
@@ -114,6 +144,17 @@ fn draw_player(ui: &mut egui::Ui, state: &mut UiState) {
         });
     });
 
+    let effective_stats: std::collections::HashMap<String, EffectiveStat> = state
+        .snapshot
+        .as_ref()
+        .map(|s| {
+            s.effective_stats
+                .iter()
+                .map(|stat| (stat.id.clone(), stat.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+
     ui.collapsing("Stats", |ui| {
         ui.add_enabled_ui(!state.player_locked, |ui| {
             let mut remove_key: Option<String> = None;
@@ -122,6 +163,12 @@ fn draw_player(ui: &mut egui::Ui, state: &mut UiState) {
                     ui.horizontal(|ui| {
                         ui.label(&key);
                         ui.add(egui::DragValue::new(val).speed(1));
+                        if let Some(effective) = effective_stats.get(&key) {
+                            if effective.contributions.len() > 1 {
+                                ui.label(format!("→ {}", effective.total))
+                                    .on_hover_text(stat_breakdown_tooltip(effective));
+                            }
+                        }
                         if ui.small_button("❌").clicked() {
                             remove_key = Some(key.clone());
                         }
@@ -205,6 +252,70 @@ fn draw_player(ui: &mut egui::Ui, state: &mut UiState) {
     }
 }
 
+fn stat_breakdown_tooltip(stat: &EffectiveStat) -> String {
+    let mut lines = Vec::new();
+    for c in &stat.contributions {
+        let source = match c.source {
+            StatSource::Base => "Base",
+            StatSource::Level => "Level",
+            StatSource::Set => "Set",
+            StatSource::Equipment => "Equipment",
+            StatSource::Effect => "Effect",
+        };
+        if c.source == StatSource::Base {
+            lines.push(format!("{}: {}", source, c.value));
+        } else {
+            lines.push(format!("{} ({}): {:+}", source, c.label, c.value));
+        }
+    }
+    lines.push(format!("Total: {}", stat.total));
+    lines.join("\n")
+}
+
+fn draw_status_effect_chips(ui: &mut egui::Ui, snapshot: &GameStateSnapshot) {
+    if snapshot.status_effects.is_empty() {
+        return;
+    }
+    let mut effects = snapshot.status_effects.clone();
+    effects.sort_by(|a, b| a.name.cmp(&b.name));
+
+    ui.horizontal_wrapped(|ui| {
+        for effect in &effects {
+            let net: i32 = effect.modifiers.values().sum();
+            let color = if net > 0 {
+                egui::Color32::from_rgb(70, 140, 80)
+            } else if net < 0 {
+                egui::Color32::from_rgb(170, 70, 70)
+            } else {
+                egui::Color32::from_rgb(90, 90, 110)
+            };
+
+            let mut hover = String::new();
+            if !effect.description.trim().is_empty() {
+                hover.push_str(effect.description.trim());
+                hover.push('\n');
+            }
+            let mods = effect.modifiers_text();
+            if !mods.is_empty() {
+                hover.push_str(&mods);
+                hover.push('\n');
+            }
+            hover.push_str(&effect.duration_text(snapshot.world_time_minutes));
+
+            egui::Frame::new()
+                .fill(color)
+                .corner_radius(egui::CornerRadius::same(8))
+                .inner_margin(egui::Margin::symmetric(6, 2))
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(&effect.name).color(egui::Color32::WHITE));
+                })
+                .response
+                .on_hover_text(hover);
+        }
+    });
+    ui.add_space(6.0);
+}
+
 fn draw_currencies(ui: &mut egui::Ui, state: &UiState) {
     let Some(snapshot) = &state.snapshot else {
         ui.label("No currencies yet.");
@@ -513,13 +624,132 @@ fn draw_world(ui: &mut egui::Ui, state: &mut UiState, cmd_tx: &Sender<EngineComm
                 );
             });
             ui.checkbox(&mut w.npc_quests_enabled, "NPCs can offer quests");
+            ui.horizontal(|ui| {
+                ui.label("Offers lapse after (turns)");
+                ui.add(egui::DragValue::new(&mut w.quest_offer_turns).range(1..=20));
+            });
+            ui.label("Answer offers from the Quests tab or with a clear reply (\"I accept\", \"no thanks\").");
+        });
+    });
+
+    ui.collapsing("Faction Reputation", |ui| {
+        ui.add_enabled_ui(!state.world_locked, |ui| {
+            ui.label("Named tiers and the reputation each one starts at.");
+            draw_tier_editor(ui, &mut w.reputation_tiers, ReputationTier::defaults);
+            ui.label("Gaining reputation costs half as much with a faction's enemies; allies gain a quarter.");
+        });
+    });
+
+    ui.collapsing("Relationships", |ui| {
+        ui.add_enabled_ui(!state.world_locked, |ui| {
+            ui.label("Named tiers and the relationship value each one starts at.");
+            draw_tier_editor(ui, &mut w.relationship_tiers, ReputationTier::relationship_defaults);
+            ui.horizontal(|ui| {
+                ui.label("Decay toward 0 per day");
+                ui.add(egui::DragValue::new(&mut w.relationship_decay_per_day).range(0..=100));
+            });
+            ui.label("A party member's morale is how they feel about the player.");
+            ui.horizontal(|ui| {
+                let mut loyal = w.loyal_morale.is_some();
+                if ui.checkbox(&mut loyal, "Loyal members stay at morale").changed() {
+                    w.loyal_morale = loyal.then_some(20);
+                }
+                if let Some(morale) = w.loyal_morale.as_mut() {
+                    ui.add(egui::DragValue::new(morale).range(-100..=100));
+                }
+            });
+        });
+    });
+
+    ui.collapsing("Calendar", |ui| {
+        ui.add_enabled_ui(!state.world_locked, |ui| {
+            draw_calendar_editor(ui, &mut w.calendar);
+        });
+    });
+
+    ui.collapsing("Scheduled Events", |ui| {
+        ui.add_enabled_ui(!state.world_locked, |ui| {
+            ui.label("World events that fire at a set day and hour (day 1 is the start date).");
+            draw_schedule_editor(ui, &mut w.scheduled_events, &w.calendar);
+        });
+    });
+
+    ui.collapsing("Generation Overrides", |ui| {
+        ui.add_enabled_ui(!state.world_locked, |ui| {
+            let mut overridden = state.world.generation.is_some();
+            if ui
+                .checkbox(&mut overridden, "Use world-specific generation presets")
+                .on_hover_text("Replaces the presets from Options while this world is loaded")
+                .changed()
+            {
+                state.world.generation = overridden.then(|| state.generation.clone());
+            }
+            if let Some(generation) = state.world.generation.as_mut() {
+                ui.collapsing("Narration", |ui| {
+                    draw_sampling_settings(ui, "world_narration", &mut generation.narration);
+                });
+                ui.collapsing("Structured EVENTS extraction", |ui| {
+                    draw_sampling_settings(ui, "world_events", &mut generation.events);
+                });
+            }
+        });
+    });
+
+    ui.collapsing("Event Limits", |ui| {
+        ui.add_enabled_ui(!state.world_locked, |ui| {
+            ui.label("LLM events past these limits are rejected and reported back next turn.");
+            let limits = &mut state.world.event_limits;
+            egui::Grid::new("world_event_limits").num_columns(2).show(ui, |ui| {
+                limit_value(ui, "Max stat change per turn", &mut limits.max_stat_delta_per_turn, 5, 1..=100);
+                limit_value(ui, "Max EXP per turn", &mut limits.max_exp_per_turn, 500, 1..=1_000_000);
+                limit_value(ui, "Max levels per turn", &mut limits.max_levels_per_turn, 1, 1..=100);
+            });
+            ui.checkbox(&mut limits.allow_negative_balances, "Allow negative currency balances");
+            ui.label("Allowed currencies (empty allows any)");
+            editable_list(ui, "Allowed Currencies", &mut limits.allowed_currencies, "Add currency");
             ui.separator();
-            ui.label("World quest offer phrase:");
-            ui.label("*ding* the world is offering you a quest.");
-            ui.add_space(4.0);
-            ui.label("NPC quest offer phrase:");
-            ui.label("I hereby offer you a quest.");
+            ui.label("Event types applied without review when event review is on");
+            editable_list(
+                ui,
+                "Auto-approved Events",
+                &mut state.world.event_review.auto_approve,
+                "Add event type",
+            );
+        });
+    });
+
+    ui.collapsing("Lorebook", |ui| {
+        ui.add_enabled_ui(!state.world_locked, |ui| {
+            draw_lorebook(ui, &mut state.world.lorebook);
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!state.world_locked, egui::Button::new("📂 Import"))
+                .on_hover_text("SillyTavern, Character Card V2, NovelAI or Agnai JSON")
+                .clicked()
+            {
+                match UiState::import_lorebook_from_dialog() {
+                    Some(Ok(lorebook)) => {
+                        state.world.lorebook.scan_depth = lorebook.scan_depth;
+                        state.merge_lorebook(&lorebook, "file");
+                    }
+                    Some(Err(err)) => {
+                        state.lorebook_status = Some(format!("Import failed: {}", err));
+                    }
+                    None => {}
+                }
+            }
+            if ui
+                .button("💾 Export")
+                .on_hover_text("Save as SillyTavern world info")
+                .clicked()
+            {
+                state.export_lorebook();
+            }
         });
+        if let Some(status) = &state.lorebook_status {
+            ui.small(status);
+        }
     });
 
     ui.add_space(6.0);
@@ -540,15 +770,64 @@ fn draw_world(ui: &mut egui::Ui, state: &mut UiState, cmd_tx: &Sender<EngineComm
     }
 }
 
+fn draw_lorebook(ui: &mut egui::Ui, lorebook: &mut Lorebook) {
+    ui.label(
+        "Entries are added to the prompt when a keyword appears in the \
+         player input or recent messages.",
+    );
+    ui.horizontal(|ui| {
+        ui.label("Scan depth (messages)");
+        ui.add(egui::DragValue::new(&mut lorebook.scan_depth).speed(1).range(0..=50));
+    });
+
+    let mut remove_index: Option<usize> = None;
+    for (i, entry) in lorebook.entries.iter_mut().enumerate() {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut entry.name).hint_text("Entry name"));
+                if ui.small_button("❌").on_hover_text("Remove entry").clicked() {
+                    remove_index = Some(i);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut entry.enabled, "Enabled");
+                ui.checkbox(&mut entry.always_on, "Always on");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Priority");
+                ui.add(egui::DragValue::new(&mut entry.priority).speed(1));
+                ui.label("Token cap");
+                ui.add(egui::DragValue::new(&mut entry.token_cap).speed(1).range(0..=4096))
+                    .on_hover_text("0 = no cap");
+            });
+            ui.label("Keywords");
+            editable_list(ui, &format!("lore_keywords_{}", i), &mut entry.keywords, "Add keyword");
+            ui.label("Content");
+            ui.add(
+                egui::TextEdit::multiline(&mut entry.content)
+                    .desired_rows(3)
+                    .desired_width(f32::INFINITY),
+            );
+        });
+    }
+    if let Some(i) = remove_index {
+        lorebook.entries.remove(i);
+    }
+
+    if ui.button("➕ Add entry").clicked() {
+        lorebook.entries.push(LoreEntry::default());
+    }
+}
+
 /* =========================
    Helper for editable string lists
    ========================= */
 
 fn editable_list(ui: &mut egui::Ui, label: &str, items: &mut Vec<String>, placeholder: &str) {
     let mut remove_index: Option<usize> = None;
-    for i in 0..items.len() {
+    for (i, item) in items.iter_mut().enumerate() {
         ui.horizontal(|ui| {
-            ui.text_edit_singleline(&mut items[i]);
+            ui.text_edit_singleline(item);
             if ui.small_button("❌").clicked() {
                 remove_index = Some(i);
             }
@@ -577,26 +856,26 @@ fn editable_list(ui: &mut egui::Ui, label: &str, items: &mut Vec<String>, placeh
 
 fn editable_power_list(ui: &mut egui::Ui, items: &mut Vec<PowerEntry>, player_locked: bool) {
     let mut remove_index: Option<usize> = None;
-    for i in 0..items.len() {
+    for (i, item) in items.iter_mut().enumerate() {
         ui.group(|ui| {
             ui.horizontal(|ui| {
                 ui.add_enabled(
-                    !player_locked && !items[i].locked,
-                    egui::TextEdit::singleline(&mut items[i].name)
+                    !player_locked && !item.locked,
+                    egui::TextEdit::singleline(&mut item.name)
                         .hint_text("Power/skill name"),
                 );
 
-                let lock_label = if items[i].locked { "🔒" } else { "🔓" };
+                let lock_label = if item.locked { "🔒" } else { "🔓" };
                 if ui
                     .add_enabled(!player_locked, egui::Button::new(lock_label))
                     .on_hover_text("Lock/unlock this power")
                     .clicked()
                 {
-                    items[i].locked = !items[i].locked;
+                    item.locked = !item.locked;
                 }
 
                 if ui
-                    .add_enabled(!player_locked && !items[i].locked, egui::Button::new("❌"))
+                    .add_enabled(!player_locked && !item.locked, egui::Button::new("❌"))
                     .clicked()
                 {
                     remove_index = Some(i);
@@ -604,8 +883,8 @@ fn editable_power_list(ui: &mut egui::Ui, items: &mut Vec<PowerEntry>, player_lo
             });
 
             ui.add_enabled(
-                !player_locked && !items[i].locked,
-                egui::TextEdit::multiline(&mut items[i].description)
+                !player_locked && !item.locked,
+                egui::TextEdit::multiline(&mut item.description)
                     .hint_text("Description")
                     .desired_rows(2),
             );
@@ -664,8 +943,8 @@ fn ensure_skill_tier_names(names: &mut Vec<String>) {
         "Grandmaster",
     ];
     if names.len() < 5 {
-        for i in names.len()..5 {
-            names.push(defaults[i].to_string());
+        for default in defaults.iter().skip(names.len()) {
+            names.push(default.to_string());
         }
     } else if names.len() > 5 {
         names.truncate(5);
@@ -676,3 +955,208 @@ fn ensure_skill_tier_names(names: &mut Vec<String>) {
         }
     }
 }
+
+fn draw_calendar_editor(ui: &mut egui::Ui, calendar: &mut Calendar) {
+    ui.label("Start date");
+    ui.horizontal(|ui| {
+        ui.label("Year");
+        ui.add(egui::DragValue::new(&mut calendar.start.year));
+        ui.label("Month");
+        let months = calendar.months.len().max(1) as u32;
+        ui.add(egui::DragValue::new(&mut calendar.start.month).range(1..=months));
+        ui.label("Day");
+        ui.add(egui::DragValue::new(&mut calendar.start.day).range(1..=366));
+        ui.label("Hour");
+        ui.add(egui::DragValue::new(&mut calendar.start.hour).range(0..=23));
+    });
+    let weekday_names = calendar.weekdays.clone();
+    if !weekday_names.is_empty() {
+        let selected = weekday_names
+            .get(calendar.start.weekday as usize)
+            .cloned()
+            .unwrap_or_default();
+        egui::ComboBox::from_label("Start weekday")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (i, name) in weekday_names.iter().enumerate() {
+                    ui.selectable_value(&mut calendar.start.weekday, i as u32, name);
+                }
+            });
+    }
+
+    ui.separator();
+    ui.label("Months (name, days)");
+    let mut remove_idx: Option<usize> = None;
+    for (idx, month) in calendar.months.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut month.name);
+            ui.add(egui::DragValue::new(&mut month.days).range(1..=1000));
+            if ui.small_button("❌").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        calendar.months.remove(idx);
+    }
+    if ui.button("➕ Add Month").clicked() {
+        calendar.months.push(CalendarMonth { name: "New Month".to_string(), days: 30 });
+    }
+
+    ui.separator();
+    editable_list(ui, "Weekdays", &mut calendar.weekdays, "Add weekday");
+
+    ui.separator();
+    ui.label("Seasons (name, starting month)");
+    let mut remove_idx: Option<usize> = None;
+    for (idx, season) in calendar.seasons.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut season.name);
+            ui.add(egui::DragValue::new(&mut season.start_month).range(1..=100));
+            if ui.small_button("❌").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        calendar.seasons.remove(idx);
+    }
+    if ui.button("➕ Add Season").clicked() {
+        calendar.seasons.push(Season { name: "New Season".to_string(), start_month: 1 });
+    }
+
+    ui.separator();
+    ui.label("Day phases (name, starting hour)");
+    let mut remove_idx: Option<usize> = None;
+    for (idx, phase) in calendar.day_phases.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut phase.name);
+            ui.add(egui::DragValue::new(&mut phase.start_hour).range(0..=23));
+            if ui.small_button("❌").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        calendar.day_phases.remove(idx);
+    }
+    ui.horizontal(|ui| {
+        if ui.button("➕ Add Phase").clicked() {
+            calendar.day_phases.push(DayPhase { name: "New Phase".to_string(), start_hour: 0 });
+        }
+        if ui.button("Gregorian preset").clicked() {
+            *calendar = Calendar::gregorian();
+        }
+        if ui.button("Clear calendar").clicked() {
+            *calendar = Calendar::default();
+        }
+    });
+}
+
+fn draw_schedule_editor(ui: &mut egui::Ui, events: &mut Vec<ScheduledEvent>, calendar: &Calendar) {
+    let mut remove_idx: Option<usize> = None;
+    for (idx, event) in events.iter_mut().enumerate() {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Id");
+                ui.text_edit_singleline(&mut event.id);
+                if ui.small_button("❌").clicked() {
+                    remove_idx = Some(idx);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut event.name);
+            });
+            ui.label("Description");
+            ui.text_edit_multiline(&mut event.description);
+
+            let (mut day, mut hour) = calendar.day_and_hour(event.at_minutes);
+            let mut repeat_days = event.repeat_minutes.unwrap_or(0) / (24 * 60);
+            ui.horizontal(|ui| {
+                ui.label("Day");
+                let day_changed = ui.add(egui::DragValue::new(&mut day).range(1..=100_000)).changed();
+                ui.label("Hour");
+                let hour_changed = ui.add(egui::DragValue::new(&mut hour).range(0..=23)).changed();
+                if day_changed || hour_changed {
+                    event.at_minutes = calendar.minutes_at(day, hour);
+                }
+            });
+            ui.label(calendar.describe(event.at_minutes));
+            ui.horizontal(|ui| {
+                ui.label("Repeat every (days, 0 = once)");
+                if ui.add(egui::DragValue::new(&mut repeat_days).range(0..=10_000)).changed() {
+                    event.repeat_minutes = (repeat_days > 0).then_some(repeat_days * 24 * 60);
+                }
+            });
+            if !event.effects.is_empty() {
+                ui.label(format!("{} engine event(s) when it fires (edit in the world file)", event.effects.len()));
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        events.remove(idx);
+    }
+    if ui.button("➕ Add Scheduled Event").clicked() {
+        events.push(ScheduledEvent {
+            id: format!("world_event_{}", events.len() + 1),
+            name: "Festival".to_string(),
+            description: String::new(),
+            at_minutes: calendar.minutes_at(2, 18),
+            repeat_minutes: None,
+            effects: Vec::new(),
+        });
+    }
+}
+
+fn draw_tier_editor(
+    ui: &mut egui::Ui,
+    tiers: &mut Vec<ReputationTier>,
+    defaults: fn() -> Vec<ReputationTier>,
+) {
+    let mut remove_idx: Option<usize> = None;
+    for (idx, tier) in tiers.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut tier.name);
+            ui.add(egui::DragValue::new(&mut tier.min).speed(1).range(-10000..=10000));
+            if ui.small_button("❌").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        tiers.remove(idx);
+    }
+    ui.horizontal(|ui| {
+        if ui.button("➕ Add Tier").clicked() {
+            let min = tiers.iter().map(|t| t.min).max().unwrap_or(0) + 10;
+            tiers.push(ReputationTier { name: "New Tier".to_string(), min });
+        }
+        if ui.button("Reset to defaults").clicked() {
+            *tiers = defaults();
+        }
+    });
+}
+
+/// A grid row for an optional limit: checkbox to enable, value to edit.
+fn limit_value<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<T>,
+    default: T,
+    range: std::ops::RangeInclusive<T>,
+) {
+    let mut enabled = value.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *value = enabled.then_some(default);
+    }
+    match value.as_mut() {
+        Some(v) => {
+            ui.add(egui::DragValue::new(v).speed(1).range(range));
+        }
+        None => {
+            ui.label("no limit");
+        }
+    }
+    ui.end_row();
+}